@@ -0,0 +1,36 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use employees::database::Database;
+
+const EMPLOYEES_PER_DEPARTMENT: usize = 100;
+
+fn populate(employee_count: usize) -> Database {
+    let mut db = Database::new();
+    (0..employee_count / EMPLOYEES_PER_DEPARTMENT).for_each(|department| {
+        db.query(format!("form Department{}", department));
+    });
+    (0..employee_count).for_each(|employee| {
+        db.query(format!(
+            "assign Employee {} to Department{}",
+            employee,
+            employee / EMPLOYEES_PER_DEPARTMENT
+        ));
+    });
+    db
+}
+
+fn find_employee(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("find employee");
+    [1_000, 10_000, 100_000].iter().for_each(|employee_count| {
+        let mut db = populate(*employee_count);
+        let query = format!("find Employee {}", employee_count / 2);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(employee_count),
+            &query,
+            |bencher, query| bencher.iter(|| db.query(black_box(query.to_owned()))),
+        );
+    });
+    group.finish();
+}
+
+criterion_group!(benches, find_employee);
+criterion_main!(benches);
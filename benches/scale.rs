@@ -0,0 +1,119 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, SamplingMode};
+use employees::database::{Database, Generator, QueryResponse, SizeDistribution};
+use std::time::{Duration, Instant};
+
+const SIZES: [usize; 3] = [1_000, 100_000, 1_000_000];
+const DEPARTMENTS: usize = 100;
+
+// Uniformly spread departments, so every size lists, filters and transfers the same shape of data
+fn fixture(employee_count: usize) -> Database {
+    let mut db = Database::new();
+    db.generate(&Generator {
+        distribution: SizeDistribution::Uniform,
+        ..Generator::new(36, DEPARTMENTS, employee_count)
+    });
+    db.query("assign Bench Mover to Accounting".to_string());
+    db
+}
+
+fn expect_message(response: QueryResponse) {
+    match response {
+        QueryResponse::Message(message) => {
+            assert!(!message.starts_with("ERROR"), "{}", message);
+        }
+        _ => panic!("expected a message"),
+    }
+}
+
+fn bench(
+    criterion: &mut Criterion,
+    fixtures: &mut [(usize, Database)],
+    name: &str,
+    run: &dyn Fn(&mut Database),
+) {
+    let mut group = criterion.benchmark_group(name);
+    group.sample_size(10).warm_up_time(Duration::from_secs(1));
+    fixtures.iter_mut().for_each(|(employee_count, db)| {
+        group.bench_function(BenchmarkId::from_parameter(employee_count), |bencher| {
+            bencher.iter(|| run(db))
+        });
+    });
+    group.finish();
+}
+
+// Changes leave their history behind, so each sample runs its iterations on a fresh fixture, and
+// every sample runs as many of them, for every sample to measure the same states. A fixture per
+// iteration would be generated many thousand times at the largest size.
+fn bench_changes(criterion: &mut Criterion, name: &str, run: &dyn Fn(&mut Database)) {
+    let mut group = criterion.benchmark_group(name);
+    group
+        .sample_size(10)
+        .warm_up_time(Duration::from_secs(1))
+        .sampling_mode(SamplingMode::Flat);
+    SIZES.iter().for_each(|employee_count| {
+        group.bench_function(BenchmarkId::from_parameter(employee_count), |bencher| {
+            bencher.iter_custom(|iterations| {
+                let mut db = fixture(*employee_count);
+                let start = Instant::now();
+                (0..iterations).for_each(|_| run(&mut db));
+                start.elapsed()
+            })
+        });
+    });
+    group.finish();
+}
+
+fn scale(criterion: &mut Criterion) {
+    let mut fixtures = SIZES
+        .iter()
+        .map(|employee_count| (*employee_count, fixture(*employee_count)))
+        .collect::<Vec<(usize, Database)>>();
+
+    // The target department doesn't exist, so the filter is parsed in full and then rejected
+    // before any employee is visited
+    bench(criterion, &mut fixtures, "parse", &|db| {
+        black_box(
+            db.query(
+                "transfer employees where name contains Bob and department is not Sales to Nowhere"
+                    .to_string(),
+            ),
+        );
+    });
+    bench_changes(criterion, "assign and pull", &|db| {
+        expect_message(db.query("assign Bench Hire to Design".to_string()));
+        expect_message(db.query("pull Bench Hire from Design".to_string()));
+    });
+    bench_changes(criterion, "transfer", &|db| {
+        expect_message(db.query("transfer Bench Mover from Accounting to Design".to_string()));
+        expect_message(db.query("transfer Bench Mover from Design to Accounting".to_string()));
+    });
+    bench(criterion, &mut fixtures, "list employees", &|db| {
+        black_box(db.query("list employees".to_string()));
+    });
+    bench(criterion, &mut fixtures, "list employees as rows", &|db| {
+        black_box(db.query_rows("list employees".to_string()));
+    });
+    bench(
+        criterion,
+        &mut fixtures,
+        "list employees by department",
+        &|db| {
+            black_box(db.query("list employees by department".to_string()));
+        },
+    );
+    bench(
+        criterion,
+        &mut fixtures,
+        "list employees in department",
+        &|db| {
+            black_box(db.query("list employees in Accounting".to_string()));
+        },
+    );
+    // Matches nobody, so every employee is tested against the filter but none is moved
+    bench(criterion, &mut fixtures, "filter", &|db| {
+        black_box(db.query("transfer employees where name contains Nobody to Design".to_string()));
+    });
+}
+
+criterion_group!(benches, scale);
+criterion_main!(benches);
@@ -1,26 +1,92 @@
 //! In-memory departmental employee database with SQL-like query parsing.
 use std::collections::HashMap;
+use std::fs;
+#[cfg(feature = "export")]
+use std::fs::File;
+#[cfg(feature = "export")]
+use std::io::{self, BufWriter, Read, Write};
 
+mod aliases;
+use aliases::{Alias, Aliases};
+mod chart;
+pub use chart::{Chart, ChartFormat};
 mod commands;
-use commands::Command;
+use commands::{Command, ConflictPolicy, ErrorPolicy};
+mod date;
+pub use date::Date;
+mod diff;
 mod errors;
 use errors::QueryError;
+mod export;
+pub use export::ExportFormat;
+mod filter;
+use filter::{Field as FilterField, Filter};
+mod names;
+use names::Names;
+pub use names::{Collation, NameCase};
+mod pattern;
+mod permissions;
+use permissions::{Access, Permissions, Role};
+mod prepared;
+pub use prepared::Prepared;
+mod report;
+mod rows;
+pub use rows::{Row, Rows};
+mod script;
+use script::Statement;
+mod search;
+use search::Search;
+#[cfg(feature = "serde")]
+mod snapshot;
 mod store;
-use store::Store;
+use store::{DepartmentField, EmployeeRef, Store};
+pub use store::{Generated, Generator, SizeDistribution};
+mod value;
+pub use value::{ColumnType, Value};
 
 /// Unformatted tabular data.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Table {
     /// Data set name.
     pub title: String,
     /// Column names / header labels, in default order.
     pub headers: Vec<String>,
+    /// Column types, one per header.
+    pub types: Vec<ColumnType>,
     /// Row data, with each row containing a map of data by column name.
-    pub data: Vec<HashMap<String, String>>,
+    pub data: Vec<HashMap<String, Value>>,
+}
+
+/// Several tables answering one query, such as the sections of a report.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report {
+    /// Report name.
+    pub title: String,
+    /// Tables in the order they should be read, each with its own title.
+    pub sections: Vec<Table>,
+}
+
+/// One result among several returned together in a `QueryResponse::Composite`
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ResponsePart {
+    /// String message output
+    Message(String),
+    /// Something the query did that the user may not have expected, such as skipping rows
+    Warning(String),
+    /// Tabular data output
+    Table(Table),
+    /// Tabular data output laid out for writing row by row, returned by `Database::query_rows`
+    Rows(Rows),
 }
 
 /// Standardized query result output formats
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum QueryResponse {
     /// Stop listening for queries
     Exit,
@@ -30,6 +96,16 @@ pub enum QueryResponse {
     Message(String),
     /// Tabular data output
     Table(Table),
+    /// Tabular data output laid out for writing row by row, returned by `Database::query_rows`
+    Rows(Rows),
+    /// Several sections of tabular data output
+    Report(Report),
+    /// Several messages, warnings and tables, in the order they should be shown
+    ///
+    /// Bulk transfers and pulls return their results this way when some employees couldn't be
+    /// transferred or were left without a manager, following the table of results with a warning.
+    /// Merges always do, following the table with a message on the department they dissolved.
+    Composite(Vec<ResponsePart>),
 }
 
 /// Departmental employee database with data store and SQL-like query parsing
@@ -37,6 +113,11 @@ pub enum QueryResponse {
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Database {
     store: Store,
+    permissions: Permissions,
+    user: Option<String>,
+    aliases: Aliases,
+    // Names of the macros being run, so that a macro can't run itself
+    running_macros: Vec<String>,
 }
 
 impl Database {
@@ -54,11 +135,112 @@ impl Database {
     /// let mut db = Database::new();
     /// ```
     pub fn new() -> Self {
+        Database::with_names(NameCase::Title, Collation::Unicode)
+    }
+
+    /// Creates a new empty database with custom name handling.
+    ///
+    /// `case` decides how the names of departments and employees are displayed, and `collation`
+    /// decides which names are treated as the same department, employee or user. `Database::new`
+    /// title-cases names and compares them with Unicode case folding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Collation, Database, NameCase, QueryResponse};
+    ///
+    /// let mut db = Database::with_names(NameCase::Preserve, Collation::Unicode);
+    /// db.query("form IT".to_string());
+    ///
+    /// assert_eq!(
+    ///     QueryResponse::Message("Assigned employee \"Anil McGregor\" to IT department".to_string()),
+    ///     db.query("assign Anil McGregor to it".to_string())
+    /// );
+    /// ```
+    pub fn with_names(case: NameCase, collation: Collation) -> Self {
+        let names = Names::new(case, collation);
         Database {
-            store: Store::new(),
+            store: Store::new(names),
+            permissions: Permissions::new(names),
+            user: None,
+            aliases: Aliases::new(names),
+            running_macros: Vec::new(),
         }
     }
 
+    /// Sets the user that subsequent queries are run as.
+    ///
+    /// Access control is off until the first role is granted, at which point every query is
+    /// checked against the roles of the logged in user. The database does not authenticate
+    /// users itself, so callers should only log in users they have already verified.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, QueryResponse};
+    ///
+    /// let mut db = Database::new();
+    /// db.query("grant admin to root".to_string());
+    /// db.login("root");
+    /// db.query("grant viewer to intern".to_string());
+    /// db.query("form engineering".to_string());
+    ///
+    /// db.login("intern");
+    /// assert_eq!(
+    ///   db.query("dissolve engineering".to_string()),
+    ///   QueryResponse::Message(
+    ///     "ERROR: Permission denied: User \"intern\" requires the Admin role on department(s) \"engineering\""
+    ///     .to_string()
+    ///   )
+    /// );
+    /// ```
+    pub fn login(&mut self, user_name: &str) {
+        self.user = Some(user_name.to_owned());
+    }
+
+    /// Clears the user that queries are run as.
+    ///
+    /// Once access control is on, queries other than `"help"` and `"exit"` are denied until
+    /// another user logs in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::Database;
+    ///
+    /// let mut db = Database::new();
+    /// db.login("root");
+    /// db.logout();
+    /// ```
+    pub fn logout(&mut self) {
+        self.user = None;
+    }
+
+    /// Sets the date that subsequent changes take effect on, or `None` to use today's date.
+    ///
+    /// Every assignment, transfer and pull is recorded in the history of the employees involved,
+    /// along with the date it took effect, so that listings can be run `"as of"` a past date.
+    /// Setting the date allows changes to be backdated, or entered in advance.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, Date, QueryResponse};
+    ///
+    /// let mut db = Database::new();
+    /// db.query("form sales".to_string());
+    /// db.set_date(Date::new(2026, 3, 1));
+    /// db.query("assign bob to sales".to_string());
+    /// db.set_date(None);
+    ///
+    /// if let QueryResponse::Table(table) = db.query("list employees in sales as of 2026-02-28".to_string()) {
+    ///     assert!(table.data.is_empty());
+    /// }
+    /// ```
+    pub fn set_date(&mut self, date: Option<Date>) {
+        self.store.set_today(date);
+    }
+
     /// Seeds a database with some dummy data.
     ///
     /// Often times it's easier to develop with a pre-seeded database. To facilitate that,
@@ -91,6 +273,167 @@ impl Database {
         self.store.seed()
     }
 
+    /// Merges a reproducible synthetic organization into the database.
+    ///
+    /// Unlike `.seed()`, the size and shape of the organization are configurable, and the same
+    /// `Generator` always produces the same departments and employees. Departments that already
+    /// exist are merged into and employees that already exist are skipped, with both reported
+    /// as conflicts instead of causing a panic.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, Generator, SizeDistribution};
+    ///
+    /// let mut db = Database::new();
+    /// db.seed();
+    /// let generated = db.generate(&Generator {
+    ///     distribution: SizeDistribution::Zipf,
+    ///     attributes: true,
+    ///     ..Generator::new(42, 12, 5_000)
+    /// });
+    ///
+    /// // "Accounting", "Design", "Engineering", "Logistics" and "Sales" were merged into
+    /// assert_eq!(7, generated.departments);
+    /// assert_eq!(5_000 + 5, generated.employees + generated.conflicts.len());
+    /// ```
+    pub fn generate(&mut self, generator: &Generator) -> Generated {
+        self.store.generate(generator)
+    }
+
+    /// Writes the departments, employees and users of the database as a document that
+    /// `Database::import` can read back.
+    ///
+    /// Passing a department name exports just that department and its employees, leaving out its
+    /// parent, any reporting lines into other departments, and users. CSV documents only hold
+    /// departments and employees. Like `.seed()`, exporting is not subject to access control.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `NotFound` if the department doesn't exist, or any error raised
+    /// while serializing or writing the document.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, ExportFormat};
+    ///
+    /// let mut db = Database::new();
+    /// db.seed();
+    /// let mut document = Vec::new();
+    /// db.export(ExportFormat::Yaml, Some("Sales"), &mut document).unwrap();
+    ///
+    /// let mut imported = Database::import(ExportFormat::Yaml, &document[..]).unwrap();
+    /// assert_eq!(
+    ///     db.query("list employees in sales".to_string()),
+    ///     imported.query("list employees in sales".to_string())
+    /// );
+    /// ```
+    #[cfg(feature = "export")]
+    pub fn export<W: Write>(
+        &self,
+        format: ExportFormat,
+        department_name: Option<&str>,
+        writer: W,
+    ) -> io::Result<()> {
+        let snapshot = self
+            .snapshot_of(department_name)
+            .map_err(|error| io::Error::new(io::ErrorKind::NotFound, error.to_string()))?;
+        export::write(&snapshot, format, writer)
+    }
+
+    /// Reads a database from a document written by `Database::export`.
+    ///
+    /// No user is logged in to the imported database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error of kind `InvalidData` if the document can't be parsed, or describes a
+    /// database that couldn't have been exported, such as one with an employee reporting to
+    /// someone who doesn't exist.
+    #[cfg(feature = "export")]
+    pub fn import<R: Read>(format: ExportFormat, reader: R) -> io::Result<Database> {
+        Database::from_snapshot(&export::read(format, reader)?).map_err(export::invalid_data)
+    }
+
+    /// Draws the departments of the database as a tree, with their employees and the reporting
+    /// lines between them, as Graphviz DOT or Mermaid text.
+    ///
+    /// Like `.seed()`, drawing a chart is not subject to access control.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Chart, ChartFormat, Database};
+    ///
+    /// let mut db = Database::new();
+    /// db.seed();
+    /// let chart = db.chart(&Chart {
+    ///     collapse_above: Some(12),
+    ///     ..Chart::new(ChartFormat::Mermaid)
+    /// });
+    ///
+    /// assert!(chart.starts_with("flowchart TD"));
+    /// assert!(chart.contains("[[\"Sales\"]]"));
+    /// ```
+    pub fn chart(&self, chart: &Chart) -> String {
+        chart::render(&self.store, chart)
+    }
+
+    /// Reports the headcount of every department, the largest, smallest and empty departments,
+    /// and how many employees joined and left each department between two dates.
+    ///
+    /// Either date may be left out to leave the period open at that end. Movement is only
+    /// reported once employees have been assigned, and tells transfers apart from assignments
+    /// and pulls.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, Date};
+    ///
+    /// let mut db = Database::new();
+    /// db.seed();
+    /// let report = db.headcount_report(Date::new(2026, 1, 1), None);
+    ///
+    /// assert_eq!("Headcount by Department", report.sections[0].title);
+    /// assert_eq!("Movement since 2026-01-01", report.sections[3].title);
+    /// ```
+    pub fn headcount_report(&self, from: Option<Date>, to: Option<Date>) -> Report {
+        report::headcount(&self.store, from, to)
+    }
+
+    /// Compares the database against an earlier copy of it, such as one read back with
+    /// `Database::import`, listing what changed since.
+    ///
+    /// Departments are listed as added or removed, and employees as added, removed or
+    /// transferred, with the departments they moved from and to. Names are matched under this
+    /// database's name handling.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::Database;
+    ///
+    /// let mut earlier = Database::new();
+    /// earlier.query("form sales".to_string());
+    /// earlier.query("form design".to_string());
+    /// earlier.query("assign bob to sales".to_string());
+    /// let mut db = Database::new();
+    /// db.query("form sales".to_string());
+    /// db.query("form design".to_string());
+    /// db.query("assign bob to design".to_string());
+    ///
+    /// let changes = db.diff(&earlier);
+    /// assert_eq!(1, changes.data.len());
+    /// assert_eq!("Employee transferred", changes.data[0]["Change"]);
+    /// assert_eq!("Sales", changes.data[0]["From"]);
+    /// assert_eq!("Design", changes.data[0]["To"]);
+    /// ```
+    pub fn diff(&self, earlier: &Database) -> Table {
+        Table::from(diff::diff(&earlier.store, &self.store))
+    }
+
     /// Perform a query on the database
     ///
     /// # Examples
@@ -149,7 +492,8 @@ impl Database {
     /// );
     /// ```
     ///
-    /// Otherwise, if the query syntax is invalid in some other way, `.query()` will respond with a command-specific message:
+    /// Otherwise, if the query syntax is invalid in some other way, `.query()` will respond with a message naming the first
+    /// word that doesn't fit the command, and what could have come in its place:
     /// ```rust
     /// use employees::database::{Database, QueryResponse};
     ///
@@ -157,7 +501,7 @@ impl Database {
     /// assert_eq!(
     ///   db.query("list waffles".to_string()),
     ///   QueryResponse::Message(
-    ///     "ERROR: Invalid command syntax: Cannot list \"waffles\": list does not exist"
+    ///     "ERROR: Invalid command syntax: Unexpected \"waffles\" after \"list\": expected \"employees\", \"reports\" or \"all\""
     ///     .to_string()
     ///   )
     /// );
@@ -168,7 +512,7 @@ impl Database {
     /// Departments can be viewed and edited with the `"list"`, `"create"`, and `"delete"` query commands. Departments must be one word long.
     /// ```rust
     /// use std::collections::HashMap;
-    /// use employees::database::{Database, QueryResponse, Table};
+    /// use employees::database::{ColumnType, Database, QueryResponse, Table, Value};
     ///
     /// let mut db = Database::new();
     ///
@@ -182,9 +526,10 @@ impl Database {
     ///   QueryResponse::Table(Table {
     ///     title: "Showing all Departments".to_string(),
     ///     headers: vec!["Department".to_string()],
+    ///     types: vec![ColumnType::Text],
     ///     data: vec![{
     ///       let mut data = HashMap::new();
-    ///       data.insert("Department".to_string(), "Sales".to_string());
+    ///       data.insert("Department".to_string(), Value::Text("Sales".to_string()));
     ///       data
     ///     }]
     ///   })
@@ -200,6 +545,7 @@ impl Database {
     /// #   QueryResponse::Table(Table {
     /// #     title: "Showing all Departments".to_string(),
     /// #     headers: vec!["Department".to_string()],
+    /// #     types: vec![ColumnType::Text],
     /// #     data: vec![]
     /// #   })
     /// # );
@@ -212,7 +558,7 @@ impl Database {
     ///
     /// ```rust
     /// # use std::collections::HashMap;
-    /// # use employees::database::{Database, QueryResponse, Table};
+    /// # use employees::database::{ColumnType, Database, QueryResponse, Table, Value};
     /// #
     /// # let mut db = Database::new();
     /// #
@@ -229,25 +575,26 @@ impl Database {
     ///   QueryResponse::Table(Table {
     ///     title: "Showing all Employees".to_string(),
     ///     headers: vec!["Employee".to_string()],
+    ///     types: vec![ColumnType::Text],
     ///     data: vec![
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Employee".to_string(), "Baby Driver".to_string());
+    ///         data.insert("Employee".to_string(), Value::Text("Baby Driver".to_string()));
     ///         data
     ///       },
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Employee".to_string(), "Portal".to_string());
+    ///         data.insert("Employee".to_string(), Value::Text("Portal".to_string()));
     ///         data
     ///       },
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Employee".to_string(), "Portal".to_string());
+    ///         data.insert("Employee".to_string(), Value::Text("Portal".to_string()));
     ///         data
     ///       },
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Employee".to_string(), "The Blob".to_string());
+    ///         data.insert("Employee".to_string(), Value::Text("The Blob".to_string()));
     ///         data
     ///       },
     ///     ]
@@ -259,29 +606,30 @@ impl Database {
     ///   QueryResponse::Table(Table {
     ///     title: "Showing Employees grouped by Department".to_string(),
     ///     headers: vec!["Department".to_string(), "Employee".to_string()],
+    ///     types: vec![ColumnType::Text, ColumnType::Text],
     ///     data: vec![
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Department".to_string(), "Receiving".to_string());
-    ///         data.insert("Employee".to_string(), "Portal".to_string());
+    ///         data.insert("Department".to_string(), Value::Text("Receiving".to_string()));
+    ///         data.insert("Employee".to_string(), Value::Text("Portal".to_string()));
     ///         data
     ///       },
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Department".to_string(), "Receiving".to_string());
-    ///         data.insert("Employee".to_string(), "The Blob".to_string());
+    ///         data.insert("Department".to_string(), Value::Text("Receiving".to_string()));
+    ///         data.insert("Employee".to_string(), Value::Text("The Blob".to_string()));
     ///         data
     ///       },
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Department".to_string(), "Shipping".to_string());
-    ///         data.insert("Employee".to_string(), "Baby Driver".to_string());
+    ///         data.insert("Department".to_string(), Value::Text("Shipping".to_string()));
+    ///         data.insert("Employee".to_string(), Value::Text("Baby Driver".to_string()));
     ///         data
     ///       },
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Department".to_string(), "Shipping".to_string());
-    ///         data.insert("Employee".to_string(), "Portal".to_string());
+    ///         data.insert("Department".to_string(), Value::Text("Shipping".to_string()));
+    ///         data.insert("Employee".to_string(), Value::Text("Portal".to_string()));
     ///         data
     ///       },
     ///     ]
@@ -296,28 +644,155 @@ impl Database {
     ///   QueryResponse::Table(Table {
     ///     title: "Showing Employees assigned to the Shipping Department".to_string(),
     ///     headers: vec!["Employee".to_string()],
+    ///     types: vec![ColumnType::Text],
     ///     data: vec![
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Employee".to_string(), "Portal".to_string());
+    ///         data.insert("Employee".to_string(), Value::Text("Portal".to_string()));
     ///         data
     ///       },
     ///       {
     ///         let mut data = HashMap::new();
-    ///         data.insert("Employee".to_string(), "The Blob".to_string());
+    ///         data.insert("Employee".to_string(), Value::Text("The Blob".to_string()));
     ///         data
     ///       },
     ///     ]
     ///   })
     /// );
     /// ```
+    ///
+    /// ## Several Commands
+    ///
+    /// Commands can be separated by `;`, and `--` or `#` comment out the rest of a line. A query
+    /// with more than one command returns a `Composite` response holding the results of each in
+    /// turn, with errors numbered by the line their command starts on. Only `;` ends a command, so
+    /// a command may span several lines, and a query that reaches `exit` responds with `Exit`
+    /// once the commands before it have run.
+    /// ```rust
+    /// use employees::database::{Database, QueryResponse, ResponsePart};
+    ///
+    /// let mut db = Database::new();
+    ///
+    /// assert_eq!(
+    ///   db.query("form sales; -- first\nassign bob to marketing".to_string()),
+    ///   QueryResponse::Composite(vec![
+    ///     ResponsePart::Message("Formed \"Sales\" department".to_string()),
+    ///     ResponsePart::Message(
+    ///       "ERROR: Line 2: Query target not found: Department \"marketing\" not found".to_string()
+    ///     ),
+    ///   ])
+    /// );
+    /// ```
     pub fn query(&mut self, query_string: String) -> QueryResponse {
+        tables(self.execute(query_string))
+    }
+
+    /// Parses a query holding `?` or `$1`, `$2`... placeholders, so that it can be executed
+    /// many times with different values without being parsed again.
+    ///
+    /// Placeholders stand for whole names, values or file names, and can't stand for keywords,
+    /// formats or the dates and numbers of a command's options.
+    ///
+    /// # Errors
+    ///
+    /// Returns the syntax error `Database::query` would report, without its `ERROR:` prefix, if
+    /// the query doesn't hold exactly one valid command. Queries that run a macro, whose
+    /// statements are only parsed as it runs, and queries containing U+E000 or U+E001, the
+    /// private use characters placeholders are parsed as, are rejected too.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, QueryResponse};
+    ///
+    /// let mut db = Database::new();
+    /// db.query("form sales".to_string());
+    /// let mut assign = db.prepare("assign $2 to $1").unwrap();
+    /// ["Ann", "Bob"].iter().for_each(|name| {
+    ///     assign.execute(&["sales", name]);
+    /// });
+    ///
+    /// match db.query("list employees in sales".to_string()) {
+    ///     QueryResponse::Table(table) => assert_eq!(2, table.data.len()),
+    ///     _ => panic!(),
+    /// }
+    /// ```
+    pub fn prepare(&mut self, query_string: &str) -> Result<Prepared<'_>, String> {
+        Prepared::new(self, query_string)
+    }
+
+    /// Perform a query on the database, returning tabular data as `Rows`.
+    ///
+    /// Behaves like `.query()`, except that tabular results are returned as
+    /// `QueryResponse::Rows` instead of `QueryResponse::Table`. `Rows` hold every cell in a
+    /// single buffer, so they are cheaper to build for large results and can be written out
+    /// row by row.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, QueryResponse, Value};
+    ///
+    /// let mut db = Database::new();
+    /// db.query("form sales".to_string());
+    /// db.query("assign bob to sales".to_string());
+    ///
+    /// match db.query_rows("list employees".to_string()) {
+    ///     QueryResponse::Rows(rows) => {
+    ///         assert_eq!("Showing all Employees", rows.title());
+    ///         assert_eq!(Some(Value::Text("Bob")), rows.iter().next().unwrap().get("Employee"));
+    ///     }
+    ///     _ => panic!(),
+    /// }
+    /// ```
+    pub fn query_rows(&mut self, query_string: String) -> QueryResponse {
+        match self.execute(query_string) {
+            QueryResponse::Table(table) => QueryResponse::Rows(Rows::from(table)),
+            QueryResponse::Composite(parts) => QueryResponse::Composite(
+                parts
+                    .into_iter()
+                    .map(|part| match part {
+                        ResponsePart::Table(table) => ResponsePart::Rows(Rows::from(table)),
+                        part => part,
+                    })
+                    .collect(),
+            ),
+            response => response,
+        }
+    }
+
+    // Queries holding more than one statement answer with the results of each statement in turn
+    fn execute(&mut self, query_string: String) -> QueryResponse {
+        let mut statements = script::split(&query_string);
+        if statements.len() > 1 {
+            let ran = self.run_statements(statements, ErrorPolicy::Continue);
+            if ran.exited {
+                return QueryResponse::Exit;
+            }
+            return QueryResponse::Composite(ran.parts);
+        }
+        match statements.pop() {
+            None => QueryResponse::NoOp,
+            Some(statement) => {
+                self.execute_command(commands::parse_with(statement.text, &self.aliases))
+            }
+        }
+    }
+
+    fn execute_command(&mut self, command: Command) -> QueryResponse {
         // Steps to completed execution
         // 1. Tokenize & parse query string into command (or return err on missing command / invalid command syntax)
-        // 2. Execute command
-        // 3. Format response
-        match commands::parse(query_string) {
-            Command::EmptyCommand => QueryResponse::NoOp,
+        // 2. Check the logged in user may run the command
+        // 3. Execute command
+        // 4. Format response
+        let access = Access::required(&command, |department_name| {
+            self.store.departments().descendants(department_name)
+        });
+        if let Err(query_error) = self.permissions.authorize(self.user.as_deref(), &access) {
+            return format_query_error(query_error);
+        }
+        match command {
+            Command::Empty => QueryResponse::NoOp,
             Command::Exit => QueryResponse::Exit,
             Command::InvalidCommandErr(command) => QueryResponse::Message(
                 format!("ERROR: Invalid command \"{command}\". Please check your spelling, or type \"Help\" for the list of available commands", command = command)
@@ -325,13 +800,33 @@ impl Database {
             Command::SyntaxErr(syntax_error_message) => QueryResponse::Message(
                 format!("ERROR: Invalid command syntax: {}", syntax_error_message)
             ),
-            Command::Help => QueryResponse::Message(
-                commands::help()
+            Command::Help(None) => QueryResponse::Message(
+                commands::help_with(&self.aliases)
+            ),
+            Command::Help(Some(keyword)) => QueryResponse::Message(
+                match commands::help_for(&keyword, &self.aliases) {
+                    Some(help) => help,
+                    None => format!("ERROR: Invalid command syntax: \"{}\" is not a command or alias: type \"Help\" for the list of available commands", keyword),
+                }
             ),
             Command::ShowDepartments => self.list_departments(),
+            Command::ShowDepartmentTree => self.list_department_tree(),
+            Command::ShowDepartmentsDetailed => self.list_departments_detailed(),
+            Command::SetDepartmentField(department_name, field, value) => {
+                self.set_department_field(department_name, field, Some(value))
+            },
+            Command::ClearDepartmentField(department_name, field) => {
+                self.set_department_field(department_name, field, None)
+            },
             Command::FormDepartment(department_name) => {
                 self.create_department(department_name)
             },
+            Command::FormDepartmentUnder(department_name, parent_department_name) => {
+                self.create_department_under(department_name, parent_department_name)
+            },
+            Command::MoveDepartment(department_name, parent_department_name) => {
+                self.move_department(department_name, parent_department_name)
+            },
             Command::ListEmployees => {
                 self.list_employees()
             },
@@ -341,6 +836,19 @@ impl Database {
             Command::ListEmployeesInDepartment(department_name) => {
                 self.list_employees_in_department(department_name)
             },
+            Command::ListEmployeesUnderDepartment(department_name) => {
+                self.list_employees_under_department(department_name)
+            },
+            Command::ListEmployeesAsOf(date) => self.list_employees_as_of(date),
+            Command::ListEmployeesByDepartmentAsOf(date) => {
+                self.list_employees_by_department_as_of(date)
+            },
+            Command::ListEmployeesInDepartmentAsOf(department_name, date) => {
+                self.list_employees_in_department_as_of(department_name, date)
+            },
+            Command::ShowTimeline(employee_name, department_name) => {
+                self.show_timeline(employee_name, department_name)
+            }
             Command::AssignEmployeeToDepartment(employee_name, department_name) => self.create_employee(employee_name, department_name),
             Command::TransferEmployeeBetweenDepartments(employee_name, from_department_name, to_department_name) => {
                 self.move_employee(employee_name, from_department_name, to_department_name)
@@ -349,179 +857,1106 @@ impl Database {
                 self.delete_employee(employee_name, department_name)
             },
             Command::DissolveDepartment(department_name) => self.delete_department(department_name),
-        }
-    }
-
-    fn create_department(&mut self, department_name: String) -> QueryResponse {
-        match self.store.departments_mut().create(&department_name) {
-            Ok(department) => {
-                QueryResponse::Message(format!("Formed \"{}\" department", department))
+            Command::TransferAllBetweenDepartments(from_department_name, to_department_name, policy) => {
+                self.transfer_all_employees(from_department_name, to_department_name, policy)
+            },
+            Command::TransferMatchingToDepartment(filter, to_department_name, policy) => {
+                self.transfer_matching_employees(filter, to_department_name, policy)
+            },
+            Command::PullAllFromDepartment(department_name) => {
+                self.delete_all_employees(department_name)
+            },
+            Command::MergeDepartments(from_department_name, to_department_name, policy) => {
+                self.merge_departments(from_department_name, to_department_name, policy)
+            },
+            Command::RenameDepartment(department_name, new_name) => {
+                self.rename_department(department_name, new_name)
+            },
+            Command::RenameEmployee(employee_name, department_name, new_name) => {
+                self.rename_employee(employee_name, department_name, new_name)
+            },
+            Command::SetManager(employee_name, department_name, manager_name, manager_department_name) => {
+                self.set_manager(employee_name, department_name, Some((manager_name, manager_department_name)))
+            },
+            Command::ClearManager(employee_name, department_name) => {
+                self.set_manager(employee_name, department_name, None)
+            },
+            Command::ListReports(employee_name, department_name) => {
+                self.list_reports(employee_name, department_name, false)
+            },
+            Command::ListAllReports(employee_name, department_name) => {
+                self.list_reports(employee_name, department_name, true)
+            },
+            Command::ShowManagementChain(employee_name, department_name) => {
+                self.list_chain_of_command(employee_name, department_name)
+            },
+            Command::GrantRole(user_name, role, department_name) => {
+                self.grant_role(user_name, role, department_name)
+            }
+            Command::RevokeRole(user_name, role, department_name) => {
+                self.revoke_role(user_name, role, department_name)
+            }
+            Command::ShowUsers => self.list_users(),
+            Command::FindEmployee(employee_name) => self.find_employee(employee_name),
+            Command::Search(text) => self.search_employees(text),
+            Command::Export(format, department_name, file_name) => {
+                self.export_to_file(format, department_name, file_name)
+            }
+            Command::Diff(file_name) => self.diff_file(file_name),
+            Command::Chart(chart) => QueryResponse::Message(self.chart(&chart)),
+            Command::Run(file_name, policy) => self.run_script(file_name, policy),
+            Command::Define(alias) => self.define_alias(alias),
+            Command::Undefine(name) => self.undefine_alias(name),
+            Command::Macro(name, statements) => self.run_macro(name, statements),
+            Command::ReportHeadcount(from, to) => {
+                QueryResponse::Report(self.headcount_report(from, to))
             }
-            Err(query_error) => format_query_error(query_error),
         }
     }
 
-    fn create_employee(&mut self, employee_name: String, department_name: String) -> QueryResponse {
-        match self.store.department_mut(&department_name) {
-            Ok(department) => match department.employees_mut().create(&employee_name) {
-                Ok(employee) => QueryResponse::Message(format!(
-                    "Assigned employee \"{}\" to {} department",
-                    employee,
-                    department.name()
+    // Errors are told apart from other messages by their prefix, and numbered with the line
+    // their statement starts on
+    fn run_statements(&mut self, statements: Vec<Statement>, policy: ErrorPolicy) -> Ran {
+        let mut ran = Ran {
+            parts: Vec::new(),
+            statements: 0,
+            errors: 0,
+            exited: false,
+        };
+        let total = statements.len();
+        for statement in statements {
+            ran.statements += 1;
+            let errors = ran.errors;
+            let response = match commands::parse_with(statement.text, &self.aliases) {
+                Command::Run(..) => QueryResponse::Message(String::from(
+                    "ERROR: Invalid command syntax: Scripts cannot run other scripts",
                 )),
-                Err(query_error) => format_query_error(query_error),
-            },
-            Err(query_error) => format_query_error(query_error),
+                // The errors of a macro count as errors of the statement that ran it
+                Command::Macro(name, macro_statements) => {
+                    match self.expand_macro(name, macro_statements) {
+                        Err(response) => response,
+                        Ok(expanded) => {
+                            ran.errors += expanded.errors;
+                            ran.exited = expanded.exited;
+                            QueryResponse::Composite(expanded.parts)
+                        }
+                    }
+                }
+                command => self.execute_command(command),
+            };
+            match response {
+                QueryResponse::NoOp => (),
+                QueryResponse::Exit => ran.exited = true,
+                QueryResponse::Message(message) => match message.strip_prefix("ERROR: ") {
+                    None => ran.parts.push(ResponsePart::Message(message)),
+                    Some(error) => {
+                        ran.errors += 1;
+                        ran.parts.push(ResponsePart::Message(format!(
+                            "ERROR: Line {}: {}",
+                            statement.line, error
+                        )));
+                    }
+                },
+                QueryResponse::Table(table) => ran.parts.push(ResponsePart::Table(table)),
+                QueryResponse::Rows(rows) => ran.parts.push(ResponsePart::Rows(rows)),
+                QueryResponse::Report(report) => {
+                    ran.parts.push(ResponsePart::Message(report.title));
+                    ran.parts
+                        .extend(report.sections.into_iter().map(ResponsePart::Table));
+                }
+                QueryResponse::Composite(parts) => ran.parts.extend(parts),
+            }
+            if ran.exited {
+                break;
+            }
+            if policy == ErrorPolicy::Stop && ran.errors > errors {
+                if ran.statements < total {
+                    ran.parts.push(ResponsePart::Warning(format!(
+                        "Stopped at the error on line {}; {} statements were not run",
+                        statement.line,
+                        total - ran.statements
+                    )));
+                }
+                break;
+            }
         }
+        ran
     }
 
-    fn delete_department(&mut self, department_name: String) -> QueryResponse {
-        match self.store.departments_mut().delete(&department_name) {
-            Ok(department) => {
-                QueryResponse::Message(format!("Dissolved \"{}\" department", department))
+    fn run_script(&mut self, file_name: String, policy: ErrorPolicy) -> QueryResponse {
+        let statements = match fs::read_to_string(&file_name) {
+            Err(error) => {
+                return QueryResponse::Message(format!(
+                    "ERROR: Could not read \"{}\": {}",
+                    file_name, error
+                ))
             }
-            Err(query_error) => format_query_error(query_error),
+            Ok(script) => script::split(&script),
+        };
+        let total = statements.len();
+        let mut ran = self.run_statements(statements, policy);
+        if ran.exited {
+            return QueryResponse::Exit;
         }
+        ran.parts.push(ResponsePart::Message(format!(
+            "Ran {} of {} statements from \"{}\" with {} errors",
+            ran.statements, total, file_name, ran.errors
+        )));
+        QueryResponse::Composite(ran.parts)
     }
 
-    fn delete_employee(&mut self, employee_name: String, department_name: String) -> QueryResponse {
-        match self.store.department_mut(&department_name) {
-            Ok(department) => match department.employees_mut().delete(&employee_name) {
-                Err(query_error) => format_query_error(query_error),
-                Ok(_) => QueryResponse::Message(format!(
-                    "Pulled employee \"{}\" from department \"{}\"",
-                    employee_name, department_name
-                )),
+    fn define_alias(&mut self, alias: Alias) -> QueryResponse {
+        let message = format!(
+            "{} {} \"{}\" taking {} arguments",
+            match self.aliases.get(alias.name()) {
+                None => "Defined",
+                Some(_) => "Redefined",
             },
-            Err(query_error) => format_query_error(query_error),
-        }
+            alias.kind().name(),
+            alias.name(),
+            alias.parameters()
+        );
+        self.aliases.define(alias);
+        QueryResponse::Message(message)
     }
 
-    fn list_departments(&self) -> QueryResponse {
-        let departments = self.store.departments().list();
-        const COLUMN_NAME: &str = "Department";
-        QueryResponse::Table(Table {
-            title: String::from("Showing all Departments"),
-            headers: vec![COLUMN_NAME.to_string()],
-            data: departments
-                .iter()
-                .map(|dept_name| {
-                    let mut row = HashMap::new();
-                    row.insert(COLUMN_NAME.to_string(), dept_name.to_owned());
-                    row
-                })
-                .fold(Vec::new(), |mut rows, row| {
-                    rows.push(row);
-                    rows
-                }),
-        })
+    fn undefine_alias(&mut self, name: String) -> QueryResponse {
+        match self.aliases.remove(&name) {
+            None => format_query_error(QueryError::NotFound(format!(
+                "Alias or macro \"{}\" not found",
+                name
+            ))),
+            Some(alias) => QueryResponse::Message(format!(
+                "Removed {} \"{}\"",
+                alias.kind().name(),
+                alias.name()
+            )),
+        }
     }
 
-    fn list_employees(&self) -> QueryResponse {
-        let departments = self.store.departments().list();
-        let department_employee_groups = departments
-            .iter()
-            .map(|department_name| {
-                (
-                    department_name.to_owned(),
-                    self.store
-                        .department(department_name.as_str())
-                        .unwrap()
-                        .employees()
-                        .list(),
-                )
-            })
-            .collect::<Vec<(String, Vec<String>)>>();
-        let mut employees: Vec<String> = Vec::new();
-        for (_department_name, employee_list) in department_employee_groups {
-            for employee_name in employee_list {
-                employees.push(employee_name.to_owned());
-            }
+    // Macros stop at their first error, since later statements usually depend on earlier ones
+    fn run_macro(&mut self, name: String, statements: Vec<String>) -> QueryResponse {
+        match self.expand_macro(name, statements) {
+            Err(response) => response,
+            Ok(ran) if ran.exited => QueryResponse::Exit,
+            Ok(ran) => QueryResponse::Composite(ran.parts),
         }
-        employees.sort_by_key(|name| name.to_uppercase());
-        let employees = employees;
-        const COLUMN_NAME: &str = "Employee";
-        QueryResponse::Table(Table {
-            title: String::from("Showing all Employees"),
-            headers: vec![COLUMN_NAME.to_string()],
-            data: employees
-                .iter()
-                .map(|employee| {
-                    let mut row = HashMap::new();
-                    row.insert(COLUMN_NAME.to_string(), employee.to_owned());
-                    row
-                })
-                .fold(Vec::new(), |mut rows, row| {
-                    rows.push(row);
-                    rows
-                }),
-        })
     }
 
-    fn list_employees_by_department(&self) -> QueryResponse {
-        let departments = self.store.departments().list();
-        let department_employee_groups = departments
+    // Runs the statements of a macro, or responds with why it can't be run
+    fn expand_macro(
+        &mut self,
+        name: String,
+        statements: Vec<String>,
+    ) -> Result<Ran, QueryResponse> {
+        let names = self.aliases.names();
+        if self
+            .running_macros
             .iter()
-            .map(|department_name| {
-                (
-                    department_name.to_owned(),
-                    self.store
-                        .department(department_name.as_str())
-                        .unwrap()
-                        .employees()
-                        .list(),
-                )
-            })
-            .collect::<Vec<(String, Vec<String>)>>();
-        let mut department_employees: Vec<(String, String)> = Vec::new();
-        for (department_name, employees) in department_employee_groups {
-            for employee_name in employees {
-                department_employees.push((department_name.to_owned(), employee_name.to_owned()));
-            }
+            .any(|running| names.key(running) == names.key(&name))
+        {
+            return Err(QueryResponse::Message(format!(
+                "ERROR: Invalid command syntax: Macro \"{}\" cannot run itself",
+                name
+            )));
         }
-        let department_employees = department_employees;
-        const COLUMN_NAMES: [&str; 2] = ["Department", "Employee"];
-        QueryResponse::Table(Table {
-            title: String::from("Showing Employees grouped by Department"),
-            headers: vec![COLUMN_NAMES[0].to_string(), COLUMN_NAMES[1].to_string()],
-            data: department_employees
-                .iter()
-                .map(|(department_name, employee_name)| {
-                    let mut row = HashMap::new();
-                    row.insert(COLUMN_NAMES[0].to_string(), department_name.to_owned());
-                    row.insert(COLUMN_NAMES[1].to_string(), employee_name.to_owned());
-                    row
-                })
-                .fold(Vec::new(), |mut rows, row| {
-                    rows.push(row);
-                    rows
-                }),
-        })
+        let statements = statements
+            .into_iter()
+            .enumerate()
+            .map(|(index, text)| Statement {
+                line: index + 1,
+                text,
+            })
+            .collect();
+        self.running_macros.push(name);
+        let ran = self.run_statements(statements, ErrorPolicy::Stop);
+        self.running_macros.pop();
+        Ok(ran)
+    }
+
+    fn create_department(&mut self, department_name: String) -> QueryResponse {
+        match self.store.departments_mut().create(&department_name) {
+            Ok(department) => {
+                self.stamp_created(&department);
+                QueryResponse::Message(format!("Formed \"{}\" department", department))
+            }
+            Err(query_error) => format_query_error(query_error),
+        }
+    }
+
+    fn stamp_created(&mut self, department_name: &str) {
+        let today = self.store.today();
+        self.store
+            .department_mut(department_name)
+            .unwrap()
+            .set_created(today);
+    }
+
+    fn set_department_field(
+        &mut self,
+        department_name: String,
+        field: DepartmentField,
+        value: Option<String>,
+    ) -> QueryResponse {
+        match self.store.department_mut(&department_name) {
+            Err(query_error) => format_query_error(query_error),
+            Ok(department) => match department.set_field(field, value.as_deref()) {
+                Err(query_error) => format_query_error(query_error),
+                Ok(()) => QueryResponse::Message(match department.field(field) {
+                    None => format!(
+                        "Cleared {} of \"{}\" department",
+                        field.name().to_lowercase(),
+                        department.name()
+                    ),
+                    Some(value) => format!(
+                        "Set {} of \"{}\" department to \"{}\"",
+                        field.name().to_lowercase(),
+                        department.name(),
+                        value
+                    ),
+                }),
+            },
+        }
+    }
+
+    fn vacate_head(&mut self, employee: &EmployeeRef) -> String {
+        let employee_name = self.store.employee_name(employee);
+        match self.store.department_mut(employee.department()) {
+            Ok(department) if department.head() == Some(employee_name.as_str()) => {
+                department.set_field(DepartmentField::Head, None).unwrap();
+                format!(
+                    "; \"{}\" is no longer head of the {} department",
+                    employee_name,
+                    department.name()
+                )
+            }
+            _ => String::new(),
+        }
+    }
+
+    fn create_department_under(
+        &mut self,
+        department_name: String,
+        parent_department_name: String,
+    ) -> QueryResponse {
+        match self
+            .store
+            .departments_mut()
+            .create_under(&department_name, &parent_department_name)
+        {
+            Ok(department) => QueryResponse::Message(format!(
+                "Formed \"{}\" department under \"{}\"",
+                department,
+                self.store
+                    .department(&parent_department_name)
+                    .unwrap()
+                    .name()
+            )),
+            Err(query_error) => format_query_error(query_error),
+        }
+    }
+
+    fn move_department(
+        &mut self,
+        department_name: String,
+        parent_department_name: Option<String>,
+    ) -> QueryResponse {
+        let departments = self.store.departments_mut();
+        match departments.set_parent(&department_name, parent_department_name.as_deref()) {
+            Err(query_error) => format_query_error(query_error),
+            Ok(()) => {
+                let department = departments.department(&department_name).unwrap();
+                QueryResponse::Message(match department.parent() {
+                    None => format!(
+                        "Moved \"{}\" department to the top level",
+                        department.name()
+                    ),
+                    Some(parent) => format!(
+                        "Moved \"{}\" department under \"{}\"",
+                        department.name(),
+                        departments.department(parent).unwrap().name()
+                    ),
+                })
+            }
+        }
+    }
+
+    fn create_employee(&mut self, employee_name: String, department_name: String) -> QueryResponse {
+        match self.store.assign(&employee_name, &department_name) {
+            Ok(employee) => QueryResponse::Message(format!(
+                "Assigned employee \"{}\" to {} department",
+                employee,
+                self.store.department(&department_name).unwrap().name()
+            )),
+            Err(query_error) => format_query_error(query_error),
+        }
+    }
+
+    fn delete_department(&mut self, department_name: String) -> QueryResponse {
+        match self.dissolve_department(&department_name) {
+            Err(query_error) => format_query_error(query_error),
+            Ok((dissolved, released)) => {
+                QueryResponse::Message(format!("{}{}", dissolved, released.describe()))
+            }
+        }
+    }
+
+    fn dissolve_department(
+        &mut self,
+        department_name: &str,
+    ) -> Result<(String, ReleasedReports), QueryError> {
+        let department = self.store.department(department_name)?;
+        let removed = department
+            .employees()
+            .iter()
+            .map(|employee| {
+                EmployeeRef::new(self.store.names(), department.name(), employee.name())
+            })
+            .collect::<Vec<EmployeeRef>>();
+        self.store.check_leaving(&removed)?;
+        let released_reports = self.store.release_reports(&removed);
+        let released = self.released_reports(&released_reports);
+        let departments = self.store.departments();
+        let children = departments.children(department_name);
+        let parent = departments
+            .department(department_name)
+            .ok()
+            .and_then(|department| department.parent())
+            .map(|parent| departments.department(parent).unwrap().name().to_owned());
+        let department = self.store.delete_department(department_name)?;
+        if children.is_empty() {
+            return Ok((format!("Dissolved \"{}\" department", department), released));
+        }
+        Ok((
+            format!(
+                "Dissolved \"{}\" department; moved its sub-departments {} {}",
+                department,
+                children
+                    .iter()
+                    .map(|child| format!("\"{}\"", child))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                match parent {
+                    None => String::from("to the top level"),
+                    Some(parent) => format!("under \"{}\"", parent),
+                }
+            ),
+            released,
+        ))
+    }
+
+    fn delete_employee(&mut self, employee_name: String, department_name: String) -> QueryResponse {
+        let employee = match self.store.locate(&employee_name, Some(&department_name)) {
+            Err(query_error) => match query_error {
+                QueryError::NotFound(_) if self.store.department(&department_name).is_ok() => {
+                    return format_query_error(QueryError::NotFound(format!(
+                        "Employee \"{}\" could not be found",
+                        employee_name
+                    )))
+                }
+                _ => return format_query_error(query_error),
+            },
+            Ok(employee) => employee,
+        };
+        if let Err(query_error) = self.store.check_leaving(std::slice::from_ref(&employee)) {
+            return format_query_error(query_error);
+        }
+        let vacated_head = self.vacate_head(&employee);
+        let released_reports = self.store.release_reports(std::slice::from_ref(&employee));
+        match self.store.delete_employee(&employee) {
+            Err(query_error) => format_query_error(query_error),
+            Ok(_) => QueryResponse::Message(format!(
+                "Pulled employee \"{}\" from department \"{}\"{}{}",
+                employee_name,
+                department_name,
+                vacated_head,
+                self.released_reports(&released_reports).describe()
+            )),
+        }
+    }
+
+    fn released_reports(&self, released: &[(EmployeeRef, Option<EmployeeRef>)]) -> ReleasedReports {
+        let mut reassigned: Vec<(String, Vec<String>)> = Vec::new();
+        let mut orphaned: Vec<String> = Vec::new();
+        released.iter().for_each(|(report, new_manager)| {
+            let report_name = format!("\"{}\"", self.store.employee_name(report));
+            match new_manager {
+                None => orphaned.push(report_name),
+                Some(new_manager) => {
+                    let manager_name = self.store.employee_name(new_manager);
+                    match reassigned
+                        .iter_mut()
+                        .find(|(name, _)| *name == manager_name)
+                    {
+                        Some((_, reports)) => reports.push(report_name),
+                        None => reassigned.push((manager_name, vec![report_name])),
+                    }
+                }
+            }
+        });
+        ReleasedReports {
+            reassigned,
+            orphaned,
+        }
+    }
+
+    fn set_manager(
+        &mut self,
+        employee_name: String,
+        department_name: Option<String>,
+        manager_name: Option<(String, Option<String>)>,
+    ) -> QueryResponse {
+        let employee = match self
+            .store
+            .locate(&employee_name, department_name.as_deref())
+        {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(employee) => employee,
+        };
+        let manager = match manager_name {
+            None => match self.store.employee(&employee).unwrap().manager() {
+                None => {
+                    return format_query_error(QueryError::NotFound(format!(
+                        "Employee \"{}\" has no manager",
+                        self.store.employee_name(&employee)
+                    )))
+                }
+                Some(_) => None,
+            },
+            Some((manager_name, manager_department_name)) => match self
+                .store
+                .locate(&manager_name, manager_department_name.as_deref())
+            {
+                Err(query_error) => return format_query_error(query_error),
+                Ok(manager) => Some(manager),
+            },
+        };
+        match self.store.set_manager(&employee, manager.clone()) {
+            Err(query_error) => format_query_error(query_error),
+            Ok(()) => QueryResponse::Message(match manager {
+                None => format!(
+                    "Cleared manager of \"{}\" in {}",
+                    self.store.employee_name(&employee),
+                    self.store.department_name(&employee)
+                ),
+                Some(manager) => format!(
+                    "Set manager of \"{}\" in {} to \"{}\" in {}",
+                    self.store.employee_name(&employee),
+                    self.store.department_name(&employee),
+                    self.store.employee_name(&manager),
+                    self.store.department_name(&manager)
+                ),
+            }),
+        }
+    }
+
+    fn list_reports(
+        &self,
+        employee_name: String,
+        department_name: Option<String>,
+        transitive: bool,
+    ) -> QueryResponse {
+        let manager = match self
+            .store
+            .locate(&employee_name, department_name.as_deref())
+        {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(manager) => manager,
+        };
+        let reports = if transitive {
+            self.store.all_reports(&manager)
+        } else {
+            self.store.reports(&manager)
+        };
+        const COLUMN_NAMES: [&str; 3] = ["Employee", "Department", "Manager"];
+        let mut rows = Rows::new(
+            format!(
+                "Showing {} reports of {}",
+                if transitive { "all" } else { "direct" },
+                self.store.employee_name(&manager)
+            ),
+            COLUMN_NAMES[..if transitive { 3 } else { 2 }]
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        );
+        reports.iter().for_each(|report| {
+            let employee_name = self.store.employee_name(report);
+            let department_name = self.store.department_name(report);
+            if transitive {
+                let manager_name = self
+                    .store
+                    .employee(report)
+                    .unwrap()
+                    .manager()
+                    .map(|report_manager| self.store.employee_name(report_manager));
+                rows.push_values(&[
+                    Value::Text(&employee_name),
+                    Value::Text(&department_name),
+                    manager_name.as_deref().map_or(Value::Null, Value::Text),
+                ]);
+            } else {
+                rows.push(&[&employee_name, &department_name]);
+            }
+        });
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_chain_of_command(
+        &self,
+        employee_name: String,
+        department_name: Option<String>,
+    ) -> QueryResponse {
+        let employee = match self
+            .store
+            .locate(&employee_name, department_name.as_deref())
+        {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(employee) => employee,
+        };
+        let mut chain = vec![employee.clone()];
+        chain.extend(self.store.chain_of_command(&employee));
+        let mut rows = Rows::with_types(
+            format!(
+                "Showing chain of command for {}",
+                self.store.employee_name(&employee)
+            ),
+            vec![
+                String::from("Level"),
+                String::from("Employee"),
+                String::from("Department"),
+            ],
+            vec![ColumnType::Integer, ColumnType::Text, ColumnType::Text],
+        );
+        chain.iter().enumerate().for_each(|(level, link)| {
+            rows.push_values(&[
+                Value::Integer(level as i64),
+                Value::Text(&self.store.employee_name(link)),
+                Value::Text(&self.store.department_name(link)),
+            ]);
+        });
+        QueryResponse::Rows(rows)
+    }
+
+    fn rename_department(&mut self, department_name: String, new_name: String) -> QueryResponse {
+        let old_name = match self.store.department(&department_name) {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(department) => department.name().to_owned(),
+        };
+        match self.store.rename_department(&department_name, &new_name) {
+            Err(query_error) => format_query_error(query_error),
+            Ok(name) => {
+                self.permissions.rename_department(&old_name, &name);
+                QueryResponse::Message(format!(
+                    "Renamed \"{}\" department to \"{}\"",
+                    old_name, name
+                ))
+            }
+        }
+    }
+
+    fn rename_employee(
+        &mut self,
+        employee_name: String,
+        department_name: Option<String>,
+        new_name: String,
+    ) -> QueryResponse {
+        let employee = match self
+            .store
+            .locate(&employee_name, department_name.as_deref())
+        {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(employee) => employee,
+        };
+        let old_name = self.store.employee_name(&employee);
+        match self.store.rename_employee(&employee, &new_name) {
+            Err(query_error) => format_query_error(query_error),
+            Ok(name) => QueryResponse::Message(format!(
+                "Renamed employee \"{}\" in {} to \"{}\"",
+                old_name,
+                self.store.department_name(&employee),
+                name
+            )),
+        }
+    }
+
+    fn grant_role(
+        &mut self,
+        user_name: String,
+        role: Role,
+        department_name: Option<String>,
+    ) -> QueryResponse {
+        let department_display_name = match department_name {
+            None => None,
+            Some(department_name) => match self.store.department(&department_name) {
+                Err(query_error) => return format_query_error(query_error),
+                Ok(department) => Some(department.name().to_owned()),
+            },
+        };
+        match self
+            .permissions
+            .grant(&user_name, role, department_display_name.as_deref())
+        {
+            Err(query_error) => format_query_error(query_error),
+            Ok(()) => QueryResponse::Message(match department_display_name {
+                None => format!("Granted role \"{}\" to user \"{}\"", role.name(), user_name),
+                Some(department_name) => format!(
+                    "Granted role \"{}\" to user \"{}\" on department \"{}\"",
+                    role.name(),
+                    user_name,
+                    department_name
+                ),
+            }),
+        }
+    }
+
+    fn revoke_role(
+        &mut self,
+        user_name: String,
+        role: Role,
+        department_name: Option<String>,
+    ) -> QueryResponse {
+        match self
+            .permissions
+            .revoke(&user_name, role, department_name.as_deref())
+        {
+            Err(query_error) => format_query_error(query_error),
+            Ok(()) => QueryResponse::Message(match department_name {
+                None => format!(
+                    "Revoked role \"{}\" from user \"{}\"",
+                    role.name(),
+                    user_name
+                ),
+                Some(department_name) => format!(
+                    "Revoked role \"{}\" from user \"{}\" on department \"{}\"",
+                    role.name(),
+                    user_name,
+                    department_name
+                ),
+            }),
+        }
+    }
+
+    fn list_users(&self) -> QueryResponse {
+        let mut rows = Rows::new(
+            String::from("Showing all Users"),
+            vec![
+                String::from("User"),
+                String::from("Role"),
+                String::from("Department"),
+            ],
+        );
+        self.permissions.users().for_each(|user| {
+            user.grants().iter().for_each(|grant| {
+                rows.push(&[
+                    user.name(),
+                    grant.role().name(),
+                    grant.department().unwrap_or("All"),
+                ]);
+            });
+        });
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_departments(&self) -> QueryResponse {
+        let mut rows = Rows::new(
+            String::from("Showing all Departments"),
+            vec![String::from("Department")],
+        );
+        self.store
+            .departments()
+            .list()
+            .iter()
+            .for_each(|department_name| rows.push(&[department_name]));
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_departments_detailed(&self) -> QueryResponse {
+        const FIELDS: [DepartmentField; 5] = [
+            DepartmentField::Head,
+            DepartmentField::CostCenter,
+            DepartmentField::Location,
+            DepartmentField::Description,
+            DepartmentField::Created,
+        ];
+        let mut headers = vec![String::from("Department")];
+        headers.extend(FIELDS.iter().map(|field| field.name().to_string()));
+        headers.push(String::from("Headcount"));
+        let mut types = vec![ColumnType::Text; 5];
+        types.extend(&[ColumnType::Date, ColumnType::Integer]);
+        let mut rows = Rows::with_types(
+            String::from("Showing all Departments in detail"),
+            headers,
+            types,
+        );
+        self.store.departments().iter().for_each(|department| {
+            let fields = FIELDS
+                .iter()
+                .map(|field| department.field(*field))
+                .collect::<Vec<Option<String>>>();
+            let mut row = vec![Value::Text(department.name())];
+            row.extend(FIELDS.iter().zip(&fields).map(|(field, value)| {
+                match (field, value.as_deref()) {
+                    (DepartmentField::Created, _) => {
+                        department.created().map_or(Value::Null, Value::Date)
+                    }
+                    (_, None) => Value::Null,
+                    (_, Some(value)) => Value::Text(value),
+                }
+            }));
+            row.push(Value::Integer(department.employees().iter().count() as i64));
+            rows.push_values(&row);
+        });
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_department_tree(&self) -> QueryResponse {
+        let departments = self.store.departments();
+        let mut rows = Rows::new(
+            String::from("Showing Department Tree"),
+            vec![String::from("Department"), String::from("Parent")],
+        );
+        departments
+            .tree()
+            .iter()
+            .for_each(|(depth, department_name)| {
+                let parent = departments
+                    .department(department_name)
+                    .unwrap()
+                    .parent()
+                    .map(|parent| departments.department(parent).unwrap().name());
+                rows.push_values(&[
+                    Value::Text(&format!("{}{}", "  ".repeat(*depth), department_name)),
+                    parent.map_or(Value::Null, Value::Text),
+                ]);
+            });
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_employees(&self) -> QueryResponse {
+        let mut employees = self
+            .store
+            .departments()
+            .iter()
+            .flat_map(|department| department.employees().keyed())
+            .map(|(key, employee)| (key, employee.name()))
+            .collect::<Vec<(&str, &str)>>();
+        employees.sort_by(|left, right| left.0.cmp(right.0));
+        let mut rows = Rows::new(
+            String::from("Showing all Employees"),
+            vec![String::from("Employee")],
+        );
+        employees
+            .iter()
+            .for_each(|(_, employee_name)| rows.push(&[employee_name]));
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_employees_by_department(&self) -> QueryResponse {
+        let mut rows = Rows::new(
+            String::from("Showing Employees grouped by Department"),
+            vec![String::from("Department"), String::from("Employee")],
+        );
+        self.store.departments().iter().for_each(|department| {
+            department
+                .employees()
+                .iter()
+                .for_each(|employee| rows.push(&[department.name(), employee.name()]));
+        });
+        QueryResponse::Rows(rows)
     }
 
     fn list_employees_in_department(&self, department_name: String) -> QueryResponse {
         match self.store.department(&department_name) {
             Ok(department) => {
-                let employees = department.employees().list();
-                const COLUMN_NAME: &str = "Employee";
-                QueryResponse::Table(Table {
-                    title: format!(
+                let mut rows = Rows::new(
+                    format!(
                         "Showing Employees assigned to the {} Department",
                         department.name()
                     ),
-                    headers: vec![COLUMN_NAME.to_string()],
-                    data: employees
-                        .iter()
-                        .map(|employee_name| {
-                            let mut row = HashMap::new();
-                            row.insert(COLUMN_NAME.to_string(), employee_name.to_owned());
-                            row
-                        })
-                        .fold(Vec::new(), |mut rows, row| {
-                            rows.push(row);
-                            rows
-                        }),
-                })
+                    vec![String::from("Employee")],
+                );
+                department
+                    .employees()
+                    .iter()
+                    .for_each(|employee| rows.push(&[employee.name()]));
+                QueryResponse::Rows(rows)
+            }
+            Err(query_error) => format_query_error(query_error),
+        }
+    }
+
+    fn list_employees_as_of(&self, date: Date) -> QueryResponse {
+        let mut rows = Rows::new(
+            format!("Showing all Employees as of {}", date),
+            vec![String::from("Employee")],
+        );
+        self.store
+            .history()
+            .as_of(date)
+            .for_each(|membership| rows.push(&[membership.employee()]));
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_employees_by_department_as_of(&self, date: Date) -> QueryResponse {
+        let names = self.store.names();
+        let mut memberships = self
+            .store
+            .history()
+            .as_of(date)
+            .map(|membership| (names.key(membership.department()), membership))
+            .collect::<Vec<_>>();
+        // The history is ordered by employee, so a stable sort keeps employees in order
+        memberships.sort_by(|left, right| left.0.cmp(&right.0));
+        let mut rows = Rows::new(
+            format!("Showing Employees grouped by Department as of {}", date),
+            vec![String::from("Department"), String::from("Employee")],
+        );
+        memberships.iter().for_each(|(_, membership)| {
+            rows.push(&[membership.department(), membership.employee()])
+        });
+        QueryResponse::Rows(rows)
+    }
+
+    fn list_employees_in_department_as_of(
+        &self,
+        department_name: String,
+        date: Date,
+    ) -> QueryResponse {
+        let names = self.store.names();
+        let key = names.key(&department_name);
+        let history = self.store.history();
+        // Departments that have since been dissolved can still be listed from their history
+        let name = match self.store.department(&department_name) {
+            Ok(department) => department.name(),
+            Err(query_error) => match history
+                .iter()
+                .find(|membership| names.key(membership.department()) == key)
+            {
+                None => return format_query_error(query_error),
+                Some(membership) => membership.department(),
+            },
+        };
+        let mut rows = Rows::new(
+            format!(
+                "Showing Employees assigned to the {} Department as of {}",
+                name, date
+            ),
+            vec![String::from("Employee")],
+        );
+        history
+            .as_of(date)
+            .filter(|membership| names.key(membership.department()) == key)
+            .for_each(|membership| rows.push(&[membership.employee()]));
+        QueryResponse::Rows(rows)
+    }
+
+    fn show_timeline(
+        &self,
+        employee_name: String,
+        department_name: Option<String>,
+    ) -> QueryResponse {
+        let timeline = self
+            .store
+            .history()
+            .timeline(&employee_name, department_name.as_deref());
+        if timeline.is_empty() {
+            return format_query_error(QueryError::NotFound(match department_name {
+                None => format!(
+                    "Employee \"{}\" has never been assigned to a department",
+                    employee_name
+                ),
+                Some(department_name) => format!(
+                    "Employee \"{}\" has never been assigned to department \"{}\"",
+                    employee_name, department_name
+                ),
+            }));
+        }
+        let mut rows = Rows::with_types(
+            format!("Showing Timeline of Employee \"{}\"", employee_name),
+            vec![
+                String::from("Employee"),
+                String::from("Department"),
+                String::from("From"),
+                String::from("To"),
+            ],
+            vec![
+                ColumnType::Text,
+                ColumnType::Text,
+                ColumnType::Date,
+                ColumnType::Date,
+            ],
+        );
+        timeline.iter().for_each(|membership| {
+            rows.push_values(&[
+                Value::Text(membership.employee()),
+                Value::Text(membership.department()),
+                Value::Date(membership.from()),
+                membership.to().map_or(Value::Null, Value::Date),
+            ])
+        });
+        QueryResponse::Rows(rows)
+    }
+
+    fn find_employee(&self, employee_name: String) -> QueryResponse {
+        let mut rows = Rows::new(
+            format!("Showing Departments of Employee \"{}\"", employee_name),
+            vec![String::from("Employee"), String::from("Department")],
+        );
+        self.store.find(&employee_name).iter().for_each(|employee| {
+            rows.push(&[
+                &self.store.employee_name(employee),
+                &self.store.department_name(employee),
+            ]);
+        });
+        QueryResponse::Rows(rows)
+    }
+
+    fn search_employees(&self, text: String) -> QueryResponse {
+        let search = Search::new(self.store.names(), &text);
+        let mut results = self
+            .store
+            .departments()
+            .iter()
+            .flat_map(|department| {
+                department
+                    .employees()
+                    .iter()
+                    .map(move |employee| (employee.name(), department.name()))
+            })
+            .map(|(employee_name, department_name)| {
+                let score = search.score(&[employee_name, department_name]);
+                (score, employee_name, department_name)
+            })
+            .filter(|(score, _, _)| *score > 0)
+            .collect::<Vec<(u32, &str, &str)>>();
+        results.sort_by(|left, right| right.0.cmp(&left.0).then_with(|| left.1.cmp(right.1)));
+        let mut rows = Rows::with_types(
+            format!("Showing Search Results for \"{}\"", text),
+            vec![
+                String::from("Employee"),
+                String::from("Department"),
+                String::from("Score"),
+            ],
+            vec![ColumnType::Text, ColumnType::Text, ColumnType::Integer],
+        );
+        results
+            .iter()
+            .for_each(|(score, employee_name, department_name)| {
+                rows.push_values(&[
+                    Value::Text(employee_name),
+                    Value::Text(department_name),
+                    Value::Integer(i64::from(*score)),
+                ]);
+            });
+        QueryResponse::Rows(rows)
+    }
+
+    #[cfg(feature = "export")]
+    fn export_to_file(
+        &self,
+        format: ExportFormat,
+        department_name: Option<String>,
+        file_name: String,
+    ) -> QueryResponse {
+        let snapshot = match self.snapshot_of(department_name.as_deref()) {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(snapshot) => snapshot,
+        };
+        match File::create(&file_name)
+            .and_then(|file| export::write(&snapshot, format, BufWriter::new(file)))
+        {
+            Err(error) => QueryResponse::Message(format!(
+                "ERROR: Could not export to \"{}\": {}",
+                file_name, error
+            )),
+            Ok(()) => QueryResponse::Message(match department_name {
+                None => format!(
+                    "Exported {} departments to \"{}\" as {}",
+                    snapshot.departments.len(),
+                    file_name,
+                    format.name()
+                ),
+                Some(_) => format!(
+                    "Exported \"{}\" department to \"{}\" as {}",
+                    snapshot.departments[0].name,
+                    file_name,
+                    format.name()
+                ),
+            }),
+        }
+    }
+
+    #[cfg(not(feature = "export"))]
+    fn export_to_file(
+        &self,
+        format: ExportFormat,
+        _department_name: Option<String>,
+        _file_name: String,
+    ) -> QueryResponse {
+        QueryResponse::Message(format!(
+            "ERROR: Exporting to {} requires the \"export\" feature",
+            format.name()
+        ))
+    }
+
+    // The format of the file is told by its extension
+    #[cfg(feature = "export")]
+    fn diff_file(&self, file_name: String) -> QueryResponse {
+        let format = match ExportFormat::from_file_name(&file_name) {
+            None => {
+                return QueryResponse::Message(format!(
+                    "ERROR: Could not tell the format of \"{}\" from its extension",
+                    file_name
+                ))
+            }
+            Some(format) => format,
+        };
+        match File::open(&file_name).and_then(|file| Database::import(format, file)) {
+            Err(error) => QueryResponse::Message(format!(
+                "ERROR: Could not read \"{}\": {}",
+                file_name, error
+            )),
+            Ok(earlier) => {
+                let mut rows = diff::diff(&earlier.store, &self.store);
+                rows.set_title(format!("Showing Changes since \"{}\"", file_name));
+                QueryResponse::Rows(rows)
+            }
+        }
+    }
+
+    #[cfg(not(feature = "export"))]
+    fn diff_file(&self, _file_name: String) -> QueryResponse {
+        QueryResponse::Message(String::from(
+            "ERROR: Reading files to compare requires the \"export\" feature",
+        ))
+    }
+
+    fn list_employees_under_department(&self, department_name: String) -> QueryResponse {
+        match self.store.department(&department_name) {
+            Ok(department) => {
+                let mut rows = Rows::new(
+                    format!(
+                        "Showing Employees assigned to the {} Department and its Sub-departments",
+                        department.name()
+                    ),
+                    vec![String::from("Department"), String::from("Employee")],
+                );
+                std::iter::once(department)
+                    .chain(
+                        self.store
+                            .departments()
+                            .descendants(&department_name)
+                            .iter()
+                            .map(|department_name| self.store.department(department_name).unwrap()),
+                    )
+                    .for_each(|department| {
+                        department
+                            .employees()
+                            .iter()
+                            .for_each(|employee| rows.push(&[department.name(), employee.name()]));
+                    });
+                QueryResponse::Rows(rows)
             }
             Err(query_error) => format_query_error(query_error),
         }
@@ -533,7 +1968,8 @@ impl Database {
         from_department_name: String,
         to_department_name: String,
     ) -> QueryResponse {
-        if from_department_name.to_uppercase() == to_department_name.to_uppercase() {
+        let names = self.store.names();
+        if names.key(&from_department_name) == names.key(&to_department_name) {
             return QueryResponse::Message(String::from(
                 "ERROR: Cannot move employee from department to same department",
             ));
@@ -549,40 +1985,462 @@ impl Database {
             }
         };
         let to_department_display_name;
-        let employee_display_name;
-        match self.store.department_mut(&to_department_name) {
+        match self.store.department(&to_department_name) {
             Err(query_error) => return format_query_error(query_error),
             Ok(to_department) => {
                 to_department_display_name = to_department.name().to_owned();
-                if to_department
-                    .employees_mut()
-                    .create(&employee_name)
-                    .is_err()
-                {
+                if to_department.employees().employee(&employee_name).is_ok() {
                     return format_query_error(QueryError::Conflict(format!(
                         "Employee \"{}\" already exists in department \"{}\"",
                         employee_name, to_department_name
                     )));
                 }
-                employee_display_name = to_department
-                    .employees()
-                    .employee(&employee_name)
-                    .unwrap()
-                    .name()
-                    .to_owned();
             }
         };
-        self.store
-            .department_mut(&from_department_name)
-            .unwrap()
-            .employees_mut()
-            .delete(&employee_name)
-            .unwrap();
+        let employee = EmployeeRef::new(self.store.names(), &from_department_name, &employee_name);
+        if let Err(query_error) = self.store.check_leaving(std::slice::from_ref(&employee)) {
+            return format_query_error(query_error);
+        }
+        let (employee_display_name, vacated_head) =
+            self.transfer_employee(&employee, &to_department_name, None);
         QueryResponse::Message(format!(
-            "Transferred employee \"{}\" from \"{}\" to \"{}\" department",
-            employee_display_name, from_department_display_name, to_department_display_name
+            "Transferred employee \"{}\" from \"{}\" to \"{}\" department{}",
+            employee_display_name,
+            from_department_display_name,
+            to_department_display_name,
+            vacated_head
         ))
     }
+
+    fn transfer_employee(
+        &mut self,
+        employee: &EmployeeRef,
+        to_department_name: &str,
+        new_name: Option<&str>,
+    ) -> (String, String) {
+        let vacated_head = self.vacate_head(employee);
+        let mut moved = self.store.take_employee(employee).unwrap();
+        if let Some(new_name) = new_name {
+            moved.rename(self.store.names(), new_name);
+        }
+        let employee_display_name = self
+            .store
+            .insert_employee(to_department_name, moved)
+            .unwrap();
+        self.store.retarget_reports(
+            employee,
+            &EmployeeRef::new(
+                self.store.names(),
+                to_department_name,
+                &employee_display_name,
+            ),
+        );
+        (employee_display_name, vacated_head)
+    }
+
+    fn transfer_employees(
+        &mut self,
+        employees: Vec<EmployeeRef>,
+        to_department_name: &str,
+        policy: ConflictPolicy,
+        title: String,
+        taken_result: &str,
+        left_behind: &str,
+    ) -> Result<(Rows, Vec<ResponsePart>), QueryError> {
+        let names = self.store.names();
+        let to_department = self.store.department(to_department_name)?;
+        let to_department_display_name = to_department.name().to_owned();
+        let to_department_key = names.key(to_department_name);
+        let leaving = employees
+            .iter()
+            .filter(|employee| employee.department() != to_department_key)
+            .cloned()
+            .collect::<Vec<EmployeeRef>>();
+        self.store.check_leaving(&leaving)?;
+        if policy == ConflictPolicy::Fail {
+            let mut taken = to_department.employees().list();
+            let mut conflicts = Vec::new();
+            employees
+                .iter()
+                .filter(|employee| {
+                    **employee
+                        != EmployeeRef::new(
+                            self.store.names(),
+                            to_department_name,
+                            employee.employee(),
+                        )
+                })
+                .for_each(|employee| {
+                    let employee_name = self.store.employee_name(employee);
+                    if taken
+                        .iter()
+                        .any(|name| names.key(name) == names.key(&employee_name))
+                    {
+                        conflicts.push(format!("\"{}\"", employee_name));
+                    }
+                    taken.push(employee_name);
+                });
+            if !conflicts.is_empty() {
+                return Err(QueryError::Conflict(format!(
+                    "Employees {} already exist in department \"{}\"; no employees were transferred",
+                    conflicts.join(", "),
+                    to_department_display_name
+                )));
+            }
+        }
+        let mut rows = Rows::new(
+            title,
+            vec![
+                String::from("Employee"),
+                String::from("From"),
+                String::from("To"),
+                String::from("Result"),
+            ],
+        );
+        let mut not_transferred = 0;
+        employees.iter().for_each(|employee| {
+            let employee_name = self.store.employee_name(employee);
+            let from_department_name = self.store.department_name(employee);
+            let taken = |database: &Database, name: &str| {
+                database
+                    .store
+                    .department(to_department_name)
+                    .unwrap()
+                    .employees()
+                    .employee(name)
+                    .is_ok()
+            };
+            let result = if *employee
+                == EmployeeRef::new(self.store.names(), to_department_name, &employee_name)
+            {
+                String::from("Skipped: already in department")
+            } else if !taken(self, &employee_name) {
+                let (_, vacated_head) = self.transfer_employee(employee, to_department_name, None);
+                format!("Transferred{}", vacated_head)
+            } else if policy == ConflictPolicy::Rename {
+                let new_name = (2..)
+                    .map(|suffix| format!("{} {}", employee_name, suffix))
+                    .find(|new_name| !taken(self, new_name))
+                    .unwrap();
+                let (new_name, vacated_head) =
+                    self.transfer_employee(employee, to_department_name, Some(&new_name));
+                format!("Transferred as \"{}\"{}", new_name, vacated_head)
+            } else {
+                not_transferred += 1;
+                String::from(taken_result)
+            };
+            rows.push(&[
+                &employee_name,
+                &from_department_name,
+                &to_department_display_name,
+                &result,
+            ]);
+        });
+        let warnings = match not_transferred {
+            0 => vec![],
+            _ => vec![ResponsePart::Warning(format!(
+                "{} of {} employees were {}, as their names are already taken in department \"{}\"",
+                not_transferred,
+                employees.len(),
+                left_behind,
+                to_department_display_name
+            ))],
+        };
+        Ok((rows, warnings))
+    }
+
+    fn transfer_all_employees(
+        &mut self,
+        from_department_name: String,
+        to_department_name: String,
+        policy: ConflictPolicy,
+    ) -> QueryResponse {
+        let (employees, title) = match self.store.department(&from_department_name) {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(from_department) => (
+                department_employee_refs(
+                    self.store.names(),
+                    from_department.name(),
+                    from_department.employees().list(),
+                ),
+                format!(
+                    "Transferring all Employees from the {} Department",
+                    from_department.name()
+                ),
+            ),
+        };
+        match self.transfer_employees(
+            employees,
+            &to_department_name,
+            policy,
+            title,
+            "Skipped: name already taken",
+            "not transferred",
+        ) {
+            Err(query_error) => format_query_error(query_error),
+            Ok((rows, warnings)) => with_warnings(rows, warnings),
+        }
+    }
+
+    fn transfer_matching_employees(
+        &mut self,
+        filter: Filter,
+        to_department_name: String,
+        policy: ConflictPolicy,
+    ) -> QueryResponse {
+        // A missing target is reported before every employee is tested against the filter
+        if let Err(query_error) = self.store.department(&to_department_name) {
+            return format_query_error(query_error);
+        }
+        let employees = self
+            .store
+            .departments()
+            .iter()
+            .flat_map(|department| {
+                department_employee_refs(
+                    self.store.names(),
+                    department.name(),
+                    department.employees().list(),
+                )
+            })
+            .filter(|employee| {
+                filter.matches(|field| match field {
+                    FilterField::Name => Some(self.store.employee_name(employee)),
+                    FilterField::Department => Some(self.store.department_name(employee)),
+                    FilterField::Manager => self
+                        .store
+                        .employee(employee)
+                        .and_then(|found| found.manager())
+                        .map(|manager| self.store.employee_name(manager)),
+                })
+            })
+            .collect::<Vec<EmployeeRef>>();
+        let title = String::from("Transferring matching Employees");
+        match self.transfer_employees(
+            employees,
+            &to_department_name,
+            policy,
+            title,
+            "Skipped: name already taken",
+            "not transferred",
+        ) {
+            Err(query_error) => format_query_error(query_error),
+            Ok((rows, warnings)) => with_warnings(rows, warnings),
+        }
+    }
+
+    fn delete_all_employees(&mut self, department_name: String) -> QueryResponse {
+        let department_display_name;
+        let employees = match self.store.department(&department_name) {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(department) => {
+                department_display_name = department.name().to_owned();
+                department_employee_refs(
+                    self.store.names(),
+                    department.name(),
+                    department.employees().list(),
+                )
+            }
+        };
+        if let Err(query_error) = self.store.check_leaving(&employees) {
+            return format_query_error(query_error);
+        }
+        let released_reports = self.store.release_reports(&employees);
+        let released = self.released_reports(&released_reports);
+        let mut rows = Rows::new(
+            format!(
+                "Pulling all Employees from the {} Department",
+                department_display_name
+            ),
+            vec![
+                String::from("Employee"),
+                String::from("From"),
+                String::from("Result"),
+            ],
+        );
+        employees.iter().for_each(|employee| {
+            let employee_name = self.store.employee_name(employee);
+            let vacated_head = self.vacate_head(employee);
+            self.store.delete_employee(employee).unwrap();
+            rows.push(&[
+                &employee_name,
+                &department_display_name,
+                &format!("Pulled{}", vacated_head),
+            ]);
+        });
+        with_warnings(rows, released.parts())
+    }
+
+    fn merge_departments(
+        &mut self,
+        from_department_name: String,
+        to_department_name: String,
+        policy: ConflictPolicy,
+    ) -> QueryResponse {
+        let names = self.store.names();
+        if names.key(&from_department_name) == names.key(&to_department_name) {
+            return format_query_error(QueryError::Conflict(String::from(
+                "Cannot merge department into itself",
+            )));
+        }
+        let (employees, title) = match (
+            self.store.department(&from_department_name),
+            self.store.department(&to_department_name),
+        ) {
+            (Err(query_error), _) | (_, Err(query_error)) => {
+                return format_query_error(query_error)
+            }
+            (Ok(from_department), Ok(to_department)) => (
+                department_employee_refs(
+                    self.store.names(),
+                    from_department.name(),
+                    from_department.employees().list(),
+                ),
+                format!(
+                    "Merging the {} Department into the {} Department",
+                    from_department.name(),
+                    to_department.name()
+                ),
+            ),
+        };
+        // Employees left behind are pulled when the department is dissolved
+        let (rows, warnings) = match self.transfer_employees(
+            employees,
+            &to_department_name,
+            policy,
+            title,
+            "Pulled: name already taken",
+            "pulled",
+        ) {
+            Err(query_error) => return format_query_error(query_error),
+            Ok(transferred) => transferred,
+        };
+        let mut parts = vec![ResponsePart::Rows(rows)];
+        // Sub-departments follow the employees into the other department, unless it is one of
+        // them or below one, in which case dissolving moves them up a level
+        let departments = self.store.departments_mut();
+        let moved = departments
+            .children(&from_department_name)
+            .into_iter()
+            .filter(|child| {
+                departments
+                    .set_parent(child, Some(&to_department_name))
+                    .is_ok()
+            })
+            .map(|child| format!("\"{}\"", child))
+            .collect::<Vec<String>>();
+        if !moved.is_empty() {
+            parts.push(ResponsePart::Message(format!(
+                "Moved sub-departments {} under \"{}\"",
+                moved.join(", "),
+                departments.department(&to_department_name).unwrap().name()
+            )));
+        }
+        match self.dissolve_department(&from_department_name) {
+            Err(query_error) => parts.push(ResponsePart::Warning(query_error.to_string())),
+            Ok((dissolved, released)) => {
+                parts.push(ResponsePart::Message(dissolved));
+                parts.extend(released.parts());
+            }
+        }
+        parts.extend(warnings);
+        QueryResponse::Composite(parts)
+    }
+}
+
+// Who took over the reports of removed employees, and who was left without a manager
+struct ReleasedReports {
+    reassigned: Vec<(String, Vec<String>)>,
+    orphaned: Vec<String>,
+}
+
+impl ReleasedReports {
+    // Appended to the message of a command that removed one employee or department
+    fn describe(&self) -> String {
+        let mut description = String::new();
+        self.reassigned.iter().for_each(|(manager_name, reports)| {
+            description.push_str(&format!(
+                "; reassigned reports {} to \"{}\"",
+                reports.join(", "),
+                manager_name
+            ));
+        });
+        if !self.orphaned.is_empty() {
+            description.push_str(&format!(
+                "; WARNING: {} no longer have a manager",
+                self.orphaned.join(", ")
+            ));
+        }
+        description
+    }
+
+    // Returned alongside the table of a command that removed many employees
+    fn parts(&self) -> Vec<ResponsePart> {
+        let mut parts = self
+            .reassigned
+            .iter()
+            .map(|(manager_name, reports)| {
+                ResponsePart::Message(format!(
+                    "Reassigned reports {} to \"{}\"",
+                    reports.join(", "),
+                    manager_name
+                ))
+            })
+            .collect::<Vec<ResponsePart>>();
+        if !self.orphaned.is_empty() {
+            parts.push(ResponsePart::Warning(format!(
+                "{} no longer have a manager",
+                self.orphaned.join(", ")
+            )));
+        }
+        parts
+    }
+}
+
+struct Ran {
+    parts: Vec<ResponsePart>,
+    statements: usize,
+    errors: usize,
+    // Whether a statement asked to exit, which ends the session and not only the statements
+    exited: bool,
+}
+
+// Tabular results are returned as tables by everything but `Database::query_rows`
+fn tables(response: QueryResponse) -> QueryResponse {
+    match response {
+        QueryResponse::Rows(rows) => QueryResponse::Table(Table::from(rows)),
+        QueryResponse::Composite(parts) => QueryResponse::Composite(
+            parts
+                .into_iter()
+                .map(|part| match part {
+                    ResponsePart::Rows(rows) => ResponsePart::Table(Table::from(rows)),
+                    part => part,
+                })
+                .collect(),
+        ),
+        response => response,
+    }
+}
+
+// Results without messages or warnings stay a single table, as they were before composite responses
+fn with_warnings(rows: Rows, warnings: Vec<ResponsePart>) -> QueryResponse {
+    if warnings.is_empty() {
+        return QueryResponse::Rows(rows);
+    }
+    let mut parts = vec![ResponsePart::Rows(rows)];
+    parts.extend(warnings);
+    QueryResponse::Composite(parts)
+}
+
+fn department_employee_refs(
+    names: Names,
+    department_name: &str,
+    employee_names: Vec<String>,
+) -> Vec<EmployeeRef> {
+    employee_names
+        .iter()
+        .map(|employee_name| EmployeeRef::new(names, department_name, employee_name))
+        .collect()
 }
 
 fn format_query_error(error: QueryError) -> QueryResponse {
@@ -592,6 +2450,9 @@ fn format_query_error(error: QueryError) -> QueryResponse {
         QueryError::NotFound(message) => {
             Message(format!("ERROR: Query target not found: {}", message))
         }
+        QueryError::PermissionDenied(message) => {
+            Message(format!("ERROR: Permission denied: {}", message))
+        }
     }
 }
 
@@ -643,5 +2504,13 @@ mod tests {
                 format_query_error(QueryError::NotFound("I found it!".to_string()))
             )
         }
+
+        #[test]
+        fn permission_denied() {
+            assert_eq!(
+                QueryResponse::Message("ERROR: Permission denied: Go away".to_string()),
+                format_query_error(QueryError::PermissionDenied("Go away".to_string()))
+            )
+        }
     }
 }
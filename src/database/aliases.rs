@@ -0,0 +1,254 @@
+use super::names::Names;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AliasKind {
+    Alias,
+    Macro,
+}
+
+impl AliasKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            AliasKind::Alias => "alias",
+            AliasKind::Macro => "macro",
+        }
+    }
+}
+
+// A user-defined command standing for one query, or for several statements run in turn, whose
+// "$1", "$2"... tokens are replaced by the arguments it's called with
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alias {
+    name: String,
+    kind: AliasKind,
+    statements: Vec<String>,
+}
+
+impl Alias {
+    pub fn new(name: &str, kind: AliasKind, statements: Vec<String>) -> Self {
+        Alias {
+            name: name.to_owned(),
+            kind,
+            statements,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn kind(&self) -> AliasKind {
+        self.kind
+    }
+
+    pub fn statements(&self) -> &[String] {
+        &self.statements
+    }
+
+    pub fn parameters(&self) -> usize {
+        self.statements
+            .iter()
+            .flat_map(|statement| statement.split_whitespace())
+            .filter_map(parameter)
+            .map(|(number, _)| number)
+            .max()
+            .unwrap_or(0)
+    }
+
+    // Arguments are separated by commas, since names may hold several words
+    pub fn expand(&self, arguments: &str) -> Result<Vec<String>, String> {
+        let arguments = match arguments.trim() {
+            "" => Vec::new(),
+            arguments => arguments.split(',').map(str::trim).collect::<Vec<&str>>(),
+        };
+        if arguments.len() != self.parameters() {
+            return Err(format!(
+                "{} \"{}\" expects {} arguments separated by commas, but {} were given",
+                self.usage_name(),
+                self.name,
+                self.parameters(),
+                arguments.len()
+            ));
+        }
+        if let Some(position) = arguments.iter().position(|argument| argument.is_empty()) {
+            return Err(format!(
+                "Argument {} of {} \"{}\" must not be empty",
+                position + 1,
+                self.kind.name(),
+                self.name
+            ));
+        }
+        Ok(self
+            .statements
+            .iter()
+            .map(|statement| {
+                statement
+                    .split_whitespace()
+                    .map(|token| match parameter(token) {
+                        Some((number, rest)) => format!("{}{}", arguments[number - 1], rest),
+                        None => token.to_owned(),
+                    })
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            })
+            .collect())
+    }
+
+    fn usage_name(&self) -> &'static str {
+        match self.kind {
+            AliasKind::Alias => "Alias",
+            AliasKind::Macro => "Macro",
+        }
+    }
+}
+
+// Parameters may be followed by punctuation, such as the comma in "hire $1, Sales"
+fn parameter(token: &str) -> Option<(usize, &str)> {
+    let digits = token.strip_prefix('$')?;
+    let end = digits
+        .find(|character: char| !character.is_ascii_digit())
+        .unwrap_or(digits.len());
+    digits[..end]
+        .parse::<usize>()
+        .ok()
+        .filter(|number| *number > 0)
+        .map(|number| (number, &digits[end..]))
+}
+
+// Aliases are named by users like departments and employees are, so their names are compared
+// under the same collation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Aliases {
+    aliases: BTreeMap<String, Alias>,
+    names: Names,
+}
+
+impl Aliases {
+    pub fn new(names: Names) -> Self {
+        Aliases {
+            aliases: BTreeMap::new(),
+            names,
+        }
+    }
+
+    pub fn names(&self) -> Names {
+        self.names
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Alias> {
+        self.aliases.get(&self.names.key(name))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Alias> {
+        self.aliases.values()
+    }
+
+    pub fn len(&self) -> usize {
+        self.aliases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    // Returns the alias that was replaced, if any
+    pub fn define(&mut self, alias: Alias) -> Option<Alias> {
+        self.aliases.insert(self.names.key(&alias.name), alias)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Alias> {
+        self.aliases.remove(&self.names.key(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn onboard() -> Alias {
+        Alias::new(
+            "onboard",
+            AliasKind::Macro,
+            vec![
+                String::from("assign $1 to $2"),
+                String::from("set manager of $1 in $2 to $3"),
+            ],
+        )
+    }
+
+    mod alias {
+        use super::*;
+
+        #[test]
+        fn counts_the_highest_parameter() {
+            assert_eq!(3, onboard().parameters());
+            assert_eq!(
+                0,
+                Alias::new(
+                    "staff",
+                    AliasKind::Alias,
+                    vec![String::from("list employees")]
+                )
+                .parameters()
+            );
+        }
+
+        #[test]
+        fn replaces_parameters_with_arguments() {
+            assert_eq!(
+                Ok(vec![
+                    String::from("assign Bob Smith to Sales"),
+                    String::from("set manager of Bob Smith in Sales to Ann"),
+                ]),
+                onboard().expand(" Bob Smith , Sales,Ann")
+            );
+        }
+
+        #[test]
+        fn checks_the_number_of_arguments() {
+            assert_eq!(
+                Err(String::from(
+                    "Macro \"onboard\" expects 3 arguments separated by commas, but 1 were given"
+                )),
+                onboard().expand("Bob Smith Sales Ann")
+            );
+            assert_eq!(
+                Err(String::from(
+                    "Argument 2 of macro \"onboard\" must not be empty"
+                )),
+                onboard().expand("Bob, , Ann")
+            );
+        }
+    }
+
+    mod aliases {
+        use super::*;
+
+        #[test]
+        fn finds_aliases_ignoring_case() {
+            let mut aliases = Aliases::new(Names::default());
+            aliases.define(onboard());
+
+            assert_eq!(Some(&onboard()), aliases.get("ONBOARD"));
+            assert_eq!(Some(onboard()), aliases.define(onboard()));
+            assert_eq!(Some(onboard()), aliases.remove("Onboard"));
+            assert!(aliases.is_empty());
+        }
+
+        #[test]
+        fn compares_names_under_their_collation() {
+            let mut aliases = Aliases::new(Names::default());
+            aliases.define(Alias::new(
+                "straße",
+                AliasKind::Alias,
+                vec![String::from("list employees")],
+            ));
+
+            assert!(aliases.get("STRASSE").is_some());
+            assert!(aliases.remove("Strasse").is_some());
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use super::store::Store;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// A text format that an org chart can be drawn in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartFormat {
+    /// A Graphviz DOT digraph, for rendering with `dot`
+    Dot,
+    /// A Mermaid flowchart, for rendering in Markdown
+    Mermaid,
+}
+
+impl ChartFormat {
+    /// Parses the name of a format, ignoring case, or returns `None` if it isn't supported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::ChartFormat;
+    ///
+    /// assert_eq!(Some(ChartFormat::Dot), ChartFormat::parse("graphviz"));
+    /// assert_eq!(None, ChartFormat::parse("svg"));
+    /// ```
+    pub fn parse(format_name: &str) -> Option<ChartFormat> {
+        match format_name.to_uppercase().as_str() {
+            "DOT" | "GRAPHVIZ" => Some(ChartFormat::Dot),
+            "MERMAID" => Some(ChartFormat::Mermaid),
+            _ => None,
+        }
+    }
+
+    /// The name of the format, as written in queries.
+    pub fn name(self) -> &'static str {
+        match self {
+            ChartFormat::Dot => "DOT",
+            ChartFormat::Mermaid => "Mermaid",
+        }
+    }
+}
+
+/// Options for drawing an org chart with `Database::chart`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    /// The text format to draw the chart in
+    pub format: ChartFormat,
+    /// How many levels of sub-departments to draw below the top level departments, or `None` to
+    /// draw every level
+    pub max_depth: Option<usize>,
+    /// Departments with more employees than this are drawn with a single node counting their
+    /// employees, or `None` to always draw every employee
+    pub collapse_above: Option<usize>,
+}
+
+impl Chart {
+    /// Creates options for drawing every department and employee.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Chart, ChartFormat};
+    ///
+    /// let chart = Chart {
+    ///     max_depth: Some(1),
+    ///     ..Chart::new(ChartFormat::Mermaid)
+    /// };
+    /// ```
+    pub fn new(format: ChartFormat) -> Self {
+        Chart {
+            format,
+            max_depth: None,
+            collapse_above: None,
+        }
+    }
+}
+
+enum Node<'a> {
+    Department(&'a str),
+    Employee(&'a str),
+    Count(usize),
+}
+
+enum Edge {
+    Contains,
+    ReportsTo,
+}
+
+// Nodes are numbered in drawing order, so the same store always draws the same chart
+pub fn render(store: &Store, chart: &Chart) -> String {
+    let names = store.names();
+    let departments = store.departments();
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+    let mut department_ids = HashMap::new();
+    // Employees by department key and employee key, for drawing reporting lines
+    let mut employee_ids = HashMap::new();
+    departments
+        .tree()
+        .iter()
+        .filter(|(depth, _)| chart.max_depth.is_none_or(|max_depth| *depth <= max_depth))
+        .for_each(|(_, department_name)| {
+            let department = departments.department(department_name).unwrap();
+            let department_key = names.key(department.name());
+            let id = nodes.len();
+            nodes.push(Node::Department(department.name()));
+            if let Some(parent) = department
+                .parent()
+                .and_then(|parent| department_ids.get(parent))
+            {
+                edges.push((*parent, id, Edge::Contains));
+            }
+            let headcount = department.employees().iter().count();
+            if chart
+                .collapse_above
+                .is_some_and(|collapse_above| headcount > collapse_above)
+            {
+                nodes.push(Node::Count(headcount));
+                edges.push((id, id + 1, Edge::Contains));
+            } else {
+                department.employees().keyed().for_each(|(key, employee)| {
+                    employee_ids.insert((department_key.clone(), key.to_owned()), nodes.len());
+                    edges.push((id, nodes.len(), Edge::Contains));
+                    nodes.push(Node::Employee(employee.name()));
+                });
+            }
+            department_ids.insert(department_key, id);
+        });
+    // Reporting lines are only drawn between employees that are both on the chart
+    departments.iter().for_each(|department| {
+        let department_key = names.key(department.name());
+        department.employees().keyed().for_each(|(key, employee)| {
+            let report = employee_ids.get(&(department_key.clone(), key.to_owned()));
+            let manager = employee.manager().and_then(|manager| {
+                employee_ids.get(&(
+                    manager.department().to_owned(),
+                    manager.employee().to_owned(),
+                ))
+            });
+            if let (Some(report), Some(manager)) = (report, manager) {
+                edges.push((*manager, *report, Edge::ReportsTo));
+            }
+        });
+    });
+
+    let mut text = String::new();
+    match chart.format {
+        ChartFormat::Dot => {
+            text.push_str("digraph org {\n    node [shape=box];\n");
+            nodes.iter().enumerate().for_each(|(id, node)| {
+                let (label, shape) = match node {
+                    Node::Department(name) => (name.to_string(), "folder"),
+                    Node::Employee(name) => (name.to_string(), "box"),
+                    Node::Count(count) => (employees(*count), "ellipse"),
+                };
+                writeln!(
+                    text,
+                    "    n{} [label=\"{}\", shape={}];",
+                    id,
+                    label.replace('\\', "\\\\").replace('"', "\\\""),
+                    shape
+                )
+                .unwrap();
+            });
+            edges.iter().for_each(|(from, to, edge)| match edge {
+                Edge::Contains => writeln!(text, "    n{} -> n{};", from, to).unwrap(),
+                Edge::ReportsTo => {
+                    writeln!(text, "    n{} -> n{} [style=dashed];", from, to).unwrap()
+                }
+            });
+            text.push('}');
+        }
+        ChartFormat::Mermaid => {
+            text.push_str("flowchart TD\n");
+            nodes.iter().enumerate().for_each(|(id, node)| {
+                let (label, open, close) = match node {
+                    Node::Department(name) => (name.to_string(), "[[", "]]"),
+                    Node::Employee(name) => (name.to_string(), "[", "]"),
+                    Node::Count(count) => (employees(*count), "(", ")"),
+                };
+                writeln!(
+                    text,
+                    "    n{}{}\"{}\"{}",
+                    id,
+                    open,
+                    label.replace('"', "#quot;"),
+                    close
+                )
+                .unwrap();
+            });
+            edges.iter().for_each(|(from, to, edge)| {
+                let arrow = match edge {
+                    Edge::Contains => "-->",
+                    Edge::ReportsTo => "-.->",
+                };
+                writeln!(text, "    n{} {} n{}", from, arrow, to).unwrap();
+            });
+            text.pop();
+        }
+    }
+    text
+}
+
+fn employees(count: usize) -> String {
+    match count {
+        1 => String::from("1 employee"),
+        _ => format!("{} employees", count),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn organization() -> Database {
+        let mut db = Database::new();
+        [
+            "Form Sales",
+            "Form Europe under Sales",
+            "Form Paris under Europe",
+            "Assign Bob to Sales",
+            "Assign Alice to Europe",
+            "Assign Carol to Europe",
+            "Assign Dan to Paris",
+            "Set manager of Alice to Bob",
+        ]
+        .iter()
+        .for_each(|query| {
+            db.query(query.to_string());
+        });
+        db
+    }
+
+    mod fn_render {
+        use super::*;
+
+        #[test]
+        fn draws_departments_employees_and_reporting_lines_in_dot() {
+            let db = organization();
+
+            assert_eq!(
+                "digraph org {\
+                \n    node [shape=box];\
+                \n    n0 [label=\"Sales\", shape=folder];\
+                \n    n1 [label=\"Bob\", shape=box];\
+                \n    n2 [label=\"Europe\", shape=folder];\
+                \n    n3 [label=\"Alice\", shape=box];\
+                \n    n4 [label=\"Carol\", shape=box];\
+                \n    n5 [label=\"Paris\", shape=folder];\
+                \n    n6 [label=\"Dan\", shape=box];\
+                \n    n0 -> n1;\
+                \n    n0 -> n2;\
+                \n    n2 -> n3;\
+                \n    n2 -> n4;\
+                \n    n2 -> n5;\
+                \n    n5 -> n6;\
+                \n    n1 -> n3 [style=dashed];\
+                \n}",
+                render(&db.store, &Chart::new(ChartFormat::Dot))
+            );
+        }
+
+        #[test]
+        fn limits_depth_and_collapses_large_departments_in_mermaid() {
+            let db = organization();
+            let chart = Chart {
+                max_depth: Some(1),
+                collapse_above: Some(1),
+                ..Chart::new(ChartFormat::Mermaid)
+            };
+
+            assert_eq!(
+                "flowchart TD\
+                \n    n0[[\"Sales\"]]\
+                \n    n1[\"Bob\"]\
+                \n    n2[[\"Europe\"]]\
+                \n    n3(\"2 employees\")\
+                \n    n0 --> n1\
+                \n    n0 --> n2\
+                \n    n2 --> n3",
+                render(&db.store, &chart)
+            );
+        }
+
+        #[test]
+        fn escapes_quotes_in_labels() {
+            let mut db = Database::new();
+            db.query("Form Sales".to_string());
+            db.query("Assign \"Bo\" to Sales".to_string());
+
+            assert!(render(&db.store, &Chart::new(ChartFormat::Dot))
+                .contains("n1 [label=\"\\\"bo\\\"\", shape=box];"));
+            assert!(render(&db.store, &Chart::new(ChartFormat::Mermaid))
+                .contains("n1[\"#quot;bo#quot;\"]"));
+        }
+    }
+}
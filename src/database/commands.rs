@@ -1,271 +1,1371 @@
-#[derive(Debug, PartialEq)]
+use super::aliases::{Alias, AliasKind, Aliases};
+use super::chart::{Chart, ChartFormat};
+use super::date::Date;
+use super::export::ExportFormat;
+use super::filter::Filter;
+use super::pattern::{Captures, Mismatch, Pattern};
+use super::permissions::Role;
+use super::store::DepartmentField;
+
+#[cfg(test)]
+mod legacy;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    Fail,
+    Skip,
+    Rename,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorPolicy {
+    Continue,
+    Stop,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Command {
-    EmptyCommand,
+    Empty,
     InvalidCommandErr(String),
     SyntaxErr(String),
     Exit,
-    Help,
+    Help(Option<String>),
     ShowDepartments,
     ListEmployees,
     ListEmployeesByDepartment,
     ListEmployeesInDepartment(String),
+    ListEmployeesUnderDepartment(String),
+    ListEmployeesAsOf(Date),
+    ListEmployeesByDepartmentAsOf(Date),
+    ListEmployeesInDepartmentAsOf(String, Date),
+    ShowTimeline(String, Option<String>),
     FormDepartment(String),
+    FormDepartmentUnder(String, String),
+    MoveDepartment(String, Option<String>),
+    ShowDepartmentTree,
+    ShowDepartmentsDetailed,
+    SetDepartmentField(String, DepartmentField, String),
+    ClearDepartmentField(String, DepartmentField),
     AssignEmployeeToDepartment(String, String),
     TransferEmployeeBetweenDepartments(String, String, String),
     PullEmployeeFromDepartment(String, String),
+    TransferAllBetweenDepartments(String, String, ConflictPolicy),
+    TransferMatchingToDepartment(Filter, String, ConflictPolicy),
+    PullAllFromDepartment(String),
+    MergeDepartments(String, String, ConflictPolicy),
     DissolveDepartment(String),
+    RenameDepartment(String, String),
+    RenameEmployee(String, Option<String>, String),
+    SetManager(String, Option<String>, String, Option<String>),
+    ClearManager(String, Option<String>),
+    ListReports(String, Option<String>),
+    ListAllReports(String, Option<String>),
+    ShowManagementChain(String, Option<String>),
+    GrantRole(String, Role, Option<String>),
+    RevokeRole(String, Role, Option<String>),
+    ShowUsers,
+    FindEmployee(String),
+    Search(String),
+    Export(ExportFormat, Option<String>, String),
+    Diff(String),
+    Chart(Chart),
+    ReportHeadcount(Option<Date>, Option<Date>),
+    Run(String, ErrorPolicy),
+    Define(Alias),
+    Undefine(String),
+    Macro(String, Vec<String>),
 }
 
-pub fn parse(command_string: String) -> Command {
-    let mut tokens = command_string.split_whitespace();
-    let command_prefix = tokens.next();
-    match command_prefix {
-        None => Command::EmptyCommand,
-        Some(command_string) => match command_string.to_uppercase().as_str() {
-            "EXIT" | "QUIT" | "LEAVE" | "BYE" => Command::Exit,
-            "HELP" | "HALP" => Command::Help,
-            "SHOW" => parse_show(tokens),
-            "LIST" => parse_list(tokens),
-            "ASSIGN" => parse_assign(tokens),
-            "TRANSFER" => parse_transfer(tokens),
-            "PULL" => parse_pull(tokens),
-            "FORM" => parse_form(tokens),
-            "DISSOLVE" => parse_dissolve(tokens),
-            _ => Command::InvalidCommandErr(String::from(command_string)),
-        },
+// Whether a name or value in a command is one word, as department and user names are, or any
+// number of words
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Text {
+    Word,
+    Words,
+}
+
+impl Command {
+    // Copies the command with every name, value and file name rewritten, for binding parameters
+    pub fn map_text<F: Fn(&str, Text) -> String>(&self, map: &F) -> Command {
+        use Command::*;
+        let word = |text: &str| map(text, Text::Word);
+        let words = |text: &str| map(text, Text::Words);
+        let word_option = |text: &Option<String>| text.as_deref().map(word);
+        match self {
+            InvalidCommandErr(command) => InvalidCommandErr(words(command)),
+            SyntaxErr(message) => SyntaxErr(words(message)),
+            ListEmployeesInDepartment(department) => ListEmployeesInDepartment(word(department)),
+            ListEmployeesUnderDepartment(department) => {
+                ListEmployeesUnderDepartment(word(department))
+            }
+            ListEmployeesInDepartmentAsOf(department, date) => {
+                ListEmployeesInDepartmentAsOf(word(department), *date)
+            }
+            ShowTimeline(employee, department) => {
+                ShowTimeline(words(employee), word_option(department))
+            }
+            FormDepartment(department) => FormDepartment(word(department)),
+            FormDepartmentUnder(department, parent) => {
+                FormDepartmentUnder(word(department), word(parent))
+            }
+            MoveDepartment(department, parent) => {
+                MoveDepartment(word(department), word_option(parent))
+            }
+            SetDepartmentField(department, field, value) => {
+                SetDepartmentField(word(department), *field, words(value))
+            }
+            ClearDepartmentField(department, field) => {
+                ClearDepartmentField(word(department), *field)
+            }
+            AssignEmployeeToDepartment(employee, department) => {
+                AssignEmployeeToDepartment(words(employee), word(department))
+            }
+            TransferEmployeeBetweenDepartments(employee, from, to) => {
+                TransferEmployeeBetweenDepartments(words(employee), word(from), word(to))
+            }
+            PullEmployeeFromDepartment(employee, department) => {
+                PullEmployeeFromDepartment(words(employee), word(department))
+            }
+            TransferAllBetweenDepartments(from, to, policy) => {
+                TransferAllBetweenDepartments(word(from), word(to), *policy)
+            }
+            TransferMatchingToDepartment(filter, to, policy) => {
+                TransferMatchingToDepartment(filter.map_values(&words), word(to), *policy)
+            }
+            PullAllFromDepartment(department) => PullAllFromDepartment(word(department)),
+            MergeDepartments(from, to, policy) => MergeDepartments(word(from), word(to), *policy),
+            DissolveDepartment(department) => DissolveDepartment(word(department)),
+            RenameDepartment(department, name) => RenameDepartment(word(department), word(name)),
+            RenameEmployee(employee, department, name) => {
+                RenameEmployee(words(employee), word_option(department), words(name))
+            }
+            SetManager(employee, department, manager, manager_department) => SetManager(
+                words(employee),
+                word_option(department),
+                words(manager),
+                word_option(manager_department),
+            ),
+            ClearManager(employee, department) => {
+                ClearManager(words(employee), word_option(department))
+            }
+            ListReports(employee, department) => {
+                ListReports(words(employee), word_option(department))
+            }
+            ListAllReports(employee, department) => {
+                ListAllReports(words(employee), word_option(department))
+            }
+            ShowManagementChain(employee, department) => {
+                ShowManagementChain(words(employee), word_option(department))
+            }
+            GrantRole(user, role, department) => {
+                GrantRole(word(user), *role, word_option(department))
+            }
+            RevokeRole(user, role, department) => {
+                RevokeRole(word(user), *role, word_option(department))
+            }
+            FindEmployee(employee) => FindEmployee(words(employee)),
+            Search(text) => Search(words(text)),
+            Export(format, department, file) => {
+                Export(*format, word_option(department), words(file))
+            }
+            Diff(file) => Diff(words(file)),
+            Run(file, policy) => Run(words(file), *policy),
+            command => command.clone(),
+        }
     }
 }
 
-pub fn help() -> String {
-    const HELP_MESSAGE: &str = ("\
-        \nAvailable Operations:\
-        \n- \"Help\" - display available operations (this help message)\
-        \n- \"Exit\" - quits the program\
-        \n- \"Show departments\" - list departments alphabetically\
-        \n- \"List employees\" - list employees alphabetically\
-        \n- \"List employees by department\" - list employees and their dept, grouped by dept. alphabetically, sorted alphabetically\
-        \n- \"List employees in {department}\" - list employees in a dept, sorted alphabetically\
-        \n- \"Form {department}\" - create new department\
-        \n- \"Assign {employee} to {department}\" - create new employee under department\
-        \n- \"Transfer {employee} from {department} to {department}\" - move employee from first department to second\
-        \n- \"Pull {employee} from {department}\" - remove employee from department\
-        \n- \"Dissolve {department}\" - remove department and all employees in it\
-    \n");
-    String::from(HELP_MESSAGE)
+// The grammar of queries is a table of commands. Each command is one or more forms, written as
+// patterns of keywords, {slots} and [optional clauses] (see the pattern module), which are tried
+// in turn. The first form whose pattern matches the query builds the command from the words in
+// its slots, checking values such as dates and roles, and a query no form matches is a syntax
+// error pointing at the furthest word any form could reach.
+
+// One command of the grammar: the words it may start with, the forms of the rest, and its help. The overview lists every usage and note of every command, while
+// "Help {command}" also describes each clause of the command and gives examples.
+struct Grammar {
+    keywords: &'static [&'static str],
+    forms: &'static [Form],
+    usages: &'static [(&'static str, &'static str)],
+    notes: &'static [&'static str],
+    clauses: &'static [(&'static str, &'static str)],
+    examples: &'static [&'static str],
+    related: &'static [&'static str],
+}
+
+struct Form {
+    pattern: &'static str,
+    build: fn(&Captures) -> Result<Command, String>,
+}
+
+impl Grammar {
+    fn name(&self) -> String {
+        let keyword = self.keywords[0];
+        format!("{}{}", &keyword[..1], keyword[1..].to_lowercase())
+    }
 }
 
-fn parse_assign<'a, T: DoubleEndedIterator<Item = &'a str>>(mut tokens: T) -> Command {
-    const ASSIGN_SYNTAX_ERR: &str =
-        "\"Assign\" command must specify an employee to assign and a department to assign to";
-    match tokens.next_back() {
-        None => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
-        Some(department) => match tokens.next_back() {
-            None => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
-            Some(group_op) => match group_op.to_uppercase().as_str() {
-                "TO" => match tokens.next() {
-                    None => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
-                    Some(employee_first_name) => {
-                        let mut employee = String::from(employee_first_name);
-                        tokens.for_each(|token| {
-                            employee.push(' ');
-                            employee.push_str(token);
-                        });
-                        Command::AssignEmployeeToDepartment(employee, department.to_string())
+const DEPARTMENT: (&str, &str) = (
+    "{department}",
+    "the name of a department, which is one word long",
+);
+const EMPLOYEE: (&str, &str) = (
+    "{employee}",
+    "the name of an employee, which may be several words long",
+);
+const EMPLOYEE_IN_DEPARTMENT: (&str, &str) = (
+    "{employee} in {department}",
+    "an employee followed by their department, for names that are in more than one department",
+);
+const FIELD: (&str, &str) = (
+    "{field}",
+    "one of head, cost center, location, description or created",
+);
+const FILE: (&str, &str) = ("{file}", "the path of a file, which may hold spaces");
+const CONFLICT_POLICY: (&str, &str) = (
+    "on conflict {policy}",
+    "what happens to employees whose name is already taken in the department they move to: \"fail\", the default, moves no one, \"skip\" leaves them where they are, and \"rename\" moves them with a number after their name",
+);
+const ROLE: (&str, &str) = (
+    "{role}",
+    "Viewer, who may read, Editor, who may also change employees and department details, or Admin, who may also change departments and users, run scripts, and export and diff files",
+);
+const USER: (&str, &str) = ("{user}", "the name a user logs in with");
+
+const GRAMMAR: &[Grammar] = &[
+    Grammar {
+        keywords: &["HELP", "HALP"],
+        forms: &[Form {
+            pattern: "help [{command}]",
+            // Words after the command are ignored, so that "Help" may be put before any query
+            build: |captures| {
+                Ok(Command::Help(captures.optional(0).map(|command| {
+                    command.split_whitespace().next().unwrap_or_default().to_owned()
+                })))
+            },
+        }],
+        usages: &[
+            ("Help", "display available operations (this help message)"),
+            ("Help {command}", "display the syntax of a command, what each of its clauses means, examples and related commands"),
+        ],
+        notes: &[],
+        clauses: &[("{command}", "the first word of a command, such as \"Transfer\", or the name of an alias or macro")],
+        examples: &["Help", "Help transfer"],
+        related: &[],
+    },
+    Grammar {
+        keywords: &["EXIT", "QUIT", "LEAVE", "BYE"],
+        forms: &[Form {
+            pattern: "exit [{text}]",
+            build: |_| Ok(Command::Exit),
+        }],
+        usages: &[("Exit", "quits the program")],
+        notes: &[],
+        clauses: &[],
+        examples: &["Exit"],
+        related: &["Run"],
+    },
+    Grammar {
+        keywords: &["DEFINE"],
+        forms: &[Form {
+            pattern: "define {kind} {name} as {query}",
+            build: |captures| define(&captures.text(0), &captures.text(1), &captures.text(2)),
+        }],
+        usages: &[
+            ("Define alias {name} as {query}", "add a command standing for a query, whose \"$1\", \"$2\"... are replaced by the arguments it's given, separated by commas"),
+            ("Define macro {name} as {query}; {query}... End", "add a command running several queries in turn, stopping at the first error"),
+        ],
+        notes: &[],
+        clauses: &[
+            ("{name}", "one word of letters, digits, \"-\" and \"_\" that isn't already a command"),
+            ("{query}", "a command, in which \"$1\", \"$2\"... stand for the first, second... argument"),
+            ("End", "ends a macro spanning several lines of a script; without it, the macro runs to the end of the query"),
+        ],
+        examples: &[
+            "Define alias hire as assign $1 to $2",
+            "Define macro onboard as form $2; assign $1 to $2; set head of $2 to $1",
+        ],
+        related: &["Undefine", "Run"],
+    },
+    Grammar {
+        keywords: &["UNDEFINE"],
+        forms: &[Form {
+            pattern: "undefine {name}",
+            build: |captures| Ok(Command::Undefine(captures.text(0))),
+        }],
+        usages: &[("Undefine {name}", "remove an alias or macro")],
+        notes: &[],
+        clauses: &[("{name}", "the name of an alias or macro, in any case")],
+        examples: &["Undefine hire"],
+        related: &["Define"],
+    },
+    Grammar {
+        keywords: &["RUN"],
+        forms: &[Form {
+            pattern: "run {file} [on error {policy}]",
+            build: |captures| {
+                Ok(Command::Run(captures.text(0), error_policy(captures.optional(1))?))
+            },
+        }],
+        usages: &[
+            ("Run {file}", "run each command in a script file, reporting errors with their line number"),
+            ("Run {file} on error stop", "same as \"Run {file}\", stopping at the first error instead of the default \"on error continue\""),
+        ],
+        notes: &["Separate several commands in one query or script with \";\", since a new line does not end a command, and comment out the rest of a line with \"--\" or \"#\""],
+        clauses: &[
+            FILE,
+            ("on error {policy}", "\"continue\", the default, runs every command, while \"stop\" runs none after the first error"),
+        ],
+        examples: &["Run setup.sql", "Run setup.sql on error stop"],
+        related: &["Define", "Exit"],
+    },
+    Grammar {
+        keywords: &["SHOW"],
+        forms: &[
+            Form {
+                pattern: "show departments",
+                build: |_| Ok(Command::ShowDepartments),
+            },
+            Form {
+                pattern: "show departments tree",
+                build: |_| Ok(Command::ShowDepartmentTree),
+            },
+            Form {
+                pattern: "show departments detailed",
+                build: |_| Ok(Command::ShowDepartmentsDetailed),
+            },
+            Form {
+                pattern: "show timeline of|for {employee} [in {department}]",
+                build: |captures| {
+                    Ok(Command::ShowTimeline(captures.text(0), captures.optional(1)))
+                },
+            },
+            Form {
+                pattern: "show chain of command for {employee} [in {department}]",
+                build: |captures| {
+                    Ok(Command::ShowManagementChain(captures.text(0), captures.optional(1)))
+                },
+            },
+            Form {
+                pattern: "show users",
+                build: |_| Ok(Command::ShowUsers),
+            },
+        ],
+        usages: &[
+            ("Show departments", "list departments alphabetically"),
+            ("Show department tree", "list departments nested under their parent departments"),
+            ("Show departments detailed", "list departments with their head, cost center, location, description, created date and headcount"),
+            ("Show timeline of {employee}", "list every department an employee has been in, with the dates they joined and left"),
+            ("Show timeline of {employee} in {department}", "same as \"Show timeline of {employee}\", only for the employee of that name in one department"),
+            ("Show chain of command for {employee}", "list an employee's managers up to the top"),
+            ("Show users", "list users and their granted roles"),
+        ],
+        notes: &[],
+        clauses: &[EMPLOYEE, EMPLOYEE_IN_DEPARTMENT],
+        examples: &[
+            "Show departments detailed",
+            "Show timeline of Bob Smith",
+            "Show chain of command for Bob Smith in Sales",
+        ],
+        related: &["List", "Find", "Report"],
+    },
+    Grammar {
+        keywords: &["LIST"],
+        forms: &[
+            Form {
+                pattern: "list employees [as of {date}]",
+                build: |captures| match optional_date(captures.optional(0))? {
+                    None => Ok(Command::ListEmployees),
+                    Some(date) => Ok(Command::ListEmployeesAsOf(date)),
+                },
+            },
+            Form {
+                pattern: "list employees by department [as of {date}]",
+                build: |captures| match optional_date(captures.optional(0))? {
+                    None => Ok(Command::ListEmployeesByDepartment),
+                    Some(date) => Ok(Command::ListEmployeesByDepartmentAsOf(date)),
+                },
+            },
+            Form {
+                pattern: "list employees in {department} [as of {date}]",
+                build: |captures| match optional_date(captures.optional(1))? {
+                    None => Ok(Command::ListEmployeesInDepartment(captures.text(0))),
+                    Some(date) => Ok(Command::ListEmployeesInDepartmentAsOf(captures.text(0), date)),
+                },
+            },
+            Form {
+                pattern: "list employees under {department}",
+                build: |captures| Ok(Command::ListEmployeesUnderDepartment(captures.text(0))),
+            },
+            Form {
+                pattern: "list reports of {employee} [in {department}]",
+                build: |captures| Ok(Command::ListReports(captures.text(0), captures.optional(1))),
+            },
+            Form {
+                pattern: "list all reports of {employee} [in {department}]",
+                build: |captures| {
+                    Ok(Command::ListAllReports(captures.text(0), captures.optional(1)))
+                },
+            },
+        ],
+        usages: &[
+            ("List employees", "list employees alphabetically"),
+            ("List employees by department", "list employees and their dept, grouped by dept. alphabetically, sorted alphabetically"),
+            ("List employees in {department}", "list employees in a dept, sorted alphabetically"),
+            ("List employees under {department}", "list employees in a dept and all of its sub-departments"),
+            ("List reports of {employee}", "list employees reporting directly to an employee"),
+            ("List all reports of {employee}", "list employees reporting directly or indirectly to an employee"),
+        ],
+        notes: &["Employee lists other than \"under\" may end with \"as of {date}\" (YYYY-MM-DD) to list who was assigned on that date"],
+        clauses: &[
+            DEPARTMENT,
+            EMPLOYEE,
+            EMPLOYEE_IN_DEPARTMENT,
+            ("as of {date}", "lists who was assigned on a past date, written YYYY-MM-DD"),
+        ],
+        examples: &[
+            "List employees in Sales",
+            "List employees by department as of 2026-01-31",
+            "List all reports of Bob Smith",
+        ],
+        related: &["Show", "Find", "Search"],
+    },
+    Grammar {
+        keywords: &["FIND"],
+        forms: &[Form {
+            pattern: "find {employee}",
+            build: |captures| Ok(Command::FindEmployee(captures.text(0))),
+        }],
+        usages: &[("Find {employee}", "list the departments an employee is in")],
+        notes: &[],
+        clauses: &[EMPLOYEE],
+        examples: &["Find Bob Smith"],
+        related: &["Where", "Search"],
+    },
+    Grammar {
+        keywords: &["WHERE"],
+        forms: &[Form {
+            pattern: "where is {employee}",
+            build: |captures| Ok(Command::FindEmployee(captures.text(0))),
+        }],
+        usages: &[("Where is {employee}", "same as \"Find {employee}\"")],
+        notes: &[],
+        clauses: &[EMPLOYEE],
+        examples: &["Where is Bob Smith"],
+        related: &["Find"],
+    },
+    Grammar {
+        keywords: &["SEARCH"],
+        forms: &[Form {
+            pattern: "search {text}",
+            build: |captures| Ok(Command::Search(captures.text(0))),
+        }],
+        usages: &[("Search {text}", "rank employees whose name or department resembles the text, allowing typos")],
+        notes: &[],
+        clauses: &[("{text}", "words to look for in the names of employees and their departments")],
+        examples: &["Search bob smyth"],
+        related: &["Find"],
+    },
+    Grammar {
+        keywords: &["REPORT"],
+        forms: &[Form {
+            pattern: "report headcount [from {date}] [to {date}]",
+            build: |captures| {
+                match (
+                    optional_date(captures.optional(0))?,
+                    optional_date(captures.optional(1))?,
+                ) {
+                    (Some(from), Some(to)) if to < from => Err(String::from(
+                        "\"from\" date must not be after \"to\" date",
+                    )),
+                    (from, to) => Ok(Command::ReportHeadcount(from, to)),
+                }
+            },
+        }],
+        usages: &[
+            ("Report headcount", "list headcounts and shares by department, the largest, smallest and empty departments, and employees joining and leaving each department"),
+            ("Report headcount from {date} to {date}", "same as \"Report headcount\", only counting employees joining and leaving between the dates (YYYY-MM-DD)"),
+        ],
+        notes: &[],
+        clauses: &[("from {date} to {date}", "the first and last days counted, written YYYY-MM-DD, either of which may be left out")],
+        examples: &["Report headcount", "Report headcount from 2026-01-01 to 2026-03-31"],
+        related: &["Show", "List"],
+    },
+    Grammar {
+        keywords: &["FORM"],
+        forms: &[Form {
+            pattern: "form {department} [under {department}]",
+            build: |captures| match captures.optional(1) {
+                None => Ok(Command::FormDepartment(captures.text(0))),
+                Some(parent) => Ok(Command::FormDepartmentUnder(captures.text(0), parent)),
+            },
+        }],
+        usages: &[
+            ("Form {department}", "create new department"),
+            ("Form {department} under {department}", "create new department inside another department"),
+        ],
+        notes: &[],
+        clauses: &[DEPARTMENT],
+        examples: &["Form Legal", "Form Payroll under Accounting"],
+        related: &["Move", "Dissolve"],
+    },
+    Grammar {
+        keywords: &["MOVE"],
+        forms: &[
+            Form {
+                pattern: "move {department} under {department}",
+                build: |captures| {
+                    Ok(Command::MoveDepartment(captures.text(0), captures.optional(1)))
+                },
+            },
+            Form {
+                pattern: "move {department} to top",
+                build: |captures| Ok(Command::MoveDepartment(captures.text(0), None)),
+            },
+        ],
+        usages: &[
+            ("Move {department} under {department}", "place department inside another department"),
+            ("Move {department} to top", "take department out of its parent department"),
+        ],
+        notes: &[],
+        clauses: &[DEPARTMENT],
+        examples: &["Move Payroll under Finance", "Move Payroll to top"],
+        related: &["Form"],
+    },
+    Grammar {
+        keywords: &["ASSIGN"],
+        forms: &[Form {
+            pattern: "assign {employee} to {department}",
+            build: |captures| {
+                Ok(Command::AssignEmployeeToDepartment(captures.text(0), captures.text(1)))
+            },
+        }],
+        usages: &[("Assign {employee} to {department}", "create new employee under department")],
+        notes: &[],
+        clauses: &[EMPLOYEE, DEPARTMENT],
+        examples: &["Assign Bob Smith to Sales"],
+        related: &["Transfer", "Pull"],
+    },
+    Grammar {
+        keywords: &["TRANSFER"],
+        forms: &[
+            Form {
+                pattern: "transfer all from {department} to {department} [on conflict {policy}]",
+                build: |captures| {
+                    Ok(Command::TransferAllBetweenDepartments(
+                        captures.text(0),
+                        captures.text(1),
+                        conflict_policy(captures.optional(2))?,
+                    ))
+                },
+            },
+            Form {
+                pattern: "transfer employees where {condition} to {department} [on conflict {policy}]",
+                build: |captures| {
+                    let condition = captures.text(0);
+                    Ok(Command::TransferMatchingToDepartment(
+                        Filter::parse(&condition.split_whitespace().collect::<Vec<&str>>())?,
+                        captures.text(1),
+                        conflict_policy(captures.optional(2))?,
+                    ))
+                },
+            },
+            Form {
+                pattern: "transfer {employee} from {department} to {department}",
+                build: |captures| {
+                    Ok(Command::TransferEmployeeBetweenDepartments(
+                        captures.text(0),
+                        captures.text(1),
+                        captures.text(2),
+                    ))
+                },
+            },
+        ],
+        usages: &[
+            ("Transfer {employee} from {department} to {department}", "move employee from first department to second"),
+            ("Transfer all from {department} to {department}", "move every employee from first department to second"),
+            ("Transfer employees where {condition} to {department}", "move every matching employee, e.g. \"where name contains Bob and department is not Sales\""),
+        ],
+        notes: &[],
+        clauses: &[
+            EMPLOYEE,
+            DEPARTMENT,
+            ("{condition}", "conditions on the name, department or manager of employees, using \"is\", \"is not\", \"contains\" or \"starts with\", combined with \"not\", \"and\" and \"or\", which bind in that order, and grouped with parentheses"),
+            CONFLICT_POLICY,
+        ],
+        examples: &[
+            "Transfer Bob Smith from Sales to Design",
+            "Transfer all from Sales to Design on conflict rename",
+            "Transfer employees where department is Sales and name starts with B to Design",
+            "Transfer employees where not (manager is Bob or manager is Alice) to Design",
+        ],
+        related: &["Assign", "Pull", "Merge"],
+    },
+    Grammar {
+        keywords: &["PULL"],
+        forms: &[
+            Form {
+                pattern: "pull all from {department}",
+                build: |captures| Ok(Command::PullAllFromDepartment(captures.text(0))),
+            },
+            Form {
+                pattern: "pull {employee} from {department}",
+                build: |captures| {
+                    Ok(Command::PullEmployeeFromDepartment(captures.text(0), captures.text(1)))
+                },
+            },
+        ],
+        usages: &[
+            ("Pull {employee} from {department}", "remove employee from department, passing their reports to their manager"),
+            ("Pull all from {department}", "remove every employee from department"),
+        ],
+        notes: &[],
+        clauses: &[EMPLOYEE, DEPARTMENT],
+        examples: &["Pull Bob Smith from Sales", "Pull all from Sales"],
+        related: &["Transfer", "Dissolve"],
+    },
+    Grammar {
+        keywords: &["MERGE"],
+        forms: &[Form {
+            pattern: "merge [department] {department} into {department} [on conflict {policy}]",
+            build: |captures| {
+                Ok(Command::MergeDepartments(
+                    captures.text(0),
+                    captures.text(1),
+                    conflict_policy(captures.optional(2))?,
+                ))
+            },
+        }],
+        usages: &[("Merge department {department} into {department}", "move every employee into second department, then dissolve the first")],
+        notes: &["Bulk transfers and merges may end with \"on conflict skip\", \"on conflict rename\" or the default \"on conflict fail\""],
+        clauses: &[DEPARTMENT, CONFLICT_POLICY],
+        examples: &["Merge department Sales into Design on conflict skip"],
+        related: &["Transfer", "Dissolve"],
+    },
+    Grammar {
+        keywords: &["SET"],
+        forms: &[
+            Form {
+                pattern: "set manager of {employee} [in {department}] to {employee} [in {department}]",
+                build: |captures| {
+                    Ok(Command::SetManager(
+                        captures.text(0),
+                        captures.optional(1),
+                        captures.text(2),
+                        captures.optional(3),
+                    ))
+                },
+            },
+            Form {
+                pattern: "set {field} of {department} to {value}",
+                build: |captures| {
+                    let field = department_field(&captures.text(0), "set")?;
+                    let value = captures.text(2);
+                    if field == DepartmentField::Created && Date::parse(&value).is_none() {
+                        return Err(format!(
+                            "\"{}\" is not a date: dates are written YYYY-MM-DD",
+                            value
+                        ));
+                    }
+                    Ok(Command::SetDepartmentField(captures.text(1), field, value))
+                },
+            },
+        ],
+        usages: &[
+            ("Set manager of {employee} to {employee}", "make employee report to another employee"),
+            ("Set {field} of {department} to {value}", "set a department's head, cost center, location, description or created date (YYYY-MM-DD)"),
+        ],
+        notes: &[],
+        clauses: &[
+            EMPLOYEE,
+            EMPLOYEE_IN_DEPARTMENT,
+            DEPARTMENT,
+            FIELD,
+            ("{value}", "the new value of the field, which may be several words long, or a date written YYYY-MM-DD for the created date"),
+        ],
+        examples: &["Set manager of Bob Smith to Ann Lee", "Set location of Sales to Building 7"],
+        related: &["Clear", "List"],
+    },
+    Grammar {
+        keywords: &["CLEAR"],
+        forms: &[
+            Form {
+                pattern: "clear manager of {employee} [in {department}]",
+                build: |captures| Ok(Command::ClearManager(captures.text(0), captures.optional(1))),
+            },
+            Form {
+                pattern: "clear {field} of {department}",
+                build: |captures| {
+                    Ok(Command::ClearDepartmentField(
+                        captures.text(1),
+                        department_field(&captures.text(0), "cleared")?,
+                    ))
+                },
+            },
+        ],
+        usages: &[
+            ("Clear manager of {employee}", "remove employee's reporting line"),
+            ("Clear {field} of {department}", "remove a department's head, cost center, location, description or created date"),
+        ],
+        notes: &[],
+        clauses: &[EMPLOYEE, EMPLOYEE_IN_DEPARTMENT, DEPARTMENT, FIELD],
+        examples: &["Clear manager of Bob Smith", "Clear location of Sales"],
+        related: &["Set"],
+    },
+    Grammar {
+        keywords: &["RENAME"],
+        forms: &[
+            Form {
+                pattern: "rename department {department} to {department}",
+                build: |captures| Ok(Command::RenameDepartment(captures.text(0), captures.text(1))),
+            },
+            Form {
+                pattern: "rename employee {employee} [in {department}] to {employee}",
+                build: |captures| {
+                    Ok(Command::RenameEmployee(
+                        captures.text(0),
+                        captures.optional(1),
+                        captures.text(2),
+                    ))
+                },
+            },
+        ],
+        usages: &[
+            ("Rename department {department} to {department}", "change a department's name, keeping its employees and details"),
+            ("Rename employee {employee} in {department} to {employee}", "change an employee's name, keeping their details"),
+        ],
+        notes: &[],
+        clauses: &[DEPARTMENT, EMPLOYEE],
+        examples: &[
+            "Rename department Sales to Revenue",
+            "Rename employee Bob Smith in Sales to Robert Smith",
+        ],
+        related: &["Form", "Assign"],
+    },
+    Grammar {
+        keywords: &["DISSOLVE"],
+        forms: &[Form {
+            pattern: "dissolve {department}",
+            build: |captures| Ok(Command::DissolveDepartment(captures.text(0))),
+        }],
+        usages: &[("Dissolve {department}", "remove department and all employees in it, moving its sub-departments to its parent")],
+        notes: &[],
+        clauses: &[DEPARTMENT],
+        examples: &["Dissolve Legal"],
+        related: &["Form", "Merge", "Pull"],
+    },
+    Grammar {
+        keywords: &["GRANT"],
+        forms: &[Form {
+            pattern: "grant {role} to {user} [on {department}]",
+            build: |captures| {
+                Ok(Command::GrantRole(
+                    captures.text(1),
+                    role(&captures.text(0))?,
+                    captures.optional(2),
+                ))
+            },
+        }],
+        usages: &[
+            ("Grant {role} to {user}", "give user a Viewer, Editor or Admin role on all departments"),
+            ("Grant {role} to {user} on {department}", "give user a role on one department"),
+        ],
+        notes: &[],
+        clauses: &[ROLE, USER, DEPARTMENT],
+        examples: &["Grant Admin to Root", "Grant Editor to Ellen on Design"],
+        related: &["Revoke", "Show"],
+    },
+    Grammar {
+        keywords: &["REVOKE"],
+        forms: &[Form {
+            pattern: "revoke {role} from {user} [on {department}]",
+            build: |captures| {
+                Ok(Command::RevokeRole(
+                    captures.text(1),
+                    role(&captures.text(0))?,
+                    captures.optional(2),
+                ))
+            },
+        }],
+        usages: &[
+            ("Revoke {role} from {user}", "take away a role on all departments from user"),
+            ("Revoke {role} from {user} on {department}", "take away a role on one department from user"),
+        ],
+        notes: &[],
+        clauses: &[ROLE, USER, DEPARTMENT],
+        examples: &["Revoke Editor from Ellen on Design"],
+        related: &["Grant", "Show"],
+    },
+    Grammar {
+        keywords: &["EXPORT"],
+        forms: &[Form {
+            pattern: "export {format} [of {department}] to {file}",
+            build: |captures| {
+                let format_name = captures.text(0);
+                match ExportFormat::parse(&format_name) {
+                    None => Err(format!(
+                        "\"{}\" is not an export format: formats are JSON, CSV and YAML",
+                        format_name
+                    )),
+                    Some(format) => {
+                        Ok(Command::Export(format, captures.optional(1), captures.text(2)))
                     }
+                }
+            },
+        }],
+        usages: &[
+            ("Export {format} to {file}", "write every department, its employees and users to a JSON, CSV or YAML file"),
+            ("Export {format} of {department} to {file}", "write one department and its employees to a JSON, CSV or YAML file"),
+        ],
+        notes: &[],
+        clauses: &[("{format}", "JSON, CSV or YAML"), DEPARTMENT, FILE],
+        examples: &["Export JSON to org.json", "Export CSV of Sales to sales.csv"],
+        related: &["Diff"],
+    },
+    Grammar {
+        keywords: &["DIFF"],
+        forms: &[Form {
+            pattern: "diff {file}",
+            build: |captures| Ok(Command::Diff(captures.text(0))),
+        }],
+        usages: &[("Diff {file}", "compare the organization with an exported file, listing departments added and removed, and employees added, removed and transferred")],
+        notes: &[],
+        clauses: &[("{file}", "the path of a file written by \"Export\", whose format is told by its extension")],
+        examples: &["Diff org.json"],
+        related: &["Export"],
+    },
+    Grammar {
+        keywords: &["CHART"],
+        forms: &[
+            Form {
+                pattern: "chart {format} [depth {levels}] [collapse above {headcount}]",
+                build: |captures| {
+                    chart(&captures.text(0), captures.optional(1), captures.optional(2))
+                },
+            },
+            Form {
+                pattern: "chart {format} collapse above {headcount} depth {levels}",
+                build: |captures| {
+                    chart(&captures.text(0), captures.optional(2), captures.optional(1))
                 },
-                _ => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
             },
+        ],
+        usages: &[
+            ("Chart {format}", "draw departments, sub-departments, employees and reporting lines as a DOT or Mermaid chart"),
+            ("Chart {format} depth {levels} collapse above {headcount}", "same as \"Chart {format}\", with either clause, or both"),
+            ("Chart {format} collapse above {headcount} depth {levels}", "same, with the clauses the other way round"),
+        ],
+        notes: &["Charts may end with \"depth {levels}\" to limit the levels of sub-departments drawn, and \"collapse above {headcount}\" to draw larger departments as a count of their employees"],
+        clauses: &[
+            ("{format}", "DOT, for Graphviz, or Mermaid"),
+            ("depth {levels}", "how many levels of sub-departments to draw below the top level departments"),
+            ("collapse above {headcount}", "draws departments with more employees than this as a single count of their employees"),
+        ],
+        examples: &["Chart mermaid", "Chart dot depth 1 collapse above 10"],
+        related: &["Show"],
+    },
+];
+
+// Shown at the end of the overview, since they apply to several commands
+const NOTES: &[&str] = &[
+    "Department, departments, dept and depts mean the same wherever one of them is expected, as do the singular and plural of employees, reports and users",
+    "Any {employee} may be followed by \"in {department}\" when the name is in more than one department",
+];
+
+fn grammar(keyword: &str) -> Option<&'static Grammar> {
+    let keyword = keyword.to_uppercase();
+    GRAMMAR
+        .iter()
+        .find(|grammar| grammar.keywords.contains(&keyword.as_str()))
+}
+
+pub fn parse(command_string: String) -> Command {
+    let tokens = command_string.split_whitespace().collect::<Vec<&str>>();
+    let grammar = match tokens.first() {
+        None => return Command::Empty,
+        Some(keyword) => match grammar(keyword) {
+            None => return Command::InvalidCommandErr(keyword.to_string()),
+            Some(grammar) => grammar,
         },
+    };
+    let mut mismatch = Mismatch::default();
+    for form in grammar.forms {
+        if let Some(captures) = Pattern::new(form.pattern).matches(&tokens, &mut mismatch) {
+            return (form.build)(&captures).unwrap_or_else(Command::SyntaxErr);
+        }
     }
+    Command::SyntaxErr(mismatch.message(&tokens))
 }
 
-fn parse_dissolve<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
-    match tokens.next() {
-        None => Command::SyntaxErr(String::from(
-            "\"Dissolve\" command must specify a department to dissolve",
-        )),
-        Some(department) => match tokens.next() {
-            Some(_) => Command::SyntaxErr(String::from(
-                "Due to company policy, department names can only be one word long",
-            )),
-            None => Command::DissolveDepartment(department.to_string()),
-        },
+// Aliases are expanded before their query is parsed, and macros are left to run their
+// statements one at a time, so that each statement sees the changes made by the last
+pub fn parse_with(command_string: String, aliases: &Aliases) -> Command {
+    expand(command_string, aliases, 0)
+}
+
+fn expand(command_string: String, aliases: &Aliases, expansions: usize) -> Command {
+    let mut words = command_string.trim().splitn(2, char::is_whitespace);
+    let alias = match words.next().and_then(|name| aliases.get(name)) {
+        None => return parse(command_string),
+        Some(alias) => alias,
+    };
+    // A chain of aliases longer than the number of aliases must visit one of them twice
+    if expansions >= aliases.len() {
+        return Command::SyntaxErr(format!("Alias \"{}\" expands into itself", alias.name()));
+    }
+    match (alias.expand(words.next().unwrap_or("")), alias.kind()) {
+        (Err(message), _) => Command::SyntaxErr(message),
+        (Ok(statements), AliasKind::Alias) => {
+            expand(statements.join("; "), aliases, expansions + 1)
+        }
+        (Ok(statements), AliasKind::Macro) => Command::Macro(alias.name().to_owned(), statements),
     }
 }
 
-fn parse_form<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
-    match tokens.next() {
-        None => Command::SyntaxErr(String::from(
-            "\"Form\" command must specify a department to form",
-        )),
-        Some(department) => match tokens.next() {
-            Some(_) => Command::SyntaxErr(String::from(
-                "Due to company policy, department names can only be one word long",
-            )),
-            None => Command::FormDepartment(department.to_string()),
-        },
+pub fn help_with(aliases: &Aliases) -> String {
+    let mut help = help();
+    if aliases.is_empty() {
+        return help;
     }
+    help.push_str("User-defined Operations:");
+    aliases
+        .iter()
+        .for_each(|alias| help.push_str(&alias_usage(alias)));
+    help.push('\n');
+    help
 }
 
-fn parse_list<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
-    match tokens.next() {
-        None => Command::SyntaxErr(String::from("\"List\" command must specify a list name")),
-        Some(list_name) => match list_name.to_uppercase().as_str() {
-            "EMPLOYEES" | "EMPLOYEE" => match tokens.next() {
-                None => Command::ListEmployees,
-                Some(group_op) => match group_op.to_uppercase().as_str() {
-                    "BY" => match tokens.next() {
-                        None => Command::SyntaxErr(String::from(
-                            "\"List employees by\" must specify a group by field",
-                        )),
-                        Some(group_list) => match group_list.to_uppercase().as_str() {
-                            "DEPARTMENT" => match tokens.next() {
-                                None => Command::ListEmployeesByDepartment,
-                                Some(extra_token) => Command::SyntaxErr(format!(
-                                    "Unexpected token \"{}\" after group by field \"{}\"",
-                                    extra_token, group_list
-                                )),
-                            },
-                            _ => Command::SyntaxErr(format!(
-                                "\"{}\" is not a field employees can by grouped by",
-                                group_list
-                            )),
-                        },
-                    },
-                    "IN" => match tokens.next() {
-                        None => Command::SyntaxErr(String::from(
-                            "Command \"List employees in\" must specify a department name",
-                        )),
-                        Some(department_name) => match tokens.next() {
-                            None => Command::ListEmployeesInDepartment(department_name.to_string()),
-                            Some(extra_token) => Command::SyntaxErr(format!(
-                                "Unexpected token \"{}\" after department name \"{}\"",
-                                extra_token, department_name
-                            )),
-                        },
-                    },
-                    _ => Command::SyntaxErr(format!(
-                        "Unexpected token \"{}\" after list name \"{}\"",
-                        group_op, list_name,
-                    )),
-                },
-            },
-            _ => Command::SyntaxErr(format!(
-                "Cannot list \"{}\": list does not exist",
-                list_name,
-            )),
-        },
+pub fn help() -> String {
+    let mut help = String::from("\nAvailable Operations:");
+    GRAMMAR.iter().for_each(|grammar| {
+        push_usages(&mut help, grammar);
+        grammar
+            .notes
+            .iter()
+            .for_each(|note| help.push_str(&format!("\n- ({})", note)));
+    });
+    NOTES
+        .iter()
+        .for_each(|note| help.push_str(&format!("\n- ({})", note)));
+    help.push('\n');
+    help
+}
+
+// Describes a command, or an alias or macro, by the word it starts with
+pub fn help_for(keyword: &str, aliases: &Aliases) -> Option<String> {
+    if let Some(alias) = aliases.get(keyword) {
+        return Some(format!(
+            "\n\"{}\" {}:{}\n",
+            alias.name(),
+            alias.kind().name(),
+            alias_usage(alias)
+        ));
+    }
+    let grammar = grammar(keyword)?;
+    let mut help = format!("\n\"{}\" Operations:", grammar.name());
+    push_usages(&mut help, grammar);
+    if grammar.keywords.len() > 1 {
+        let others = grammar.keywords[1..]
+            .iter()
+            .map(|keyword| format!("\"{}{}\"", &keyword[..1], keyword[1..].to_lowercase()))
+            .collect::<Vec<String>>();
+        help.push_str(&format!(
+            "\n- (\"{}\" may also be written {})",
+            grammar.name(),
+            others.join(", ")
+        ));
     }
+    if !grammar.clauses.is_empty() {
+        help.push_str("\nClauses:");
+        grammar.clauses.iter().for_each(|(clause, description)| {
+            help.push_str(&format!("\n- \"{}\" - {}", clause, description))
+        });
+    }
+    help.push_str("\nExamples:");
+    grammar
+        .examples
+        .iter()
+        .for_each(|example| help.push_str(&format!("\n- \"{}\"", example)));
+    if !grammar.related.is_empty() {
+        let related = grammar
+            .related
+            .iter()
+            .map(|name| format!("\"Help {}\"", name))
+            .collect::<Vec<String>>();
+        help.push_str(&format!("\nSee Also: {}", related.join(", ")));
+    }
+    help.push('\n');
+    Some(help)
 }
 
-fn parse_pull<'a, T: DoubleEndedIterator<Item = &'a str>>(mut tokens: T) -> Command {
-    const PULL_SYNTAX_ERR: &str =
-        "\"Pull\" command must specify an employee to pull and a department to pull from";
-    match tokens.next_back() {
-        None => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
-        Some(department) => match tokens.next_back() {
-            None => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
-            Some(group_op) => match group_op.to_uppercase().as_str() {
-                "FROM" => match tokens.next() {
-                    None => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
-                    Some(employee_first_name) => {
-                        let mut employee = String::from(employee_first_name);
-                        tokens.for_each(|token| {
-                            employee.push(' ');
-                            employee.push_str(token);
-                        });
-                        Command::PullEmployeeFromDepartment(employee, department.to_string())
-                    }
-                },
-                _ => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
-            },
-        },
+fn push_usages(help: &mut String, grammar: &Grammar) {
+    grammar.usages.iter().for_each(|(syntax, description)| {
+        help.push_str(&format!("\n- \"{}\" - {}", syntax, description))
+    });
+}
+
+fn alias_usage(alias: &Alias) -> String {
+    let arguments = (1..=alias.parameters())
+        .map(|number| format!("${}", number))
+        .collect::<Vec<String>>();
+    format!(
+        "\n- \"{}\" - {} for \"{}\"",
+        [alias.name().to_owned(), arguments.join(", ")]
+            .join(" ")
+            .trim(),
+        alias.kind().name(),
+        alias.statements().join("; ")
+    )
+}
+
+// Names of aliases are single words that don't shadow a command, whether they are defined or
+// restored
+pub fn check_alias_name(name: &str) -> Result<(), String> {
+    if !name
+        .chars()
+        .all(|character| character.is_alphanumeric() || character == '-' || character == '_')
+    {
+        return Err(format!(
+            "\"{}\" is not a valid name: names can only hold letters, digits, \"-\" and \"_\"",
+            name
+        ));
     }
+    if grammar(name).is_some() {
+        return Err(format!(
+            "\"{}\" is already a command, and can't be redefined",
+            name
+        ));
+    }
+    Ok(())
 }
 
-fn parse_show<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
-    let table = tokens.next();
-    match table {
-        None => Command::SyntaxErr(String::from("\"Show\" command must specify a list name")),
-        Some(list_name) => match list_name.to_uppercase().as_str() {
-            "DEPARTMENTS" | "DEPT" | "DEPARTMENT" | "DEPTS" => match tokens.next() {
-                None => Command::ShowDepartments,
-                Some(extra_token) => Command::SyntaxErr(format!(
-                    "Unexpected token \"{}\" after list name \"{}\"",
-                    extra_token, list_name
-                )),
-            },
-            _ => Command::SyntaxErr(format!(
-                "Cannot show \"{}\": list does not exist",
-                list_name
-            )),
-        },
+fn define(kind: &str, name: &str, query: &str) -> Result<Command, String> {
+    let kind = match kind.to_uppercase().as_str() {
+        "ALIAS" => AliasKind::Alias,
+        "MACRO" => AliasKind::Macro,
+        _ => {
+            return Err(format!(
+                "\"{}\" can't be defined: define an alias or a macro",
+                kind
+            ))
+        }
+    };
+    check_alias_name(name)?;
+    let statements = query
+        .split(';')
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .map(String::from)
+        .collect::<Vec<String>>();
+    if statements.is_empty() {
+        return Err(format!(
+            "\"Define\" command must specify the query that {} stands for",
+            name
+        ));
     }
+    Ok(Command::Define(Alias::new(name, kind, statements)))
 }
 
-fn parse_transfer<'a, T: DoubleEndedIterator<Item = &'a str>>(mut tokens: T) -> Command {
-    const TRANSFER_SYNTAX_ERR: &str = "\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to";
-    match tokens.next_back() {
-        None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
-        Some(to_department) => match tokens.next_back() {
-            None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
-            Some(to_op) => match to_op.to_uppercase().as_str() {
-                "TO" => match tokens.next_back() {
-                    None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
-                    Some(from_department) => match tokens.next_back() {
-                        None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
-                        Some(from_op) => match from_op.to_uppercase().as_str() {
-                            "FROM" => match tokens.next() {
-                                None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
-                                Some(employee_first_name) => {
-                                    let mut employee = String::from(employee_first_name);
-                                    tokens.for_each(|token| {
-                                        employee.push(' ');
-                                        employee.push_str(token);
-                                    });
-                                    Command::TransferEmployeeBetweenDepartments(
-                                        employee,
-                                        from_department.to_string(),
-                                        to_department.to_string(),
-                                    )
-                                }
-                            },
-                            _ => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
-                        },
-                    },
-                },
-                _ => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
-            },
-        },
+fn chart(
+    format_name: &str,
+    levels: Option<String>,
+    headcount: Option<String>,
+) -> Result<Command, String> {
+    let format = ChartFormat::parse(format_name).ok_or_else(|| {
+        format!(
+            "\"{}\" is not a chart format: formats are DOT and Mermaid",
+            format_name
+        )
+    })?;
+    Ok(Command::Chart(Chart {
+        max_depth: levels.as_deref().map(number).transpose()?,
+        collapse_above: headcount.as_deref().map(number).transpose()?,
+        ..Chart::new(format)
+    }))
+}
+
+fn number(text: &str) -> Result<usize, String> {
+    text.parse()
+        .map_err(|_| format!("\"{}\" is not a whole number", text))
+}
+
+fn optional_date(date: Option<String>) -> Result<Option<Date>, String> {
+    date.map(|date| {
+        Date::parse(&date).ok_or_else(|| format!("\"{}\" is not a YYYY-MM-DD date", date))
+    })
+    .transpose()
+}
+
+fn department_field(field_name: &str, action: &str) -> Result<DepartmentField, String> {
+    DepartmentField::parse(field_name).ok_or_else(|| {
+        format!(
+            "\"{}\" is not a field that can be {}: fields are manager, head, cost center, location, description and created",
+            field_name, action
+        )
+    })
+}
+
+fn role(role_name: &str) -> Result<Role, String> {
+    Role::parse(role_name).ok_or_else(|| {
+        format!(
+            "\"{}\" is not a role: roles are Viewer, Editor and Admin",
+            role_name
+        )
+    })
+}
+
+fn conflict_policy(policy: Option<String>) -> Result<ConflictPolicy, String> {
+    let policy = match policy {
+        None => return Ok(ConflictPolicy::Fail),
+        Some(policy) => policy,
+    };
+    match policy.to_uppercase().as_str() {
+        "FAIL" => Ok(ConflictPolicy::Fail),
+        "SKIP" => Ok(ConflictPolicy::Skip),
+        "RENAME" => Ok(ConflictPolicy::Rename),
+        _ => Err(format!(
+            "\"{}\" is not a conflict policy: policies are fail, skip and rename",
+            policy
+        )),
+    }
+}
+
+fn error_policy(policy: Option<String>) -> Result<ErrorPolicy, String> {
+    let policy = match policy {
+        None => return Ok(ErrorPolicy::Continue),
+        Some(policy) => policy,
+    };
+    match policy.to_uppercase().as_str() {
+        "CONTINUE" => Ok(ErrorPolicy::Continue),
+        "STOP" => Ok(ErrorPolicy::Stop),
+        _ => Err(format!(
+            "\"{}\" is not an error policy: policies are continue and stop",
+            policy
+        )),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::database::names::Names;
 
     mod fn_help {
         use super::help;
 
         #[test]
-        fn returns_help_message() {
-            assert_eq!(
-                help(),
+        fn lists_usages_followed_by_their_notes() {
+            let help = help();
+
+            assert!(help.starts_with(
                 "\
                     \nAvailable Operations:\
                     \n- \"Help\" - display available operations (this help message)\
+                    \n- \"Help {command}\" - display the syntax of a command, what each of its clauses means, examples and related commands\
                     \n- \"Exit\" - quits the program\
-                    \n- \"Show departments\" - list departments alphabetically\
-                    \n- \"List employees\" - list employees alphabetically\
-                    \n- \"List employees by department\" - list employees and their dept, grouped by dept. alphabetically, sorted alphabetically\
-                    \n- \"List employees in {department}\" - list employees in a dept, sorted alphabetically\
-                    \n- \"Form {department}\" - create new department\
-                    \n- \"Assign {employee} to {department}\" - create new employee under department\
-                    \n- \"Transfer {employee} from {department} to {department}\" - move employee from first department to second\
-                    \n- \"Pull {employee} from {department}\" - remove employee from department\
-                    \n- \"Dissolve {department}\" - remove department and all employees in it\
-                \n"
+                    \n"
+            ));
+            assert!(help.contains(
+                "\
+                    \n- \"Merge department {department} into {department}\" - move every employee into second department, then dissolve the first\
+                    \n- (Bulk transfers and merges may end with \"on conflict skip\", \"on conflict rename\" or the default \"on conflict fail\")\
+                    \n"
+            ));
+            assert!(help.ends_with(
+                "\n- (Any {employee} may be followed by \"in {department}\" when the name is in more than one department)\n"
+            ));
+        }
+    }
+
+    mod fn_help_for {
+        use super::{help_for, Alias, AliasKind, Aliases, Names};
+
+        #[test]
+        fn describes_a_command() {
+            assert_eq!(
+                Some(
+                    "\
+                        \n\"Exit\" Operations:\
+                        \n- \"Exit\" - quits the program\
+                        \n- (\"Exit\" may also be written \"Quit\", \"Leave\", \"Bye\")\
+                        \nExamples:\
+                        \n- \"Exit\"\
+                        \nSee Also: \"Help Run\"\
+                        \n"
+                    .to_string()
+                ),
+                help_for("exit", &Aliases::new(Names::default()))
+            );
+        }
+
+        #[test]
+        fn describes_clauses_by_any_keyword() {
+            let help = help_for("Halp", &Aliases::new(Names::default())).unwrap();
+
+            assert!(help.starts_with("\n\"Help\" Operations:"));
+            assert!(help.contains(
+                "\nClauses:\n- \"{command}\" - the first word of a command, such as \"Transfer\", or the name of an alias or macro\n"
+            ));
+        }
+
+        #[test]
+        fn describes_an_alias() {
+            let mut aliases = Aliases::new(Names::default());
+            aliases.define(Alias::new(
+                "hire",
+                AliasKind::Alias,
+                vec!["assign $1 to $2".to_string()],
+            ));
+
+            assert_eq!(
+                Some(
+                    "\n\"hire\" alias:\n- \"hire $1, $2\" - alias for \"assign $1 to $2\"\n"
+                        .to_string()
+                ),
+                help_for("HIRE", &aliases)
+            );
+        }
+
+        #[test]
+        fn returns_none_for_other_words() {
+            assert_eq!(
+                None,
+                help_for("frobnicate", &Aliases::new(Names::default()))
+            );
+        }
+    }
+
+    mod grammar {
+        use super::{parse, Command, Grammar, Mismatch, Pattern, GRAMMAR};
+        use std::collections::HashSet;
+
+        // Stands for each placeholder with a value the parser accepts, so that every usage in
+        // the help can be parsed
+        fn sample(grammar: &Grammar, placeholder: &str) -> &'static str {
+            match (grammar.keywords[0], placeholder) {
+                ("CHART", "format") => "mermaid",
+                (_, "format") => "json",
+                (_, "department") => "Sales",
+                (_, "employee") => "Bob Smith",
+                (_, "date") => "2026-01-31",
+                (_, "file") => "org.json",
+                (_, "field") => "location",
+                (_, "value") => "Building 7",
+                (_, "role") => "editor",
+                (_, "user") => "Ellen",
+                (_, "text") => "bob",
+                (_, "condition") => "name contains Bob",
+                (_, "levels") => "2",
+                (_, "headcount") => "10",
+                (_, "command") => "transfer",
+                (_, "name") => "hire",
+                (_, "query") => "assign $1 to $2",
+                (_, placeholder) => panic!("No sample value for {{{}}}", placeholder),
+            }
+        }
+
+        fn assert_parses(query: &str) {
+            match parse(query.to_string()) {
+                Command::SyntaxErr(message) => panic!("\"{}\" is invalid: {}", query, message),
+                Command::InvalidCommandErr(_) => panic!("\"{}\" is not a command", query),
+                _ => (),
+            }
+        }
+
+        fn fill(grammar: &Grammar, syntax: &str) -> String {
+            let mut query = String::new();
+            let mut rest = syntax;
+            while let Some(open) = rest.find('{') {
+                let close = rest.find('}').unwrap();
+                query.push_str(&rest[..open]);
+                query.push_str(sample(grammar, &rest[open + 1..close]));
+                rest = &rest[close + 1..];
+            }
+            query.push_str(rest);
+            query
+        }
+
+        // The form that parsing would pick for the query, as the first of the grammar to match
+        fn form_of(grammar: &Grammar, query: &str) -> Option<usize> {
+            let tokens = query.split_whitespace().collect::<Vec<&str>>();
+            grammar.forms.iter().position(|form| {
+                Pattern::new(form.pattern)
+                    .matches(&tokens, &mut Mismatch::default())
+                    .is_some()
+            })
+        }
+
+        #[test]
+        fn parses_every_usage() {
+            GRAMMAR.iter().for_each(|grammar| {
+                grammar
+                    .usages
+                    .iter()
+                    .for_each(|(syntax, _)| assert_parses(&fill(grammar, syntax)))
+            });
+        }
+
+        // Usages are written beside the patterns rather than generated from them, so each form
+        // must still parse one of them
+        #[test]
+        fn documents_every_form_with_a_usage() {
+            GRAMMAR.iter().for_each(|grammar| {
+                let documented = grammar
+                    .usages
+                    .iter()
+                    .filter_map(|(syntax, _)| form_of(grammar, &fill(grammar, syntax)))
+                    .collect::<HashSet<usize>>();
+
+                grammar.forms.iter().enumerate().for_each(|(index, form)| {
+                    assert!(
+                        documented.contains(&index),
+                        "No usage of {} parses with \"{}\"",
+                        grammar.name(),
+                        form.pattern
+                    )
+                });
+            });
+        }
+
+        #[test]
+        fn parses_every_example() {
+            GRAMMAR.iter().for_each(|grammar| {
+                grammar.examples.iter().for_each(|example| {
+                    let keyword = example.split_whitespace().next().unwrap().to_uppercase();
+
+                    assert!(grammar.keywords.contains(&keyword.as_str()), "{}", example);
+                    assert_parses(example);
+                })
+            });
+        }
+
+        #[test]
+        fn relates_commands_that_exist() {
+            let names = GRAMMAR
+                .iter()
+                .map(|grammar| grammar.name())
+                .collect::<HashSet<String>>();
+
+            GRAMMAR
+                .iter()
+                .flat_map(|grammar| grammar.related.iter())
+                .for_each(|related| assert!(names.contains(*related), "{}", related));
+        }
+
+        #[test]
+        fn starts_commands_with_distinct_keywords() {
+            let keywords = GRAMMAR
+                .iter()
+                .flat_map(|grammar| grammar.keywords.iter())
+                .collect::<Vec<&&str>>();
+
+            assert_eq!(
+                keywords.len(),
+                keywords.iter().collect::<HashSet<&&&str>>().len()
             );
+            keywords
+                .iter()
+                .for_each(|keyword| assert_eq!(keyword.to_uppercase(), ***keyword));
         }
     }
 
@@ -274,7 +1374,7 @@ mod tests {
 
         #[test]
         fn empty_query() {
-            assert_eq!(Command::EmptyCommand, parse("".to_string()));
+            assert_eq!(Command::Empty, parse("".to_string()));
         }
 
         #[test]
@@ -284,663 +1384,1453 @@ mod tests {
 
         #[test]
         fn help() {
-            assert_eq!(Command::Help, parse("help".to_string()));
+            assert_eq!(Command::Help(None), parse("help".to_string()));
+            assert_eq!(
+                Command::Help(Some("transfer".to_string())),
+                parse("help transfer all".to_string())
+            );
+        }
+
+        #[test]
+        fn show() {
+            assert_eq!(
+                Command::ShowDepartments,
+                parse("show departments".to_string())
+            );
+        }
+
+        #[test]
+        fn list() {
+            assert_eq!(Command::ListEmployees, parse("list employees".to_string()));
+        }
+
+        #[test]
+        fn assign() {
+            assert_eq!(
+                Command::AssignEmployeeToDepartment(
+                    "Tidley Widley".to_string(),
+                    "Benches".to_string()
+                ),
+                parse("assign Tidley Widley to Benches".to_string())
+            );
+        }
+
+        #[test]
+        fn transfer() {
+            assert_eq!(
+                Command::TransferEmployeeBetweenDepartments(
+                    "eyes".to_string(),
+                    "left".to_string(),
+                    "right".to_string()
+                ),
+                parse("transfer eyes from left to right".to_string())
+            );
+        }
+
+        #[test]
+        fn pull() {
+            assert_eq!(
+                Command::PullEmployeeFromDepartment(
+                    "a trampoline".to_string(),
+                    "stock".to_string()
+                ),
+                parse("pull a trampoline from stock".to_string())
+            );
+        }
+
+        #[test]
+        fn form() {
+            assert_eq!(
+                Command::FormDepartment("sentences".to_string()),
+                parse("form sentences".to_string())
+            );
+        }
+
+        #[test]
+        fn dissolve() {
+            assert_eq!(
+                Command::DissolveDepartment("sugah".to_string()),
+                parse("dissolve sugah".to_string())
+            );
+        }
+
+        #[test]
+        fn other_query() {
+            assert_eq!(
+                Command::InvalidCommandErr("dance".to_string()),
+                parse("dance".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_assign {
+        use super::{parse, Command};
+
+        #[test]
+        fn employee_name_and_department_triggers_assign() {
+            assert_eq!(
+                Command::AssignEmployeeToDepartment(
+                    "Flying Tomato".to_string(),
+                    "Comedian".to_string()
+                ),
+                parse("assign Flying Tomato to Comedian".to_string())
+            );
+
+            assert_eq!(
+                Command::AssignEmployeeToDepartment("Steve".to_string(), "Patrol".to_string()),
+                parse("assign Steve to Patrol".to_string())
+            );
+        }
+
+        #[test]
+        fn no_expression_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"assign\" must be followed by {employee}".to_string()),
+                parse("assign".to_string())
+            );
+        }
+
+        #[test]
+        fn no_employee_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"assign to Nowhere\" must be followed by \"to\"".to_string()),
+                parse("assign to Nowhere".to_string())
+            );
+        }
+
+        #[test]
+        fn no_department_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"assign Knight to\" must be followed by {department}".to_string()
+                ),
+                parse("assign Knight to".to_string())
+            );
+
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"assign Bobby McBobberson to\" must be followed by {department}".to_string()
+                ),
+                parse("assign Bobby McBobberson to".to_string())
+            );
+        }
+
+        #[test]
+        fn no_from_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"assign Bob Accounting\" must be followed by \"to\"".to_string()
+                ),
+                parse("assign Bob Accounting".to_string())
+            );
+
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"assign Eldritch Horrors Closet\" must be followed by \"to\"".to_string()
+                ),
+                parse("assign Eldritch Horrors Closet".to_string())
+            );
+        }
+
+        #[test]
+        fn multi_word_department_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("Unexpected \"Darkness\" after \"assign Magic Missle to The\": expected the end of the query".to_string()),
+                parse("assign Magic Missle to The Darkness".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_chart {
+        use super::{parse, Chart, ChartFormat, Command};
+
+        #[test]
+        fn format_triggers_chart() {
+            assert_eq!(
+                Command::Chart(Chart::new(ChartFormat::Mermaid)),
+                parse("chart mermaid".to_string())
+            );
+        }
+
+        #[test]
+        fn options_limit_chart_in_any_order() {
+            assert_eq!(
+                Command::Chart(Chart {
+                    max_depth: Some(2),
+                    collapse_above: Some(25),
+                    ..Chart::new(ChartFormat::Dot)
+                }),
+                parse("chart Dot collapse above 25 depth 2".to_string())
+            );
+        }
+
+        #[test]
+        fn unknown_format_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"svg\" is not a chart format: formats are DOT and Mermaid".to_string()
+                ),
+                parse("chart svg".to_string())
+            );
+        }
+
+        #[test]
+        fn malformed_options_trigger_syntax_error() {
+            [
+                ("dot depth", "\"chart dot depth\" must be followed by {levels}"),
+                ("dot depth two", "\"two\" is not a whole number"),
+                (
+                    "dot depth 1 depth 2",
+                    "Unexpected \"depth\" after \"chart dot depth 1\": expected \"collapse\" or the end of the query",
+                ),
+                (
+                    "dot collapse 3",
+                    "Unexpected \"3\" after \"chart dot collapse\": expected \"above\"",
+                ),
+            ]
+            .iter()
+            .for_each(|(query_fragment, syntax_error)| {
+                assert_eq!(
+                    Command::SyntaxErr(syntax_error.to_string()),
+                    parse(format!("chart {}", query_fragment))
+                );
+            });
+        }
+    }
+
+    mod fn_parse_define {
+        use super::{parse, Alias, AliasKind, Command};
+
+        #[test]
+        fn alias_triggers_define() {
+            assert_eq!(
+                Command::Define(Alias::new(
+                    "hire",
+                    AliasKind::Alias,
+                    vec!["assign $1 to $2".to_string()]
+                )),
+                parse("define alias hire as assign $1 to $2".to_string())
+            );
+        }
+
+        #[test]
+        fn macro_splits_statements() {
+            assert_eq!(
+                Command::Define(Alias::new(
+                    "onboard",
+                    AliasKind::Macro,
+                    vec![
+                        "assign $1 to $2".to_string(),
+                        "set manager of $1 in $2 to $3".to_string()
+                    ]
+                )),
+                parse(
+                    "define macro onboard as assign $1 to $2; set manager of $1 in $2 to $3;"
+                        .to_string()
+                )
+            );
+        }
+
+        #[test]
+        fn built_in_name_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"Assign\" is already a command, and can't be redefined".to_string()
+                ),
+                parse("define alias Assign as list employees".to_string())
+            );
+        }
+
+        #[test]
+        fn unknown_kind_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"function\" can't be defined: define an alias or a macro".to_string()
+                ),
+                parse("define function hire as assign $1 to $2".to_string())
+            );
+        }
+
+        #[test]
+        fn no_query_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"define alias hire as\" must be followed by {query}".to_string()
+                ),
+                parse("define alias hire as".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_with {
+        use super::{parse_with, Alias, AliasKind, Aliases, Command, Names};
+
+        fn aliases() -> Aliases {
+            let mut aliases = Aliases::new(Names::default());
+            aliases.define(Alias::new(
+                "hire",
+                AliasKind::Alias,
+                vec!["assign $1 to $2".to_string()],
+            ));
+            aliases.define(Alias::new(
+                "sell",
+                AliasKind::Alias,
+                vec!["hire $1, Sales".to_string()],
+            ));
+            aliases.define(Alias::new(
+                "onboard",
+                AliasKind::Macro,
+                vec!["form $2".to_string(), "hire $1, $2".to_string()],
+            ));
+            aliases
+        }
+
+        #[test]
+        fn expands_aliases_of_aliases() {
+            assert_eq!(
+                Command::AssignEmployeeToDepartment("Bob to".to_string(), "Sales".to_string()),
+                parse_with("SELL Bob to".to_string(), &aliases())
+            );
+        }
+
+        #[test]
+        fn leaves_macro_statements_unparsed() {
+            assert_eq!(
+                Command::Macro(
+                    "onboard".to_string(),
+                    vec!["form Legal".to_string(), "hire Bob, Legal".to_string()]
+                ),
+                parse_with("onboard Bob, Legal".to_string(), &aliases())
+            );
+        }
+
+        #[test]
+        fn parses_other_queries_as_commands() {
+            assert_eq!(
+                Command::ListEmployees,
+                parse_with("list employees".to_string(), &aliases())
+            );
+        }
+
+        #[test]
+        fn cycle_triggers_syntax_error() {
+            let mut aliases = aliases();
+            aliases.define(Alias::new(
+                "again",
+                AliasKind::Alias,
+                vec!["again".to_string()],
+            ));
+
+            assert_eq!(
+                Command::SyntaxErr("Alias \"again\" expands into itself".to_string()),
+                parse_with("again".to_string(), &aliases)
+            );
+        }
+    }
+
+    mod fn_parse_diff {
+        use super::{parse, Command};
+
+        #[test]
+        fn file_triggers_diff() {
+            assert_eq!(
+                Command::Diff("org chart.json".to_string()),
+                parse("diff org chart.json".to_string())
+            );
+        }
+
+        #[test]
+        fn no_file_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"diff\" must be followed by {file}".to_string()),
+                parse("diff".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_dissolve {
+        use super::{parse, Command};
+
+        #[test]
+        fn department_name_triggers_dissolve() {
+            assert_eq!(
+                Command::DissolveDepartment("Research".to_string()),
+                parse("dissolve Research".to_string())
+            );
+        }
+
+        #[test]
+        fn empty_name_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"dissolve\" must be followed by {department}".to_string()),
+                parse("dissolve".to_string())
+            );
+        }
+
+        #[test]
+        fn multi_word_department_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"Testing\" after \"dissolve Flight\": expected the end of the query".to_string()
+                ),
+                parse("dissolve Flight Testing".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_export {
+        use super::{parse, Command, ExportFormat};
+
+        #[test]
+        fn format_and_file_trigger_export() {
+            assert_eq!(
+                Command::Export(ExportFormat::Json, None, "org chart.json".to_string()),
+                parse("export json to org chart.json".to_string())
+            );
+        }
+
+        #[test]
+        fn department_limits_export() {
+            assert_eq!(
+                Command::Export(
+                    ExportFormat::Csv,
+                    Some("Sales".to_string()),
+                    "sales.csv".to_string()
+                ),
+                parse("export Csv of Sales to sales.csv".to_string())
+            );
+        }
+
+        #[test]
+        fn unknown_format_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"xml\" is not an export format: formats are JSON, CSV and YAML".to_string()
+                ),
+                parse("export xml to org.xml".to_string())
+            );
+        }
+
+        #[test]
+        fn no_file_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"export yaml of Sales to\" must be followed by {file}".to_string()
+                ),
+                parse("export yaml of Sales to".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_find {
+        use super::{parse, Command};
+
+        #[test]
+        fn employee_name_triggers_find() {
+            assert_eq!(
+                Command::FindEmployee("Kirk Short".to_string()),
+                parse("find Kirk Short".to_string())
+            );
+        }
+
+        #[test]
+        fn empty_name_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"find\" must be followed by {employee}".to_string()),
+                parse("find".to_string())
+            );
+        }
+
+        #[test]
+        fn where_is_triggers_find() {
+            assert_eq!(
+                Command::FindEmployee("Kirk Short".to_string()),
+                parse("where is Kirk Short".to_string())
+            );
+        }
+
+        #[test]
+        fn where_without_is_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"Kirk\" after \"where\": expected \"is\"".to_string()
+                ),
+                parse("where Kirk Short".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_report {
+        use super::{parse, Command, Date};
+
+        #[test]
+        fn headcount_triggers_report() {
+            assert_eq!(
+                Command::ReportHeadcount(None, None),
+                parse("report Headcount".to_string())
+            );
+        }
+
+        #[test]
+        fn dates_limit_report_period() {
+            assert_eq!(
+                Command::ReportHeadcount(Date::new(2026, 1, 1), Date::new(2026, 3, 31)),
+                parse("report headcount from 2026-01-01 to 2026-03-31".to_string())
+            );
+            assert_eq!(
+                Command::ReportHeadcount(None, Date::new(2026, 3, 31)),
+                parse("report headcount to 2026-03-31".to_string())
+            );
+        }
+
+        #[test]
+        fn malformed_dates_trigger_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"March\" is not a YYYY-MM-DD date".to_string()),
+                parse("report headcount from March".to_string())
+            );
+            assert_eq!(
+                Command::SyntaxErr("\"from\" date must not be after \"to\" date".to_string()),
+                parse("report headcount from 2026-03-31 to 2026-01-01".to_string())
+            );
+        }
+
+        #[test]
+        fn unknown_report_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"salaries\" after \"report\": expected \"headcount\"".to_string()
+                ),
+                parse("report salaries".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_run {
+        use super::{parse, Command, ErrorPolicy};
+
+        #[test]
+        fn file_triggers_run() {
+            assert_eq!(
+                Command::Run("setup script.sql".to_string(), ErrorPolicy::Continue),
+                parse("run setup script.sql".to_string())
+            );
+        }
+
+        #[test]
+        fn policy_can_stop_run_on_error() {
+            assert_eq!(
+                Command::Run("setup.sql".to_string(), ErrorPolicy::Stop),
+                parse("run setup.sql on error stop".to_string())
+            );
+        }
+
+        #[test]
+        fn unknown_policy_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"retry\" is not an error policy: policies are continue and stop".to_string()
+                ),
+                parse("run setup.sql on error retry".to_string())
+            );
+        }
+
+        #[test]
+        fn no_file_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"run\" must be followed by {file}".to_string()),
+                parse("run".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_search {
+        use super::{parse, Command};
+
+        #[test]
+        fn text_triggers_search() {
+            assert_eq!(
+                Command::Search("the Calderon in logistics".to_string()),
+                parse("search the Calderon in logistics".to_string())
+            );
+        }
+
+        #[test]
+        fn empty_text_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"search\" must be followed by {text}".to_string()),
+                parse("search".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_form {
+        use super::{parse, Command};
+
+        #[test]
+        fn department_name_triggers_form() {
+            assert_eq!(
+                Command::FormDepartment("Bootlegging".to_string()),
+                parse("form Bootlegging".to_string())
+            );
+        }
+
+        #[test]
+        fn empty_name_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("\"form\" must be followed by {department}".to_string()),
+                parse("form".to_string())
+            );
+        }
+
+        #[test]
+        fn under_parent_triggers_form_under() {
+            assert_eq!(
+                Command::FormDepartmentUnder("Team".to_string(), "Division".to_string()),
+                parse("form Team under Division".to_string())
+            );
+        }
+
+        #[test]
+        fn under_without_parent_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"form Team under\" must be followed by {department}".to_string()
+                ),
+                parse("form Team under".to_string())
+            );
+        }
+
+        #[test]
+        fn multi_word_department_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"Wheeling\" after \"form Cheese\": expected \"under\" or the end of the query".to_string()
+                ),
+                parse("form Cheese Wheeling".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_clear {
+        use super::{parse, Command, DepartmentField};
+
+        #[test]
+        fn manager_of_employee_triggers_clear_manager() {
+            assert_eq!(
+                Command::ClearManager("Kirk Short".to_string(), Some("Accounting".to_string())),
+                parse("clear manager of Kirk Short in Accounting".to_string())
+            );
+        }
+
+        #[test]
+        fn department_field_triggers_clear_department_field() {
+            assert_eq!(
+                Command::ClearDepartmentField("Sales".to_string(), DepartmentField::CostCenter),
+                parse("clear cost center of Sales".to_string())
+            );
+        }
+
+        #[test]
+        fn other_field_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"Short\" after \"clear desk of Kirk\": expected the end of the query".to_string()
+                ),
+                parse("clear desk of Kirk Short".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_employee_in_department {
+        use super::{parse, Command};
+
+        #[test]
+        fn name_without_department() {
+            assert_eq!(
+                Command::ListReports("Kirk Short".to_string(), None),
+                parse("list reports of Kirk Short".to_string())
+            );
+        }
+
+        #[test]
+        fn name_in_department() {
+            assert_eq!(
+                Command::ListReports("Kirk Short".to_string(), Some("Sales".to_string())),
+                parse("list reports of Kirk Short in Sales".to_string())
+            );
+        }
+
+        #[test]
+        fn name_holding_in() {
+            assert_eq!(
+                Command::ListReports("Kirk in Short".to_string(), Some("Sales".to_string())),
+                parse("list reports of Kirk in Short in Sales".to_string())
+            );
+            assert_eq!(
+                Command::ListReports("Kirk in Short Sales".to_string(), None),
+                parse("list reports of Kirk in Short Sales".to_string())
+            );
+        }
+
+        #[test]
+        fn empty_name() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"list reports of\" must be followed by {employee}".to_string()
+                ),
+                parse("list reports of".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_grant {
+        use super::{parse, Command, Role};
+
+        #[test]
+        fn role_and_user_trigger_grant() {
+            assert_eq!(
+                Command::GrantRole("Hal".to_string(), Role::Editor, None),
+                parse("grant editor to Hal".to_string())
+            );
+        }
+
+        #[test]
+        fn department_scopes_grant() {
+            assert_eq!(
+                Command::GrantRole("Hal".to_string(), Role::Viewer, Some("Sales".to_string())),
+                parse("grant Viewer to Hal on Sales".to_string())
+            );
+        }
+
+        #[test]
+        fn unknown_role_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"Intern\" is not a role: roles are Viewer, Editor and Admin".to_string()
+                ),
+                parse("grant Intern to Hal".to_string())
+            );
+        }
+
+        #[test]
+        fn multi_word_user_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("Unexpected \"Jordan\" after \"grant Admin to Hal\": expected \"on\" or the end of the query".to_string()),
+                parse("grant Admin to Hal Jordan".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_rename {
+        use super::{parse, Command};
+
+        #[test]
+        fn department_triggers_rename_department() {
+            assert_eq!(
+                Command::RenameDepartment("Sales".to_string(), "Revenue".to_string()),
+                parse("rename department Sales to Revenue".to_string())
+            );
+        }
+
+        #[test]
+        fn department_synonyms_trigger_rename_department() {
+            ["dept", "Depts", "DEPARTMENTS"].iter().for_each(|keyword| {
+                assert_eq!(
+                    Command::RenameDepartment("Sales".to_string(), "Revenue".to_string()),
+                    parse(format!("rename {} Sales to Revenue", keyword))
+                );
+            });
+        }
+
+        #[test]
+        fn employee_in_department_triggers_rename_employee() {
+            assert_eq!(
+                Command::RenameEmployee(
+                    "Anil Mcgregor".to_string(),
+                    Some("Accounting".to_string()),
+                    "Anil McGregor".to_string()
+                ),
+                parse("rename employee Anil Mcgregor in Accounting to Anil McGregor".to_string())
+            );
+        }
+
+        #[test]
+        fn employee_without_department_triggers_rename_employee() {
+            assert_eq!(
+                Command::RenameEmployee("Jon".to_string(), None, "John".to_string()),
+                parse("rename employee Jon to John".to_string())
+            );
+        }
+
+        #[test]
+        fn multi_word_department_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"Sales\" after \"rename department Sales to Big\": expected the end of the query".to_string()
+                ),
+                parse("rename department Sales to Big Sales".to_string())
+            );
+        }
+
+        #[test]
+        fn other_target_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"user\" after \"rename\": expected \"department\" or \"employee\""
+                        .to_string()
+                ),
+                parse("rename user Hal to Dave".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_revoke {
+        use super::{parse, Command, Role};
+
+        #[test]
+        fn role_and_user_trigger_revoke() {
+            assert_eq!(
+                Command::RevokeRole("Hal".to_string(), Role::Admin, Some("Sales".to_string())),
+                parse("revoke admin from Hal on Sales".to_string())
+            );
+        }
+
+        #[test]
+        fn wrong_user_op_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "Unexpected \"to\" after \"revoke admin\": expected \"from\"".to_string()
+                ),
+                parse("revoke admin to Hal".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_list {
+        use super::{parse, Command, Date};
+
+        #[test]
+        fn employees_triggers_list_employees() {
+            assert_eq!(Command::ListEmployees, parse("list employees".to_string()));
         }
 
         #[test]
-        fn show() {
+        fn employees_by_dept_triggers_list_employees_by_dept() {
             assert_eq!(
-                Command::ShowDepartments,
-                parse("show departments".to_string())
+                Command::ListEmployeesByDepartment,
+                parse("list employees by department".to_string())
             );
         }
 
         #[test]
-        fn list() {
-            assert_eq!(Command::ListEmployees, parse("list employees".to_string()));
+        fn employees_by_dept_synonym_triggers_list_employees_by_dept() {
+            assert_eq!(
+                Command::ListEmployeesByDepartment,
+                parse("list employee by dept".to_string())
+            );
         }
 
         #[test]
-        fn assign() {
+        fn employees_in_dept_triggers_list_employees_in_dept() {
             assert_eq!(
-                Command::AssignEmployeeToDepartment(
-                    "Tidley Widley".to_string(),
-                    "Benches".to_string()
-                ),
-                parse("assign Tidley Widley to Benches".to_string())
+                Command::ListEmployeesInDepartment("Logistics".to_string()),
+                parse("list employees in Logistics".to_string())
             );
         }
 
         #[test]
-        fn transfer() {
+        fn reports_of_employee_triggers_list_reports() {
             assert_eq!(
-                Command::TransferEmployeeBetweenDepartments(
-                    "eyes".to_string(),
-                    "left".to_string(),
-                    "right".to_string()
-                ),
-                parse("transfer eyes from left to right".to_string())
+                Command::ListReports("Liyah Meadows".to_string(), None),
+                parse("list reports of Liyah Meadows".to_string())
             );
         }
 
         #[test]
-        fn pull() {
+        fn all_reports_of_employee_triggers_list_all_reports() {
             assert_eq!(
-                Command::PullEmployeeFromDepartment(
-                    "a trampoline".to_string(),
-                    "stock".to_string()
+                Command::ListAllReports(
+                    "Liyah Meadows".to_string(),
+                    Some("Accounting".to_string())
                 ),
-                parse("pull a trampoline from stock".to_string())
+                parse("list all reports of Liyah Meadows in Accounting".to_string())
             );
         }
 
         #[test]
-        fn form() {
+        fn employees_under_dept_triggers_list_employees_under_dept() {
             assert_eq!(
-                Command::FormDepartment("sentences".to_string()),
-                parse("form sentences".to_string())
+                Command::ListEmployeesUnderDepartment("Division".to_string()),
+                parse("list employees under Division".to_string())
             );
         }
 
         #[test]
-        fn dissolve() {
+        fn no_expression_triggers_syntax_error() {
             assert_eq!(
-                Command::DissolveDepartment("sugah".to_string()),
-                parse("dissolve sugah".to_string())
+                Command::SyntaxErr(
+                    "\"list\" must be followed by \"employees\", \"reports\" or \"all\""
+                        .to_string()
+                ),
+                parse("list".to_string())
             );
         }
 
         #[test]
-        fn other_query() {
+        fn other_list_triggers_syntax_error() {
             assert_eq!(
-                Command::InvalidCommandErr("dance".to_string()),
-                parse("dance".to_string())
+                Command::SyntaxErr("Unexpected \"Pizzas\" after \"list\": expected \"employees\", \"reports\" or \"all\"".to_string()),
+                parse("list Pizzas".to_string())
             );
         }
-    }
-
-    mod fn_parse_assign {
-        use super::{parse_assign, Command};
 
         #[test]
-        fn employee_name_and_department_triggers_assign() {
-            let query_fragment = "Flying Tomato to Comedian";
-            let tokens = query_fragment.split_whitespace();
-
+        fn employees_with_other_group_op_triggers_syntax_error() {
             assert_eq!(
-                Command::AssignEmployeeToDepartment(
-                    "Flying Tomato".to_string(),
-                    "Comedian".to_string()
+                Command::SyntaxErr(
+                    "Unexpected \"of\" after \"list Employees\": expected \"as\", \"by\", \"in\", \"under\" or the end of the query".to_string()
                 ),
-                parse_assign(tokens)
+                parse("list Employees of".to_string())
             );
+        }
 
-            let query_fragment = "Steve to Patrol";
-            let tokens = query_fragment.split_whitespace();
-
+        #[test]
+        fn employees_with_no_group_by_list_triggers_syntax_error() {
             assert_eq!(
-                Command::AssignEmployeeToDepartment("Steve".to_string(), "Patrol".to_string()),
-                parse_assign(tokens)
+                Command::SyntaxErr(
+                    "\"list Employees by\" must be followed by \"department\"".to_string()
+                ),
+                parse("list Employees by".to_string())
             );
         }
 
         #[test]
-        fn no_expression_triggers_syntax_error() {
-            let query_fragment = "";
-            let tokens = query_fragment.split_whitespace();
-
+        fn employees_with_other_group_by_list_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("\"Assign\" command must specify an employee to assign and a department to assign to".to_string()),
-                parse_assign(tokens)
+                Command::SyntaxErr(
+                    "Unexpected \"Performance\" after \"list Employees by\": expected \"department\"".to_string()
+                ),
+                parse("list Employees by Performance".to_string())
             );
         }
 
         #[test]
-        fn no_employee_triggers_syntax_error() {
-            let query_fragment = "to Nowhere";
-            let tokens = query_fragment.split_whitespace();
-
+        fn employees_with_multi_word_group_by_list_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("\"Assign\" command must specify an employee to assign and a department to assign to".to_string()),
-                parse_assign(tokens)
+                Command::SyntaxErr(
+                    "Unexpected \"Manager\" after \"list Employees by Department\": expected \"as\" or the end of the query".to_string()
+                ),
+                parse("list Employees by Department Manager".to_string())
             );
         }
 
         #[test]
-        fn no_department_triggers_syntax_error() {
-            let query_fragment = "Knight to";
-            let tokens = query_fragment.split_whitespace();
-
+        fn employees_in_dept_no_dept_name_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("\"Assign\" command must specify an employee to assign and a department to assign to".to_string()),
-                parse_assign(tokens)
+                Command::SyntaxErr(
+                    "\"list Employees in\" must be followed by {department}".to_string()
+                ),
+                parse("list Employees in".to_string())
             );
+        }
 
-            let query_fragment = "Bobby McBobberson to";
-            let tokens = query_fragment.split_whitespace();
-
+        #[test]
+        fn employees_in_multi_word_dept_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("\"Assign\" command must specify an employee to assign and a department to assign to".to_string()),
-                parse_assign(tokens)
+                Command::SyntaxErr(
+                    "Unexpected \"Detectives\" after \"list Employees in Gumshoe\": expected \"as\" or the end of the query".to_string()
+                ),
+                parse("list Employees in Gumshoe Detectives".to_string())
             );
         }
 
         #[test]
-        fn no_from_triggers_syntax_error() {
-            let query_fragment = "Bob Accounting";
-            let tokens = query_fragment.split_whitespace();
+        fn as_of_date_triggers_list_as_of() {
+            let date = Date::new(2026, 3, 1).unwrap();
 
             assert_eq!(
-                Command::SyntaxErr("\"Assign\" command must specify an employee to assign and a department to assign to".to_string()),
-                parse_assign(tokens)
+                Command::ListEmployeesAsOf(date),
+                parse("list employees as of 2026-03-01".to_string())
             );
-
-            let query_fragment = "Eldritch Horrors Closet";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Assign\" command must specify an employee to assign and a department to assign to".to_string()),
-                parse_assign(tokens)
+                Command::ListEmployeesByDepartmentAsOf(date),
+                parse("list employees by department AS OF 2026-03-01".to_string())
             );
-        }
-
-        #[test]
-        fn multi_word_department_triggers_syntax_error() {
-            let query_fragment = "Magic Missle to The Darkness";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Assign\" command must specify an employee to assign and a department to assign to".to_string()),
-                parse_assign(tokens)
+                Command::ListEmployeesInDepartmentAsOf("Sales".to_string(), date),
+                parse("list employees in Sales as of 2026-03-01".to_string())
             );
         }
-    }
-
-    mod fn_parse_dissolve {
-        use super::{parse_dissolve, Command};
 
         #[test]
-        fn department_name_triggers_dissolve() {
-            let query_fragment = "Research";
-            let tokens = query_fragment.split_whitespace();
-
+        fn as_of_other_list_triggers_syntax_error() {
             assert_eq!(
-                Command::DissolveDepartment("Research".to_string()),
-                parse_dissolve(tokens)
+                Command::SyntaxErr("Unexpected \"as\" after \"list employees under Sales\": expected the end of the query".to_string()),
+                parse("list employees under Sales as of 2026-03-01".to_string())
             );
         }
+    }
 
-        #[test]
-        fn empty_name_triggers_syntax_error() {
-            let query_fragment = "";
-            let tokens = query_fragment.split_whitespace();
+    mod fn_parse_merge {
+        use super::{parse, Command, ConflictPolicy};
 
+        #[test]
+        fn department_into_department_triggers_merge() {
             assert_eq!(
-                Command::SyntaxErr(
-                    "\"Dissolve\" command must specify a department to dissolve".to_string()
+                Command::MergeDepartments(
+                    "Design".to_string(),
+                    "Engineering".to_string(),
+                    ConflictPolicy::Rename
                 ),
-                parse_dissolve(tokens)
+                parse("merge department Design into Engineering on conflict rename".to_string())
             );
         }
 
         #[test]
-        fn multi_word_department_triggers_syntax_error() {
-            let query_fragment = "Flight Testing";
-            let tokens = query_fragment.split_whitespace();
-
+        fn unknown_policy_triggers_syntax_error() {
             assert_eq!(
                 Command::SyntaxErr(
-                    "Due to company policy, department names can only be one word long".to_string()
+                    "\"panic\" is not a conflict policy: policies are fail, skip and rename"
+                        .to_string()
                 ),
-                parse_dissolve(tokens)
+                parse("merge Design into Engineering on conflict panic".to_string())
             );
         }
     }
 
-    mod fn_parse_form {
-        use super::{parse_form, Command};
+    mod fn_parse_move {
+        use super::{parse, Command};
 
         #[test]
-        fn department_name_triggers_form() {
-            let query_fragment = "Bootlegging";
-            let tokens = query_fragment.split_whitespace();
-
+        fn under_parent_triggers_move() {
             assert_eq!(
-                Command::FormDepartment("Bootlegging".to_string()),
-                parse_form(tokens)
+                Command::MoveDepartment("Team".to_string(), Some("Division".to_string())),
+                parse("move Team under Division".to_string())
             );
         }
 
         #[test]
-        fn empty_name_triggers_syntax_error() {
-            let query_fragment = "";
-            let tokens = query_fragment.split_whitespace();
-
+        fn to_top_triggers_move_to_top_level() {
             assert_eq!(
-                Command::SyntaxErr(
-                    "\"Form\" command must specify a department to form".to_string()
-                ),
-                parse_form(tokens)
+                Command::MoveDepartment("Team".to_string(), None),
+                parse("move Team to top".to_string())
             );
         }
 
         #[test]
-        fn multi_word_department_triggers_syntax_error() {
-            let query_fragment = "Cheese Wheeling";
-            let tokens = query_fragment.split_whitespace();
-
+        fn other_target_triggers_syntax_error() {
             assert_eq!(
                 Command::SyntaxErr(
-                    "Due to company policy, department names can only be one word long".to_string()
+                    "Unexpected \"Division\" after \"move Team to\": expected \"top\"".to_string()
                 ),
-                parse_form(tokens)
+                parse("move Team to Division".to_string())
             );
         }
     }
 
-    mod fn_parse_list {
-        use super::{parse_list, Command};
-
-        #[test]
-        fn employees_triggers_list_employees() {
-            let query_fragment = "employees";
-            let tokens = query_fragment.split_whitespace();
-
-            assert_eq!(Command::ListEmployees, parse_list(tokens));
-        }
+    mod fn_parse_pull {
+        use super::{parse, Command};
 
         #[test]
-        fn employees_by_dept_triggers_list_employees_by_dept() {
-            let query_fragment = "employees by department";
-            let tokens = query_fragment.split_whitespace();
-
-            assert_eq!(Command::ListEmployeesByDepartment, parse_list(tokens));
+        fn all_from_department_triggers_pull_all() {
+            assert_eq!(
+                Command::PullAllFromDepartment("Archives".to_string()),
+                parse("pull all from Archives".to_string())
+            );
         }
 
         #[test]
-        fn employees_in_dept_triggers_list_employees_in_dept() {
-            let query_fragment = "employees in Logistics";
-            let tokens = query_fragment.split_whitespace();
+        fn employee_name_and_department_triggers_pull() {
+            assert_eq!(
+                Command::PullEmployeeFromDepartment(
+                    "Ripe Potato".to_string(),
+                    "Archives".to_string()
+                ),
+                parse("pull Ripe Potato from Archives".to_string())
+            );
 
             assert_eq!(
-                Command::ListEmployeesInDepartment("Logistics".to_string()),
-                parse_list(tokens)
+                Command::PullEmployeeFromDepartment("Steve".to_string(), "Patrol".to_string()),
+                parse("pull Steve from Patrol".to_string())
             );
         }
 
         #[test]
         fn no_expression_triggers_syntax_error() {
-            let query_fragment = "";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"List\" command must specify a list name".to_string()),
-                parse_list(tokens)
+                Command::SyntaxErr(
+                    "\"pull\" must be followed by \"all\" or {employee}".to_string()
+                ),
+                parse("pull".to_string())
             );
         }
 
         #[test]
-        fn other_list_triggers_syntax_error() {
-            let query_fragment = "Pizzas";
-            let tokens = query_fragment.split_whitespace();
-
+        fn no_employee_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("Cannot list \"Pizzas\": list does not exist".to_string()),
-                parse_list(tokens)
+                Command::SyntaxErr(
+                    "\"pull from Nothing\" must be followed by \"from\"".to_string()
+                ),
+                parse("pull from Nothing".to_string())
             );
         }
 
         #[test]
-        fn employees_with_other_group_op_triggers_syntax_error() {
-            let query_fragment = "Employees of";
-            let tokens = query_fragment.split_whitespace();
+        fn no_department_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"pull Jones from\" must be followed by {department}".to_string()
+                ),
+                parse("pull Jones from".to_string())
+            );
 
             assert_eq!(
                 Command::SyntaxErr(
-                    "Unexpected token \"of\" after list name \"Employees\"".to_string()
+                    "\"pull Bobby McBobberson from\" must be followed by {department}".to_string()
                 ),
-                parse_list(tokens)
+                parse("pull Bobby McBobberson from".to_string())
             );
         }
 
         #[test]
-        fn employees_with_no_group_by_list_triggers_syntax_error() {
-            let query_fragment = "Employees by";
-            let tokens = query_fragment.split_whitespace();
+        fn no_from_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr(
+                    "\"pull Bob Accounting\" must be followed by \"from\"".to_string()
+                ),
+                parse("pull Bob Accounting".to_string())
+            );
 
             assert_eq!(
                 Command::SyntaxErr(
-                    "\"List employees by\" must specify a group by field".to_string()
+                    "\"pull Eldritch Horrors Closet\" must be followed by \"from\"".to_string()
                 ),
-                parse_list(tokens)
+                parse("pull Eldritch Horrors Closet".to_string())
             );
         }
 
         #[test]
-        fn employees_with_other_group_by_list_triggers_syntax_error() {
-            let query_fragment = "Employees by Performance";
-            let tokens = query_fragment.split_whitespace();
+        fn multi_word_department_triggers_syntax_error() {
+            assert_eq!(
+                Command::SyntaxErr("Unexpected \"Darkness\" after \"pull Tony from The\": expected the end of the query".to_string()),
+                parse("pull Tony from The Darkness".to_string())
+            );
+        }
+    }
+
+    mod fn_parse_set {
+        use super::{parse, Command, DepartmentField};
 
+        #[test]
+        fn department_field_triggers_set_department_field() {
             assert_eq!(
-                Command::SyntaxErr(
-                    "\"Performance\" is not a field employees can by grouped by".to_string()
+                Command::SetDepartmentField(
+                    "Sales".to_string(),
+                    DepartmentField::Description,
+                    "Sells things to people".to_string()
                 ),
-                parse_list(tokens)
+                parse("set description of Sales to Sells things to people".to_string())
             );
         }
 
         #[test]
-        fn employees_with_multi_word_group_by_list_triggers_syntax_error() {
-            let query_fragment = "Employees by Department Manager";
-            let tokens = query_fragment.split_whitespace();
-
+        fn invalid_created_date_triggers_syntax_error() {
             assert_eq!(
                 Command::SyntaxErr(
-                    "Unexpected token \"Manager\" after group by field \"Department\"".to_string()
+                    "\"yesterday\" is not a date: dates are written YYYY-MM-DD".to_string()
                 ),
-                parse_list(tokens)
+                parse("set created of Sales to yesterday".to_string())
             );
         }
 
         #[test]
-        fn employees_in_dept_no_dept_name_triggers_syntax_error() {
-            let query_fragment = "Employees in";
-            let tokens = query_fragment.split_whitespace();
-
+        fn unknown_field_triggers_syntax_error() {
             assert_eq!(
                 Command::SyntaxErr(
-                    "Command \"List employees in\" must specify a department name".to_string()
+                    "\"mascot\" is not a field that can be set: fields are manager, head, cost center, location, description and created".to_string()
                 ),
-                parse_list(tokens)
+                parse("set mascot of Sales to Otter".to_string())
             );
         }
 
         #[test]
-        fn employees_in_multi_word_dept_triggers_syntax_error() {
-            let query_fragment = "Employees in Gumshoe Detectives";
-            let tokens = query_fragment.split_whitespace();
+        fn manager_of_employee_to_employee_triggers_set_manager() {
+            assert_eq!(
+                Command::SetManager(
+                    "Kirk Short".to_string(),
+                    None,
+                    "Liyah Meadows".to_string(),
+                    Some("Accounting".to_string())
+                ),
+                parse("set manager of Kirk Short to Liyah Meadows in Accounting".to_string())
+            );
+        }
 
+        #[test]
+        fn missing_manager_triggers_syntax_error() {
             assert_eq!(
                 Command::SyntaxErr(
-                    "Unexpected token \"Detectives\" after department name \"Gumshoe\"".to_string()
+                    "\"set manager of Kirk Short to\" must be followed by {employee}".to_string()
                 ),
-                parse_list(tokens)
+                parse("set manager of Kirk Short to".to_string())
             );
         }
     }
 
-    mod fn_parse_pull {
-        use super::{parse_pull, Command};
+    mod fn_parse_show {
+        use super::{parse, Command};
 
         #[test]
-        fn employee_name_and_department_triggers_pull() {
-            let query_fragment = "Ripe Potato from Archives";
-            let tokens = query_fragment.split_whitespace();
-
+        fn departments_triggers_show() {
             assert_eq!(
-                Command::PullEmployeeFromDepartment(
-                    "Ripe Potato".to_string(),
-                    "Archives".to_string()
-                ),
-                parse_pull(tokens)
+                Command::ShowDepartments,
+                parse("show departments".to_string())
             );
+        }
 
-            let query_fragment = "Steve from Patrol";
-            let tokens = query_fragment.split_whitespace();
-
+        #[test]
+        fn chain_of_command_triggers_show_chain() {
             assert_eq!(
-                Command::PullEmployeeFromDepartment("Steve".to_string(), "Patrol".to_string()),
-                parse_pull(tokens)
+                Command::ShowManagementChain("Kirk Short".to_string(), None),
+                parse("show chain of command for Kirk Short".to_string())
             );
         }
 
         #[test]
-        fn no_expression_triggers_syntax_error() {
-            let query_fragment = "";
-            let tokens = query_fragment.split_whitespace();
-
+        fn departments_detailed_triggers_show_detailed() {
             assert_eq!(
-                Command::SyntaxErr("\"Pull\" command must specify an employee to pull and a department to pull from".to_string()),
-                parse_pull(tokens)
+                Command::ShowDepartmentsDetailed,
+                parse("show departments detailed".to_string())
             );
         }
 
         #[test]
-        fn no_employee_triggers_syntax_error() {
-            let query_fragment = "from Nothing";
-            let tokens = query_fragment.split_whitespace();
-
+        fn department_tree_triggers_show_tree() {
             assert_eq!(
-                Command::SyntaxErr("\"Pull\" command must specify an employee to pull and a department to pull from".to_string()),
-                parse_pull(tokens)
+                Command::ShowDepartmentTree,
+                parse("show department tree".to_string())
             );
         }
 
         #[test]
-        fn no_department_triggers_syntax_error() {
-            let query_fragment = "Jones from";
-            let tokens = query_fragment.split_whitespace();
+        fn users_triggers_show_users() {
+            assert_eq!(Command::ShowUsers, parse("show users".to_string()));
+        }
 
+        #[test]
+        fn no_expression_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("\"Pull\" command must specify an employee to pull and a department to pull from".to_string()),
-                parse_pull(tokens)
+                Command::SyntaxErr("\"show\" must be followed by \"departments\", \"timeline\", \"chain\" or \"users\"".to_string()),
+                parse("show".to_string())
             );
+        }
 
-            let query_fragment = "Bobby McBobberson from";
-            let tokens = query_fragment.split_whitespace();
-
+        #[test]
+        fn other_list_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("\"Pull\" command must specify an employee to pull and a department to pull from".to_string()),
-                parse_pull(tokens)
+                Command::SyntaxErr("Unexpected \"bunnies\" after \"show\": expected \"departments\", \"timeline\", \"chain\" or \"users\"".to_string()),
+                parse("show bunnies".to_string())
             );
         }
 
         #[test]
-        fn no_from_triggers_syntax_error() {
-            let query_fragment = "Bob Accounting";
-            let tokens = query_fragment.split_whitespace();
-
+        fn multi_word_list_triggers_syntax_error() {
             assert_eq!(
-                Command::SyntaxErr("\"Pull\" command must specify an employee to pull and a department to pull from".to_string()),
-                parse_pull(tokens)
+                Command::SyntaxErr(
+                    "Unexpected \"flotsam\" after \"show departments\": expected \"tree\", \"detailed\" or the end of the query".to_string()
+                ),
+                parse("show departments flotsam".to_string())
             );
+        }
 
-            let query_fragment = "Eldritch Horrors Closet";
-            let tokens = query_fragment.split_whitespace();
-
+        #[test]
+        fn timeline_of_employee_triggers_show_timeline() {
             assert_eq!(
-                Command::SyntaxErr("\"Pull\" command must specify an employee to pull and a department to pull from".to_string()),
-                parse_pull(tokens)
+                Command::ShowTimeline("Anil McGregor".to_string(), None),
+                parse("show timeline of Anil McGregor".to_string())
             );
         }
 
         #[test]
-        fn multi_word_department_triggers_syntax_error() {
-            let query_fragment = "Tony from The Darkness";
-            let tokens = query_fragment.split_whitespace();
-
+        fn timeline_of_employee_in_department_triggers_show_timeline() {
             assert_eq!(
-                Command::SyntaxErr("\"Pull\" command must specify an employee to pull and a department to pull from".to_string()),
-                parse_pull(tokens)
+                Command::ShowTimeline("Anil McGregor".to_string(), Some("Sales".to_string())),
+                parse("show timeline for Anil McGregor in Sales".to_string())
             );
         }
     }
 
-    mod fn_parse_show {
-        use super::{parse_show, Command};
+    mod fn_parse_transfer {
+        use super::{parse, Command, ConflictPolicy, Filter};
 
         #[test]
-        fn departments_triggers_show() {
-            let query_fragment = "departments";
-            let tokens = query_fragment.split_whitespace();
-
-            assert_eq!(Command::ShowDepartments, parse_show(tokens));
+        fn all_from_department_triggers_transfer_all() {
+            assert_eq!(
+                Command::TransferAllBetweenDepartments(
+                    "Design".to_string(),
+                    "Engineering".to_string(),
+                    ConflictPolicy::Fail
+                ),
+                parse("transfer all from Design to Engineering".to_string())
+            );
         }
 
         #[test]
-        fn no_expression_triggers_syntax_error() {
-            let query_fragment = "";
-            let tokens = query_fragment.split_whitespace();
-
+        fn employees_where_triggers_transfer_matching() {
             assert_eq!(
-                Command::SyntaxErr("\"Show\" command must specify a list name".to_string()),
-                parse_show(tokens)
+                Command::TransferMatchingToDepartment(
+                    Filter::parse(&["name", "contains", "to", "do"]).unwrap(),
+                    "Engineering".to_string(),
+                    ConflictPolicy::Skip
+                ),
+                parse(
+                    "transfer employees where name contains to do to Engineering on conflict skip"
+                        .to_string()
+                )
             );
         }
 
         #[test]
-        fn other_list_triggers_syntax_error() {
-            let query_fragment = "bunnies";
-            let tokens = query_fragment.split_whitespace();
-
+        fn negated_and_grouped_conditions_trigger_transfer_matching() {
             assert_eq!(
-                Command::SyntaxErr("Cannot show \"bunnies\": list does not exist".to_string()),
-                parse_show(tokens)
+                Command::TransferMatchingToDepartment(
+                    Filter::parse(&[
+                        "not", "(", "manager", "is", "Bob", "or", "dept", "is", "Sales", ")"
+                    ])
+                    .unwrap(),
+                    "Design".to_string(),
+                    ConflictPolicy::Fail
+                ),
+                parse(
+                    "transfer employees where not (manager is Bob or dept is Sales) to Design"
+                        .to_string()
+                )
             );
         }
 
         #[test]
-        fn multi_word_list_triggers_syntax_error() {
-            let query_fragment = "departments flotsam";
-            let tokens = query_fragment.split_whitespace();
-
+        fn invalid_condition_triggers_syntax_error() {
             assert_eq!(
                 Command::SyntaxErr(
-                    "Unexpected token \"flotsam\" after list name \"departments\"".to_string()
+                    "\"salary\" is not a field employees can be filtered by".to_string()
                 ),
-                parse_show(tokens)
+                parse("transfer employees where salary is high to Engineering".to_string())
             );
         }
-    }
-
-    mod fn_parse_transfer {
-        use super::{parse_transfer, Command};
 
         #[test]
         fn employee_name_and_departments_trigger_transfer() {
-            let query_fragment = "Hot Potato from Susie to Micky";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
                 Command::TransferEmployeeBetweenDepartments(
                     "Hot Potato".to_string(),
                     "Susie".to_string(),
                     "Micky".to_string()
                 ),
-                parse_transfer(tokens)
+                parse("transfer Hot Potato from Susie to Micky".to_string())
             );
 
-            let query_fragment = "Girl from Uptown to Downtown";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
                 Command::TransferEmployeeBetweenDepartments(
                     "Girl".to_string(),
                     "Uptown".to_string(),
                     "Downtown".to_string()
                 ),
-                parse_transfer(tokens)
+                parse("transfer Girl from Uptown to Downtown".to_string())
             );
         }
 
         #[test]
         fn no_expression_triggers_syntax_error() {
-            let query_fragment = "";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "\"transfer\" must be followed by \"all\", \"employees\" or {employee}"
+                        .to_string()
+                ),
+                parse("transfer".to_string())
             );
         }
 
         #[test]
         fn no_employee_triggers_syntax_error() {
-            let query_fragment = "from Nothing to Everything";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "\"transfer from Nothing to Everything\" must be followed by \"from\""
+                        .to_string()
+                ),
+                parse("transfer from Nothing to Everything".to_string())
             );
         }
 
         #[test]
         fn no_from_department_triggers_syntax_error() {
-            let query_fragment = "Flare from to Sol";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "Unexpected \"Sol\" after \"transfer Flare from to\": expected \"to\""
+                        .to_string()
+                ),
+                parse("transfer Flare from to Sol".to_string())
             );
 
-            let query_fragment = "Bobby McBobberson to Staging";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "\"transfer Bobby McBobberson to Staging\" must be followed by \"from\""
+                        .to_string()
+                ),
+                parse("transfer Bobby McBobberson to Staging".to_string())
             );
         }
 
         #[test]
         fn no_to_department_triggers_syntax_error() {
-            let query_fragment = "Bones from Grimdiana";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "\"transfer Bones from Grimdiana\" must be followed by \"to\"".to_string()
+                ),
+                parse("transfer Bones from Grimdiana".to_string())
             );
 
-            let query_fragment = "Bobby McBobberson from South to";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "\"transfer Bobby McBobberson from South to\" must be followed by {department}"
+                        .to_string()
+                ),
+                parse("transfer Bobby McBobberson from South to".to_string())
             );
         }
 
         #[test]
         fn no_from_triggers_syntax_error() {
-            let query_fragment = "Bob Accounting to Editing";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "\"transfer Bob Accounting to Editing\" must be followed by \"from\""
+                        .to_string()
+                ),
+                parse("transfer Bob Accounting to Editing".to_string())
             );
         }
 
         #[test]
         fn no_to_triggers_syntax_error() {
-            let query_fragment = "Bob from Accounting Editing";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr("Unexpected \"Editing\" after \"transfer Bob from Accounting\": expected \"to\"".to_string()),
+                parse("transfer Bob from Accounting Editing".to_string())
             );
         }
 
         #[test]
         fn multi_word_department_triggers_syntax_error() {
-            let query_fragment = "Tony from The Darkness to Light";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr(
+                    "Unexpected \"Darkness\" after \"transfer Tony from The\": expected \"to\""
+                        .to_string()
+                ),
+                parse("transfer Tony from The Darkness to Light".to_string())
             );
 
-            let query_fragment = "Tony from Dark to The Lightness";
-            let tokens = query_fragment.split_whitespace();
-
             assert_eq!(
-                Command::SyntaxErr("\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to".to_string()),
-                parse_transfer(tokens)
+                Command::SyntaxErr("Unexpected \"Lightness\" after \"transfer Tony from Dark to The\": expected the end of the query".to_string()),
+                parse("transfer Tony from Dark to The Lightness".to_string())
             );
         }
     }
@@ -0,0 +1,1175 @@
+// The hand-written parsers the grammar replaced, frozen as they were, to check that the grammar
+// parses every query they accepted into the same command
+use super::{Alias, AliasKind, Chart, ChartFormat, Command, ConflictPolicy, Date, DepartmentField};
+use super::{ErrorPolicy, ExportFormat, Role};
+use crate::database::filter::Filter;
+
+pub fn parse(command_string: String) -> Command {
+    let mut tokens = command_string.split_whitespace();
+    match tokens.next() {
+        None => Command::Empty,
+        Some(keyword) => match keyword.to_uppercase().as_str() {
+            "HELP" | "HALP" => parse_help(tokens),
+            "EXIT" | "QUIT" | "LEAVE" | "BYE" => Command::Exit,
+            "DEFINE" => parse_define(tokens),
+            "UNDEFINE" => parse_undefine(tokens),
+            "RUN" => parse_run(tokens),
+            "SHOW" => parse_show(tokens),
+            "LIST" => parse_list(tokens),
+            "FIND" => parse_find(tokens),
+            "WHERE" => parse_where(tokens),
+            "SEARCH" => parse_search(tokens),
+            "REPORT" => parse_report(tokens),
+            "FORM" => parse_form(tokens),
+            "MOVE" => parse_move(tokens),
+            "ASSIGN" => parse_assign(tokens),
+            "TRANSFER" => parse_transfer(tokens),
+            "PULL" => parse_pull(tokens),
+            "MERGE" => parse_merge(tokens),
+            "SET" => parse_set(tokens),
+            "CLEAR" => parse_clear(tokens),
+            "RENAME" => parse_rename(tokens),
+            "DISSOLVE" => parse_dissolve(tokens),
+            "GRANT" => parse_grant(tokens),
+            "REVOKE" => parse_revoke(tokens),
+            "EXPORT" => parse_export(tokens),
+            "DIFF" => parse_diff(tokens),
+            "CHART" => parse_chart(tokens),
+            _ => Command::InvalidCommandErr(String::from(keyword)),
+        },
+    }
+}
+
+// Conditions were joined by "and" and "or" alone, "and" binding tighter
+fn parse_filter(tokens: &[&str]) -> Result<Filter, String> {
+    let (mut any_of, mut all_of, mut condition) = (Vec::new(), Vec::new(), Vec::new());
+    for token in tokens {
+        match token.to_uppercase().as_str() {
+            "AND" => all_of.push(Filter::parse(&condition.split_off(0))?),
+            "OR" => {
+                all_of.push(Filter::parse(&condition.split_off(0))?);
+                any_of.push(Filter::all(all_of.split_off(0)));
+            }
+            _ => condition.push(*token),
+        }
+    }
+    all_of.push(Filter::parse(&condition)?);
+    any_of.push(Filter::all(all_of));
+    Ok(Filter::any(any_of))
+}
+
+fn parse_help<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    Command::Help(tokens.next().map(String::from))
+}
+
+fn parse_assign<'a, T: DoubleEndedIterator<Item = &'a str>>(mut tokens: T) -> Command {
+    const ASSIGN_SYNTAX_ERR: &str =
+        "\"Assign\" command must specify an employee to assign and a department to assign to";
+    match tokens.next_back() {
+        None => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
+        Some(department) => match tokens.next_back() {
+            None => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
+            Some(group_op) => match group_op.to_uppercase().as_str() {
+                "TO" => match tokens.next() {
+                    None => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
+                    Some(employee_first_name) => {
+                        let mut employee = String::from(employee_first_name);
+                        tokens.for_each(|token| {
+                            employee.push(' ');
+                            employee.push_str(token);
+                        });
+                        Command::AssignEmployeeToDepartment(employee, department.to_string())
+                    }
+                },
+                _ => Command::SyntaxErr(String::from(ASSIGN_SYNTAX_ERR)),
+            },
+        },
+    }
+}
+
+fn parse_chart<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    const CHART_SYNTAX_ERR: &str =
+        "\"Chart\" command must specify a format, optionally followed by \"depth {levels}\" and \"collapse above {headcount}\"";
+    let mut chart = match tokens.next() {
+        None => return Command::SyntaxErr(String::from(CHART_SYNTAX_ERR)),
+        Some(format_name) => match ChartFormat::parse(format_name) {
+            None => {
+                return Command::SyntaxErr(format!(
+                    "\"{}\" is not a chart format: formats are DOT and Mermaid",
+                    format_name
+                ))
+            }
+            Some(format) => Chart::new(format),
+        },
+    };
+    let tokens = tokens
+        .map(|token| token.to_uppercase())
+        .collect::<Vec<String>>();
+    let mut tokens = &tokens[..];
+    loop {
+        tokens = match tokens {
+            [] => return Command::Chart(chart),
+            [depth_op, levels, rest @ ..] if depth_op == "DEPTH" => match levels.parse() {
+                Ok(levels) if chart.max_depth.is_none() => {
+                    chart.max_depth = Some(levels);
+                    rest
+                }
+                _ => return Command::SyntaxErr(String::from(CHART_SYNTAX_ERR)),
+            },
+            [collapse_op, above_op, headcount, rest @ ..]
+                if collapse_op == "COLLAPSE" && above_op == "ABOVE" =>
+            {
+                match headcount.parse() {
+                    Ok(headcount) if chart.collapse_above.is_none() => {
+                        chart.collapse_above = Some(headcount);
+                        rest
+                    }
+                    _ => return Command::SyntaxErr(String::from(CHART_SYNTAX_ERR)),
+                }
+            }
+            _ => return Command::SyntaxErr(String::from(CHART_SYNTAX_ERR)),
+        };
+    }
+}
+
+fn parse_clear<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    const CLEAR_SYNTAX_ERR: &str =
+        "\"Clear\" command must specify \"manager of {employee}\" or \"{field} of {department}\"";
+    let tokens = tokens.collect::<Vec<&str>>();
+    let of_index = match tokens.iter().position(|token| token.to_uppercase() == "OF") {
+        None => return Command::SyntaxErr(String::from(CLEAR_SYNTAX_ERR)),
+        Some(of_index) => of_index,
+    };
+    let field_name = tokens[..of_index].join(" ");
+    let tokens = &tokens[of_index + 1..];
+    if field_name.to_uppercase() == "MANAGER" {
+        return match parse_employee_ref(tokens) {
+            None => Command::SyntaxErr(String::from(CLEAR_SYNTAX_ERR)),
+            Some((employee, department)) => Command::ClearManager(employee, department),
+        };
+    }
+    match (DepartmentField::parse(&field_name), tokens) {
+        (Some(field), [department]) => Command::ClearDepartmentField(department.to_string(), field),
+        _ => Command::SyntaxErr(String::from(CLEAR_SYNTAX_ERR)),
+    }
+}
+
+fn parse_diff<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let file = tokens.collect::<Vec<&str>>();
+    if file.is_empty() {
+        return Command::SyntaxErr(String::from(
+            "\"Diff\" command must specify an exported file to compare against",
+        ));
+    }
+    Command::Diff(file.join(" "))
+}
+
+fn parse_dissolve<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    match tokens.next() {
+        None => Command::SyntaxErr(String::from(
+            "\"Dissolve\" command must specify a department to dissolve",
+        )),
+        Some(department) => match tokens.next() {
+            Some(_) => Command::SyntaxErr(String::from(
+                "Due to company policy, department names can only be one word long",
+            )),
+            None => Command::DissolveDepartment(department.to_string()),
+        },
+    }
+}
+
+fn parse_export<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    const EXPORT_SYNTAX_ERR: &str =
+        "\"Export\" command must specify a format, optionally \"of {department}\", and \"to {file}\"";
+    let format = match tokens.next() {
+        None => return Command::SyntaxErr(String::from(EXPORT_SYNTAX_ERR)),
+        Some(format_name) => match ExportFormat::parse(format_name) {
+            None => {
+                return Command::SyntaxErr(format!(
+                    "\"{}\" is not an export format: formats are JSON, CSV and YAML",
+                    format_name
+                ))
+            }
+            Some(format) => format,
+        },
+    };
+    let tokens = tokens.collect::<Vec<&str>>();
+    let (department, tokens) = match tokens.split_first() {
+        Some((of_op, [department, tokens @ ..])) if of_op.to_uppercase() == "OF" => {
+            (Some(department.to_string()), tokens)
+        }
+        _ => (None, &tokens[..]),
+    };
+    match tokens.split_first() {
+        Some((to_op, file)) if to_op.to_uppercase() == "TO" && !file.is_empty() => {
+            Command::Export(format, department, file.join(" "))
+        }
+        _ => Command::SyntaxErr(String::from(EXPORT_SYNTAX_ERR)),
+    }
+}
+
+fn parse_find<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let employee = tokens.collect::<Vec<&str>>();
+    if employee.is_empty() {
+        return Command::SyntaxErr(String::from(
+            "\"Find\" command must specify an employee to find",
+        ));
+    }
+    Command::FindEmployee(employee.join(" "))
+}
+
+fn parse_where<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    const WHERE_SYNTAX_ERR: &str = "\"Where\" command must specify \"is {employee}\"";
+    match tokens.next() {
+        Some(is_op) if is_op.to_uppercase() == "IS" => match parse_find(tokens) {
+            Command::SyntaxErr(_) => Command::SyntaxErr(String::from(WHERE_SYNTAX_ERR)),
+            command => command,
+        },
+        _ => Command::SyntaxErr(String::from(WHERE_SYNTAX_ERR)),
+    }
+}
+
+fn parse_report<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    const REPORT_SYNTAX_ERR: &str =
+        "\"Report\" command must specify \"headcount\", optionally followed by \"from {date}\" and \"to {date}\"";
+    if tokens.next().map(|token| token.to_uppercase()) != Some(String::from("HEADCOUNT")) {
+        return Command::SyntaxErr(String::from(REPORT_SYNTAX_ERR));
+    }
+    let tokens = tokens.collect::<Vec<&str>>();
+    let (from, to) = match tokens.as_slice() {
+        [] => (None, None),
+        [from_op, from] if from_op.to_uppercase() == "FROM" => (Some(*from), None),
+        [to_op, to] if to_op.to_uppercase() == "TO" => (None, Some(*to)),
+        [from_op, from, to_op, to]
+            if from_op.to_uppercase() == "FROM" && to_op.to_uppercase() == "TO" =>
+        {
+            (Some(*from), Some(*to))
+        }
+        _ => return Command::SyntaxErr(String::from(REPORT_SYNTAX_ERR)),
+    };
+    let parse_date = |date: Option<&str>| match date {
+        None => Ok(None),
+        Some(date) => Date::parse(date)
+            .map(Some)
+            .ok_or_else(|| format!("\"{}\" is not a YYYY-MM-DD date", date)),
+    };
+    match (parse_date(from), parse_date(to)) {
+        (Err(syntax_error), _) | (_, Err(syntax_error)) => Command::SyntaxErr(syntax_error),
+        (Ok(Some(from)), Ok(Some(to))) if to < from => {
+            Command::SyntaxErr(String::from("\"from\" date must not be after \"to\" date"))
+        }
+        (Ok(from), Ok(to)) => Command::ReportHeadcount(from, to),
+    }
+}
+
+fn parse_define<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let tokens = tokens.collect::<Vec<&str>>();
+    let (kind, name, body) = match tokens.as_slice() {
+        [kind, name, as_op, body @ ..] if as_op.to_uppercase() == "AS" && !body.is_empty() => {
+            match kind.to_uppercase().as_str() {
+                "ALIAS" => (AliasKind::Alias, name, body.join(" ")),
+                "MACRO" => (AliasKind::Macro, name, body.join(" ")),
+                _ => {
+                    return Command::SyntaxErr(format!(
+                        "\"{}\" can't be defined: define an alias or a macro",
+                        kind
+                    ))
+                }
+            }
+        }
+        _ => {
+            return Command::SyntaxErr(String::from(
+                "\"Define\" command must specify an alias or macro, its name, and the query it stands for after \"as\"",
+            ))
+        }
+    };
+    if !name
+        .chars()
+        .all(|character| character.is_alphanumeric() || character == '-' || character == '_')
+    {
+        return Command::SyntaxErr(format!(
+            "\"{}\" is not a valid name: names can only hold letters, digits, \"-\" and \"_\"",
+            name
+        ));
+    }
+    if parse(name.to_string()) != Command::InvalidCommandErr(name.to_string()) {
+        return Command::SyntaxErr(format!(
+            "\"{}\" is already a command, and can't be redefined",
+            name
+        ));
+    }
+    let statements = body
+        .split(';')
+        .map(str::trim)
+        .filter(|statement| !statement.is_empty())
+        .map(String::from)
+        .collect::<Vec<String>>();
+    if statements.is_empty() {
+        return Command::SyntaxErr(format!(
+            "\"Define\" command must specify the query that {} stands for",
+            name
+        ));
+    }
+    Command::Define(Alias::new(name, kind, statements))
+}
+
+fn parse_undefine<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    match tokens.collect::<Vec<&str>>().as_slice() {
+        [name] => Command::Undefine(name.to_string()),
+        _ => Command::SyntaxErr(String::from(
+            "\"Undefine\" command must specify the name of one alias or macro to remove",
+        )),
+    }
+}
+
+fn parse_run<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let tokens = tokens.collect::<Vec<&str>>();
+    let (file, policy) = match tokens.as_slice() {
+        [file @ .., on_op, error_op, policy]
+            if on_op.to_uppercase() == "ON" && error_op.to_uppercase() == "ERROR" =>
+        {
+            match policy.to_uppercase().as_str() {
+                "CONTINUE" => (file, ErrorPolicy::Continue),
+                "STOP" => (file, ErrorPolicy::Stop),
+                _ => {
+                    return Command::SyntaxErr(format!(
+                        "\"{}\" is not an error policy: policies are continue and stop",
+                        policy
+                    ))
+                }
+            }
+        }
+        file => (file, ErrorPolicy::Continue),
+    };
+    if file.is_empty() {
+        return Command::SyntaxErr(String::from(
+            "\"Run\" command must specify a script file to run",
+        ));
+    }
+    Command::Run(file.join(" "), policy)
+}
+
+fn parse_search<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let text = tokens.collect::<Vec<&str>>();
+    if text.is_empty() {
+        return Command::SyntaxErr(String::from(
+            "\"Search\" command must specify text to search for",
+        ));
+    }
+    Command::Search(text.join(" "))
+}
+
+fn parse_form<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    match tokens.next() {
+        None => Command::SyntaxErr(String::from(
+            "\"Form\" command must specify a department to form",
+        )),
+        Some(department) => match tokens.next() {
+            Some(parent_op) if parent_op.to_uppercase() == "UNDER" => {
+                match (tokens.next(), tokens.next()) {
+                    (Some(parent), None) => {
+                        Command::FormDepartmentUnder(department.to_string(), parent.to_string())
+                    }
+                    (None, _) => Command::SyntaxErr(String::from(
+                        "\"Form ... under\" must specify a parent department",
+                    )),
+                    (Some(_), Some(_)) => Command::SyntaxErr(String::from(
+                        "Due to company policy, department names can only be one word long",
+                    )),
+                }
+            }
+            Some(_) => Command::SyntaxErr(String::from(
+                "Due to company policy, department names can only be one word long",
+            )),
+            None => Command::FormDepartment(department.to_string()),
+        },
+    }
+}
+
+fn parse_grant<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    const GRANT_SYNTAX_ERR: &str =
+        "\"Grant\" command must specify a role, a user to grant it to, and optionally a department";
+    parse_role_clause(tokens, "TO", Command::GrantRole)
+        .unwrap_or_else(|| Command::SyntaxErr(String::from(GRANT_SYNTAX_ERR)))
+}
+
+fn parse_rename<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    const RENAME_SYNTAX_ERR: &str = "\"Rename\" command must specify \"department {department} to {department}\" or \"employee {employee} in {department} to {employee}\"";
+    let target = tokens.next().map(|target| target.to_uppercase());
+    let tokens = tokens.collect::<Vec<&str>>();
+    match (target.as_deref(), tokens.as_slice()) {
+        (Some("DEPARTMENT") | Some("DEPT"), [department, to_op, new_name])
+            if to_op.to_uppercase() == "TO" =>
+        {
+            Command::RenameDepartment(department.to_string(), new_name.to_string())
+        }
+        (Some("DEPARTMENT") | Some("DEPT"), [_, to_op, _, _, ..])
+            if to_op.to_uppercase() == "TO" =>
+        {
+            Command::SyntaxErr(String::from(
+                "Due to company policy, department names can only be one word long",
+            ))
+        }
+        (Some("EMPLOYEE"), _) => {
+            let in_to_index = (1..tokens.len().saturating_sub(2)).find(|index| {
+                tokens[*index].to_uppercase() == "IN" && tokens[index + 2].to_uppercase() == "TO"
+            });
+            let (employee, department, new_name) = match in_to_index {
+                Some(in_index) => (
+                    &tokens[..in_index],
+                    Some(tokens[in_index + 1].to_string()),
+                    &tokens[in_index + 3..],
+                ),
+                None => match tokens.iter().position(|token| token.to_uppercase() == "TO") {
+                    None => return Command::SyntaxErr(String::from(RENAME_SYNTAX_ERR)),
+                    Some(to_index) => (&tokens[..to_index], None, &tokens[to_index + 1..]),
+                },
+            };
+            if employee.is_empty() || new_name.is_empty() {
+                return Command::SyntaxErr(String::from(RENAME_SYNTAX_ERR));
+            }
+            Command::RenameEmployee(employee.join(" "), department, new_name.join(" "))
+        }
+        _ => Command::SyntaxErr(String::from(RENAME_SYNTAX_ERR)),
+    }
+}
+
+fn parse_revoke<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    const REVOKE_SYNTAX_ERR: &str =
+        "\"Revoke\" command must specify a role, a user to revoke it from, and optionally a department";
+    parse_role_clause(tokens, "FROM", Command::RevokeRole)
+        .unwrap_or_else(|| Command::SyntaxErr(String::from(REVOKE_SYNTAX_ERR)))
+}
+
+fn parse_role_clause<'a, T: Iterator<Item = &'a str>>(
+    mut tokens: T,
+    user_op: &str,
+    command: fn(String, Role, Option<String>) -> Command,
+) -> Option<Command> {
+    let role_name = tokens.next()?;
+    if tokens.next()?.to_uppercase() != user_op {
+        return None;
+    }
+    let user = tokens.next()?.to_string();
+    let department = match tokens.next() {
+        None => None,
+        Some(department_op) if department_op.to_uppercase() == "ON" => {
+            Some(tokens.next()?.to_string())
+        }
+        Some(_) => return None,
+    };
+    if tokens.next().is_some() {
+        return None;
+    }
+    Some(match Role::parse(role_name) {
+        None => Command::SyntaxErr(format!(
+            "\"{}\" is not a role: roles are Viewer, Editor and Admin",
+            role_name
+        )),
+        Some(role) => command(user, role, department),
+    })
+}
+
+fn parse_list<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let tokens = tokens.collect::<Vec<&str>>();
+    match tokens.as_slice() {
+        [list @ .., as_op, of_op, date]
+            if as_op.to_uppercase() == "AS" && of_op.to_uppercase() == "OF" =>
+        {
+            match (Date::parse(date), parse_current_list(list.iter().copied())) {
+                (None, _) => Command::SyntaxErr(format!("\"{}\" is not a YYYY-MM-DD date", date)),
+                (Some(_), Command::SyntaxErr(syntax_error)) => Command::SyntaxErr(syntax_error),
+                (Some(date), Command::ListEmployees) => Command::ListEmployeesAsOf(date),
+                (Some(date), Command::ListEmployeesByDepartment) => {
+                    Command::ListEmployeesByDepartmentAsOf(date)
+                }
+                (Some(date), Command::ListEmployeesInDepartment(department)) => {
+                    Command::ListEmployeesInDepartmentAsOf(department, date)
+                }
+                (Some(_), _) => Command::SyntaxErr(String::from(
+                    "\"as of {date}\" can only follow \"List employees\", \"List employees by department\" or \"List employees in {department}\"",
+                )),
+            }
+        }
+        _ => parse_current_list(tokens.into_iter()),
+    }
+}
+
+fn parse_current_list<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    match tokens.next() {
+        None => Command::SyntaxErr(String::from("\"List\" command must specify a list name")),
+        Some(list_name) => match list_name.to_uppercase().as_str() {
+            "REPORTS" | "ALL" => {
+                let transitive = list_name.to_uppercase() == "ALL";
+                if transitive
+                    && tokens.next().map(|token| token.to_uppercase())
+                        != Some("REPORTS".to_string())
+                {
+                    return Command::SyntaxErr(String::from(
+                        "\"List all\" must be followed by \"reports of {employee}\"",
+                    ));
+                }
+                match tokens.next() {
+                    Some(of_op) if of_op.to_uppercase() == "OF" => {
+                        match parse_employee_ref(&tokens.collect::<Vec<&str>>()) {
+                            None => Command::SyntaxErr(String::from(
+                                "\"List reports of\" must specify an employee",
+                            )),
+                            Some((employee, department)) if transitive => {
+                                Command::ListAllReports(employee, department)
+                            }
+                            Some((employee, department)) => {
+                                Command::ListReports(employee, department)
+                            }
+                        }
+                    }
+                    _ => Command::SyntaxErr(String::from(
+                        "\"List reports\" must be followed by \"of {employee}\"",
+                    )),
+                }
+            }
+            "EMPLOYEES" | "EMPLOYEE" => match tokens.next() {
+                None => Command::ListEmployees,
+                Some(group_op) => match group_op.to_uppercase().as_str() {
+                    "BY" => match tokens.next() {
+                        None => Command::SyntaxErr(String::from(
+                            "\"List employees by\" must specify a group by field",
+                        )),
+                        Some(group_list) => match group_list.to_uppercase().as_str() {
+                            "DEPARTMENT" => match tokens.next() {
+                                None => Command::ListEmployeesByDepartment,
+                                Some(extra_token) => Command::SyntaxErr(format!(
+                                    "Unexpected token \"{}\" after group by field \"{}\"",
+                                    extra_token, group_list
+                                )),
+                            },
+                            _ => Command::SyntaxErr(format!(
+                                "\"{}\" is not a field employees can by grouped by",
+                                group_list
+                            )),
+                        },
+                    },
+                    "IN" => match tokens.next() {
+                        None => Command::SyntaxErr(String::from(
+                            "Command \"List employees in\" must specify a department name",
+                        )),
+                        Some(department_name) => match tokens.next() {
+                            None => Command::ListEmployeesInDepartment(department_name.to_string()),
+                            Some(extra_token) => Command::SyntaxErr(format!(
+                                "Unexpected token \"{}\" after department name \"{}\"",
+                                extra_token, department_name
+                            )),
+                        },
+                    },
+                    "UNDER" => match tokens.next() {
+                        None => Command::SyntaxErr(String::from(
+                            "Command \"List employees under\" must specify a department name",
+                        )),
+                        Some(department_name) => match tokens.next() {
+                            None => {
+                                Command::ListEmployeesUnderDepartment(department_name.to_string())
+                            }
+                            Some(extra_token) => Command::SyntaxErr(format!(
+                                "Unexpected token \"{}\" after department name \"{}\"",
+                                extra_token, department_name
+                            )),
+                        },
+                    },
+                    _ => Command::SyntaxErr(format!(
+                        "Unexpected token \"{}\" after list name \"{}\"",
+                        group_op, list_name,
+                    )),
+                },
+            },
+            _ => Command::SyntaxErr(format!(
+                "Cannot list \"{}\": list does not exist",
+                list_name,
+            )),
+        },
+    }
+}
+
+fn parse_move<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    const MOVE_SYNTAX_ERR: &str =
+        "\"Move\" command must specify a department and either \"under {department}\" or \"to top\"";
+    match (tokens.next(), tokens.next(), tokens.next(), tokens.next()) {
+        (Some(department), Some(op), Some(target), None) => {
+            match (op.to_uppercase().as_str(), target.to_uppercase().as_str()) {
+                ("UNDER", _) => {
+                    Command::MoveDepartment(department.to_string(), Some(target.to_string()))
+                }
+                ("TO", "TOP") => Command::MoveDepartment(department.to_string(), None),
+                _ => Command::SyntaxErr(String::from(MOVE_SYNTAX_ERR)),
+            }
+        }
+        _ => Command::SyntaxErr(String::from(MOVE_SYNTAX_ERR)),
+    }
+}
+
+fn parse_merge<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    const MERGE_SYNTAX_ERR: &str =
+        "\"Merge\" command must specify \"department {department} into {department}\"";
+    let tokens = tokens.collect::<Vec<&str>>();
+    let (tokens, policy) = match parse_conflict_policy(&tokens) {
+        Err(syntax_error) => return syntax_error,
+        Ok(parsed) => parsed,
+    };
+    let tokens = match tokens.first().map(|token| token.to_uppercase()).as_deref() {
+        Some("DEPARTMENT") | Some("DEPT") => &tokens[1..],
+        _ => tokens,
+    };
+    match tokens {
+        [from_department, into_op, to_department] if into_op.to_uppercase() == "INTO" => {
+            Command::MergeDepartments(
+                from_department.to_string(),
+                to_department.to_string(),
+                policy,
+            )
+        }
+        _ => Command::SyntaxErr(String::from(MERGE_SYNTAX_ERR)),
+    }
+}
+
+fn parse_conflict_policy<'a, 'b>(
+    tokens: &'b [&'a str],
+) -> Result<(&'b [&'a str], ConflictPolicy), Command> {
+    let length = tokens.len();
+    if length < 3
+        || tokens[length - 3].to_uppercase() != "ON"
+        || tokens[length - 2].to_uppercase() != "CONFLICT"
+    {
+        return Ok((tokens, ConflictPolicy::Fail));
+    }
+    let policy = match tokens[length - 1].to_uppercase().as_str() {
+        "FAIL" => ConflictPolicy::Fail,
+        "SKIP" => ConflictPolicy::Skip,
+        "RENAME" => ConflictPolicy::Rename,
+        _ => {
+            return Err(Command::SyntaxErr(format!(
+                "\"{}\" is not a conflict policy: policies are fail, skip and rename",
+                tokens[length - 1]
+            )))
+        }
+    };
+    Ok((&tokens[..length - 3], policy))
+}
+
+fn parse_pull<'a, T: DoubleEndedIterator<Item = &'a str> + Clone>(mut tokens: T) -> Command {
+    const PULL_SYNTAX_ERR: &str =
+        "\"Pull\" command must specify an employee to pull and a department to pull from";
+    let mut lookahead = tokens.clone();
+    if lookahead
+        .next()
+        .map(|token| token.to_uppercase())
+        .as_deref()
+        == Some("ALL")
+    {
+        return match (lookahead.next(), lookahead.next(), lookahead.next()) {
+            (Some(from_op), Some(department), None) if from_op.to_uppercase() == "FROM" => {
+                Command::PullAllFromDepartment(department.to_string())
+            }
+            _ => Command::SyntaxErr(String::from(
+                "\"Pull all\" must be followed by \"from {department}\"",
+            )),
+        };
+    }
+    match tokens.next_back() {
+        None => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
+        Some(department) => match tokens.next_back() {
+            None => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
+            Some(group_op) => match group_op.to_uppercase().as_str() {
+                "FROM" => match tokens.next() {
+                    None => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
+                    Some(employee_first_name) => {
+                        let mut employee = String::from(employee_first_name);
+                        tokens.for_each(|token| {
+                            employee.push(' ');
+                            employee.push_str(token);
+                        });
+                        Command::PullEmployeeFromDepartment(employee, department.to_string())
+                    }
+                },
+                _ => Command::SyntaxErr(String::from(PULL_SYNTAX_ERR)),
+            },
+        },
+    }
+}
+
+fn parse_set<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    const SET_SYNTAX_ERR: &str = "\"Set\" command must specify \"manager of {employee} to {employee}\" or \"{field} of {department} to {value}\"";
+    let tokens = tokens.collect::<Vec<&str>>();
+    let of_index = match tokens.iter().position(|token| token.to_uppercase() == "OF") {
+        None => return Command::SyntaxErr(String::from(SET_SYNTAX_ERR)),
+        Some(of_index) => of_index,
+    };
+    let field_name = tokens[..of_index].join(" ");
+    let tokens = &tokens[of_index + 1..];
+    if field_name.to_uppercase() == "MANAGER" {
+        return match tokens
+            .iter()
+            .rposition(|token| token.to_uppercase() == "TO")
+        {
+            None => Command::SyntaxErr(String::from(SET_SYNTAX_ERR)),
+            Some(to_index) => match (
+                parse_employee_ref(&tokens[..to_index]),
+                parse_employee_ref(&tokens[to_index + 1..]),
+            ) {
+                (Some((employee, department)), Some((manager, manager_department))) => {
+                    Command::SetManager(employee, department, manager, manager_department)
+                }
+                _ => Command::SyntaxErr(String::from(SET_SYNTAX_ERR)),
+            },
+        };
+    }
+    match (DepartmentField::parse(&field_name), tokens) {
+        (None, _) => Command::SyntaxErr(format!(
+            "\"{}\" is not a field that can be set: fields are manager, head, cost center, location, description and created",
+            field_name
+        )),
+        (Some(field), [department, to_op, value @ ..])
+            if to_op.to_uppercase() == "TO" && !value.is_empty() =>
+        {
+            let value = value.join(" ");
+            if field == DepartmentField::Created && Date::parse(&value).is_none() {
+                return Command::SyntaxErr(format!(
+                    "\"{}\" is not a date: dates are written YYYY-MM-DD",
+                    value
+                ));
+            }
+            Command::SetDepartmentField(department.to_string(), field, value)
+        }
+        _ => Command::SyntaxErr(String::from(SET_SYNTAX_ERR)),
+    }
+}
+
+fn parse_show<'a, T: Iterator<Item = &'a str>>(mut tokens: T) -> Command {
+    let table = tokens.next();
+    match table {
+        None => Command::SyntaxErr(String::from("\"Show\" command must specify a list name")),
+        Some(list_name) => match list_name.to_uppercase().as_str() {
+            "DEPARTMENTS" | "DEPT" | "DEPARTMENT" | "DEPTS" => match tokens.next() {
+                None => Command::ShowDepartments,
+                Some(view) if view.to_uppercase() == "TREE" && tokens.next().is_none() => {
+                    Command::ShowDepartmentTree
+                }
+                Some(view) if view.to_uppercase() == "DETAILED" && tokens.next().is_none() => {
+                    Command::ShowDepartmentsDetailed
+                }
+                Some(extra_token) => Command::SyntaxErr(format!(
+                    "Unexpected token \"{}\" after list name \"{}\"",
+                    extra_token, list_name
+                )),
+            },
+            "CHAIN" => {
+                let clause = tokens
+                    .by_ref()
+                    .take(3)
+                    .map(|token| token.to_uppercase())
+                    .collect::<Vec<String>>();
+                match (
+                    clause == ["OF", "COMMAND", "FOR"],
+                    parse_employee_ref(&tokens.collect::<Vec<&str>>()),
+                ) {
+                    (true, Some((employee, department))) => {
+                        Command::ShowManagementChain(employee, department)
+                    }
+                    _ => Command::SyntaxErr(String::from(
+                        "\"Show chain\" must be followed by \"of command for {employee}\"",
+                    )),
+                }
+            }
+            "TIMELINE" => match tokens.next().map(|token| token.to_uppercase()).as_deref() {
+                Some("OF") | Some("FOR") => match tokens.collect::<Vec<&str>>() {
+                    employee if !employee.is_empty() => {
+                        Command::ShowTimeline(employee.join(" "), None)
+                    }
+                    _ => Command::SyntaxErr(String::from(
+                        "\"Show timeline\" must specify an employee",
+                    )),
+                },
+                _ => Command::SyntaxErr(String::from(
+                    "\"Show timeline\" must be followed by \"of {employee}\"",
+                )),
+            },
+            "USERS" | "USER" => match tokens.next() {
+                None => Command::ShowUsers,
+                Some(extra_token) => Command::SyntaxErr(format!(
+                    "Unexpected token \"{}\" after list name \"{}\"",
+                    extra_token, list_name
+                )),
+            },
+            _ => Command::SyntaxErr(format!(
+                "Cannot show \"{}\": list does not exist",
+                list_name
+            )),
+        },
+    }
+}
+
+fn parse_transfer<'a, T: DoubleEndedIterator<Item = &'a str> + Clone>(mut tokens: T) -> Command {
+    const TRANSFER_SYNTAX_ERR: &str = "\"Transfer\" command must specify an employee, a department to transfer from, and a department to transfer to";
+    let mut lookahead = tokens.clone();
+    match (
+        lookahead
+            .next()
+            .map(|token| token.to_uppercase())
+            .as_deref(),
+        lookahead
+            .next()
+            .map(|token| token.to_uppercase())
+            .as_deref(),
+    ) {
+        (Some("ALL"), Some("FROM")) => return parse_transfer_all(tokens.skip(2)),
+        (Some("EMPLOYEES"), Some("WHERE")) => return parse_transfer_matching(tokens.skip(2)),
+        _ => {}
+    }
+    match tokens.next_back() {
+        None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
+        Some(to_department) => match tokens.next_back() {
+            None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
+            Some(to_op) => match to_op.to_uppercase().as_str() {
+                "TO" => match tokens.next_back() {
+                    None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
+                    Some(from_department) => match tokens.next_back() {
+                        None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
+                        Some(from_op) => match from_op.to_uppercase().as_str() {
+                            "FROM" => match tokens.next() {
+                                None => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
+                                Some(employee_first_name) => {
+                                    let mut employee = String::from(employee_first_name);
+                                    tokens.for_each(|token| {
+                                        employee.push(' ');
+                                        employee.push_str(token);
+                                    });
+                                    Command::TransferEmployeeBetweenDepartments(
+                                        employee,
+                                        from_department.to_string(),
+                                        to_department.to_string(),
+                                    )
+                                }
+                            },
+                            _ => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
+                        },
+                    },
+                },
+                _ => Command::SyntaxErr(String::from(TRANSFER_SYNTAX_ERR)),
+            },
+        },
+    }
+}
+
+fn parse_transfer_all<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let tokens = tokens.collect::<Vec<&str>>();
+    let (tokens, policy) = match parse_conflict_policy(&tokens) {
+        Err(syntax_error) => return syntax_error,
+        Ok(parsed) => parsed,
+    };
+    match tokens {
+        [from_department, to_op, to_department] if to_op.to_uppercase() == "TO" => {
+            Command::TransferAllBetweenDepartments(
+                from_department.to_string(),
+                to_department.to_string(),
+                policy,
+            )
+        }
+        _ => Command::SyntaxErr(String::from(
+            "\"Transfer all\" must specify \"from {department} to {department}\"",
+        )),
+    }
+}
+
+fn parse_transfer_matching<'a, T: Iterator<Item = &'a str>>(tokens: T) -> Command {
+    let tokens = tokens.collect::<Vec<&str>>();
+    let (tokens, policy) = match parse_conflict_policy(&tokens) {
+        Err(syntax_error) => return syntax_error,
+        Ok(parsed) => parsed,
+    };
+    match tokens {
+        [conditions @ .., to_op, to_department] if to_op.to_uppercase() == "TO" => {
+            match parse_filter(conditions) {
+                Err(message) => Command::SyntaxErr(message),
+                Ok(filter) => {
+                    Command::TransferMatchingToDepartment(filter, to_department.to_string(), policy)
+                }
+            }
+        }
+        _ => Command::SyntaxErr(String::from(
+            "\"Transfer employees where\" must specify conditions and \"to {department}\"",
+        )),
+    }
+}
+
+fn parse_employee_ref(tokens: &[&str]) -> Option<(String, Option<String>)> {
+    match tokens.len() {
+        0 => None,
+        length if length >= 3 && tokens[length - 2].to_uppercase() == "IN" => Some((
+            tokens[..length - 2].join(" "),
+            Some(tokens[length - 1].to_string()),
+        )),
+        _ => Some((tokens.join(" "), None)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::commands;
+    use proptest::prelude::*;
+    use proptest::sample::select;
+
+    // Every shape of query the hand-written parsers accepted, including those whose values they
+    // rejected, with each placeholder filled in by a strategy below
+    const QUERIES: &[&str] = &[
+        "help",
+        "help {name}",
+        "halp {name}",
+        "exit",
+        "quit",
+        "leave {employee}",
+        "bye",
+        "define {kind} {name} as {employee}",
+        "define {kind} {name} as assign $1 to {department}; list reports of $1;",
+        "undefine {name}",
+        "run {file}",
+        "run {file} on error {error policy}",
+        "show {departments}",
+        "show {departments} tree",
+        "show {departments} detailed",
+        "show timeline of {employee}",
+        "show timeline for {employee}",
+        "show chain of command for {employee}",
+        "show chain of command for {employee} in {department}",
+        "show {users}",
+        "list {employees}",
+        "list {employees} as of {date}",
+        "list {employees} by department",
+        "list {employees} by department as of {date}",
+        "list {employees} in {department}",
+        "list {employees} in {department} as of {date}",
+        "list {employees} under {department}",
+        "list reports of {employee}",
+        "list reports of {employee} in {department}",
+        "list all reports of {employee}",
+        "list all reports of {employee} in {department}",
+        "find {employee}",
+        "where is {employee}",
+        "search {employee}",
+        "report headcount",
+        "report headcount from {date}",
+        "report headcount to {other date}",
+        "report headcount from {date} to {other date}",
+        "form {department}",
+        "form {department} under {other department}",
+        "move {department} under {other department}",
+        "move {department} to top",
+        "assign {employee} to {department}",
+        "transfer all from {department} to {other department}",
+        "transfer all from {department} to {other department} on conflict {conflict policy}",
+        "transfer employees where {condition} to {department}",
+        "transfer employees where {condition} to {department} on conflict {conflict policy}",
+        "transfer {employee} from {department} to {other department}",
+        "pull all from {department}",
+        "pull {employee} from {department}",
+        "merge {department} into {other department}",
+        "merge {department keyword} {department} into {other department}",
+        "merge {department} into {other department} on conflict {conflict policy}",
+        "set manager of {employee} to {other employee}",
+        "set manager of {employee} in {department} to {other employee} in {other department}",
+        "set {field} of {department} to {employee}",
+        "set created of {department} to {date}",
+        "clear manager of {employee}",
+        "clear manager of {employee} in {department}",
+        "clear {valid field} of {department}",
+        "rename {department keyword} {department} to {other department}",
+        "rename employee {employee} to {other employee}",
+        "rename employee {employee} in {department} to {other employee}",
+        "dissolve {department}",
+        "grant {role} to {name}",
+        "grant {role} to {name} on {department}",
+        "revoke {role} from {name}",
+        "revoke {role} from {name} on {department}",
+        "export {export format} to {file}",
+        "export {export format} of {department} to {file}",
+        "diff {file}",
+        "chart {chart format}",
+        "chart {chart format} depth {levels}",
+        "chart {chart format} collapse above {headcount}",
+        "chart {chart format} depth {levels} collapse above {headcount}",
+        "chart {chart format} collapse above {headcount} depth {levels}",
+    ];
+
+    // Words that mean something in some query, and so can't be part of a name
+    const KEYWORDS: &[&str] = &[
+        "ABOVE",
+        "ALL",
+        "AND",
+        "AS",
+        "BY",
+        "CHAIN",
+        "COLLAPSE",
+        "COMMAND",
+        "CONFLICT",
+        "CONTAINS",
+        "DEPARTMENT",
+        "DEPARTMENTS",
+        "DEPT",
+        "DEPTS",
+        "DEPTH",
+        "DETAILED",
+        "EMPLOYEE",
+        "EMPLOYEES",
+        "ERROR",
+        "FOR",
+        "FROM",
+        "HEADCOUNT",
+        "IN",
+        "INTO",
+        "IS",
+        "MANAGER",
+        "NAME",
+        "NOT",
+        "OF",
+        "ON",
+        "OR",
+        "REPORT",
+        "REPORTS",
+        "STARTS",
+        "TIMELINE",
+        "TO",
+        "TOP",
+        "TREE",
+        "UNDER",
+        "USER",
+        "USERS",
+        "WHERE",
+        "WITH",
+    ];
+
+    fn word() -> impl Strategy<Value = String> {
+        "[A-Z][a-z]{1,7}".prop_filter("names can't hold keywords", |word| {
+            !KEYWORDS.contains(&word.to_uppercase().as_str())
+        })
+    }
+
+    fn words() -> impl Strategy<Value = String> {
+        prop::collection::vec(word(), 1..4).prop_map(|words| words.join(" "))
+    }
+
+    fn date() -> impl Strategy<Value = String> {
+        prop_oneof![
+            "20[0-2][0-9]-(0[1-9]|1[0-2])-(0[1-9]|1[0-9]|2[0-8])",
+            select(vec!["yesterday", "2026-13-01", "2026-02-30"]).prop_map(String::from),
+        ]
+    }
+
+    fn condition() -> impl Strategy<Value = String> {
+        let field = select(vec!["name", "Employee", "department", "DEPT", "manager"]);
+        let operator = select(vec!["is", "IS NOT", "contains", "starts with"]);
+        (field, operator, words())
+            .prop_map(|(field, operator, value)| format!("{} {} {}", field, operator, value))
+    }
+
+    fn conditions() -> impl Strategy<Value = String> {
+        (
+            condition(),
+            prop::collection::vec((select(vec!["and", "OR"]), condition()), 0..3),
+        )
+            .prop_map(|(first, rest)| {
+                rest.into_iter()
+                    .fold(first, |conditions, (joiner, condition)| {
+                        format!("{} {} {}", conditions, joiner, condition)
+                    })
+            })
+    }
+
+    fn placeholders() -> impl Strategy<Value = Vec<(&'static str, String)>> {
+        let choice = |name: &'static str, values: Vec<&'static str>| {
+            select(values).prop_map(move |value| (name, value.to_string()))
+        };
+        let fill = |name: &'static str, values: BoxedStrategy<String>| {
+            values.prop_map(move |value| (name, value))
+        };
+        (
+            (
+                fill("{employee}", words().boxed()),
+                fill("{other employee}", words().boxed()),
+                fill("{department}", word().boxed()),
+                fill("{other department}", word().boxed()),
+                fill(
+                    "{name}",
+                    prop_oneof![word(), Just(String::from("List"))].boxed(),
+                ),
+                fill("{file}", words().boxed()),
+                fill("{date}", date().boxed()),
+                fill("{other date}", date().boxed()),
+                fill("{levels}", (0..10u32).prop_map(|n| n.to_string()).boxed()),
+                fill(
+                    "{headcount}",
+                    (0..100u32).prop_map(|n| n.to_string()).boxed(),
+                ),
+                fill("{condition}", conditions().boxed()),
+            ),
+            (
+                choice("{kind}", vec!["alias", "Macro", "function"]),
+                choice("{error policy}", vec!["stop", "Continue", "retry"]),
+                choice("{conflict policy}", vec!["fail", "Skip", "RENAME", "merge"]),
+                choice(
+                    "{departments}",
+                    vec!["departments", "DEPARTMENT", "dept", "Depts"],
+                ),
+                choice("{department keyword}", vec!["department", "Dept"]),
+                choice("{users}", vec!["users", "User"]),
+                choice("{employees}", vec!["employees", "Employee"]),
+                choice("{field}", vec!["head", "cost center", "Location", "salary"]),
+                choice("{valid field}", vec!["head", "Cost Center", "description"]),
+                choice("{role}", vec!["viewer", "Editor", "ADMIN", "owner"]),
+                choice("{export format}", vec!["json", "CSV", "yaml", "xml"]),
+                choice("{chart format}", vec!["dot", "Mermaid", "svg"]),
+            ),
+        )
+            .prop_map(|(names, choices)| {
+                let (a, b, c, d, e, f, g, h, i, j, k) = names;
+                let (l, m, n, o, p, q, r, s, t, u, v, w) = choices;
+                vec![
+                    a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q, r, s, t, u, v, w,
+                ]
+            })
+    }
+
+    // A query filled in from one of the shapes, cased as a whole and spaced out at random
+    fn query() -> impl Strategy<Value = String> {
+        (
+            select(QUERIES),
+            placeholders(),
+            select(vec![str::to_string, str::to_uppercase, str::to_lowercase]),
+            prop::collection::vec(select(vec![" ", "  ", "\t", " \t "]), 64),
+        )
+            .prop_map(|(shape, placeholders, case, spaces)| {
+                let query = placeholders
+                    .iter()
+                    .fold(shape.to_string(), |query, (placeholder, value)| {
+                        query.replace(placeholder, value)
+                    });
+                let query = case(&query);
+                let mut spaced = String::from(spaces[0]);
+                query
+                    .split_whitespace()
+                    .zip(spaces.iter().cycle().skip(1))
+                    .for_each(|(token, space)| {
+                        spaced.push_str(token);
+                        spaced.push_str(space);
+                    });
+                spaced
+            })
+    }
+
+    mod fn_parse {
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn parses_every_query_the_hand_written_parsers_did(query in query()) {
+                prop_assert_eq!(super::super::parse(query.clone()), commands::parse(query));
+            }
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date, displayed in ISO 8601 form ("YYYY-MM-DD")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// Creates a date, or returns `None` if the month or day is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::Date;
+    ///
+    /// assert!(Date::new(2024, 2, 29).is_some());
+    /// assert!(Date::new(2026, 2, 29).is_none());
+    /// ```
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Parses a date in "YYYY-MM-DD" form, or returns `None` if it isn't a valid date.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::Date;
+    ///
+    /// assert_eq!(Date::new(2026, 3, 1), Date::parse("2026-03-01"));
+    /// assert_eq!(None, Date::parse("March 1st"));
+    /// ```
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.splitn(3, '-');
+        let year = parts.next()?;
+        let month = parts.next()?;
+        let day = parts.next()?;
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+        Date::new(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+    }
+
+    /// Returns the current date in UTC.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Date::from_days((seconds / 86_400) as i64)
+    }
+
+    // Converts days since 1970-01-01 to a civil date, after Howard Hinnant's `civil_from_days`
+    fn from_days(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        } as u32;
+        let year = (year_of_era + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "{:04}-{:02}-{:02}",
+            self.year, self.month, self.day
+        )
+    }
+}
+
+// Dates are serialized in the same "YYYY-MM-DD" form they are displayed and queried in
+#[cfg(feature = "serde")]
+impl serde::Serialize for Date {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Date {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Date::parse(&value).ok_or_else(|| {
+            serde::de::Error::custom(format!("\"{}\" is not a YYYY-MM-DD date", value))
+        })
+    }
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod date {
+        use super::Date;
+
+        #[test]
+        fn parse_accepts_iso_dates() {
+            assert_eq!(Date::new(2026, 3, 1), Date::parse("2026-03-01"));
+            assert_eq!(Date::new(2024, 2, 29), Date::parse("2024-02-29"));
+        }
+
+        #[test]
+        fn parse_rejects_invalid_dates() {
+            assert_eq!(None, Date::parse("2026-3-1"));
+            assert_eq!(None, Date::parse("2026-02-29"));
+            assert_eq!(None, Date::parse("March 1st"));
+        }
+
+        #[test]
+        fn displays_as_iso_date() {
+            assert_eq!("2026-03-01", Date::new(2026, 3, 1).unwrap().to_string());
+        }
+
+        #[test]
+        fn converts_days_since_epoch() {
+            assert_eq!(Date::new(1970, 1, 1).unwrap(), Date::from_days(0));
+            assert_eq!(Date::new(2000, 3, 1).unwrap(), Date::from_days(11_017));
+        }
+
+        #[test]
+        fn orders_chronologically() {
+            assert!(Date::parse("2025-12-31") < Date::parse("2026-01-01"));
+        }
+    }
+}
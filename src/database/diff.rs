@@ -0,0 +1,191 @@
+use super::names::Names;
+use super::store::Store;
+use super::{Rows, Value};
+use std::collections::BTreeMap;
+
+// Departments and employees are matched by key under the later store's name handling, so that
+// a document read back with different capitalization isn't reported as changed
+pub fn diff(before: &Store, after: &Store) -> Rows {
+    let names = after.names();
+    let mut rows = Rows::new(
+        String::from("Showing Changes"),
+        vec![
+            String::from("Change"),
+            String::from("Name"),
+            String::from("From"),
+            String::from("To"),
+        ],
+    );
+    let (departments_before, departments_after) =
+        (departments(before, names), departments(after, names));
+    departments_before
+        .iter()
+        .filter(|(key, _)| !departments_after.contains_key(*key))
+        .for_each(|(_, name)| push(&mut rows, "Department removed", name, None, None));
+    departments_after
+        .iter()
+        .filter(|(key, _)| !departments_before.contains_key(*key))
+        .for_each(|(_, name)| push(&mut rows, "Department added", name, None, None));
+
+    let (employees_before, employees_after) = (employees(before, names), employees(after, names));
+    let mut keys = employees_before
+        .keys()
+        .chain(employees_after.keys())
+        .collect::<Vec<&String>>();
+    keys.sort();
+    keys.dedup();
+    keys.into_iter().for_each(|key| {
+        let (name_before, was_in) = employees_before
+            .get(key)
+            .map_or((None, BTreeMap::new()), |(name, in_departments)| {
+                (Some(name), in_departments.clone())
+            });
+        let (name_after, is_in) = employees_after
+            .get(key)
+            .map_or((None, BTreeMap::new()), |(name, in_departments)| {
+                (Some(name), in_departments.clone())
+            });
+        let name = name_after.or(name_before).unwrap();
+        let left = was_in
+            .iter()
+            .filter(|(department, _)| !is_in.contains_key(*department))
+            .map(|(_, department_name)| department_name.as_str())
+            .collect::<Vec<&str>>();
+        let joined = is_in
+            .iter()
+            .filter(|(department, _)| !was_in.contains_key(*department))
+            .map(|(_, department_name)| department_name.as_str())
+            .collect::<Vec<&str>>();
+        // Leaving one department and joining another is a transfer, and only what can't be
+        // paired up that way is a removal or an addition
+        let transfers = left.len().min(joined.len());
+        left.iter().zip(joined.iter()).for_each(|(from, to)| {
+            push(
+                &mut rows,
+                "Employee transferred",
+                name,
+                Some(from),
+                Some(to),
+            )
+        });
+        left[transfers..]
+            .iter()
+            .for_each(|from| push(&mut rows, "Employee removed", name, Some(from), None));
+        joined[transfers..]
+            .iter()
+            .for_each(|to| push(&mut rows, "Employee added", name, None, Some(to)));
+    });
+    rows
+}
+
+fn push(rows: &mut Rows, change: &str, name: &str, from: Option<&str>, to: Option<&str>) {
+    rows.push_values(&[
+        Value::Text(change),
+        Value::Text(name),
+        from.map_or(Value::Null, Value::Text),
+        to.map_or(Value::Null, Value::Text),
+    ]);
+}
+
+// Department names by key
+fn departments(store: &Store, names: Names) -> BTreeMap<String, String> {
+    store
+        .departments()
+        .iter()
+        .map(|department| (names.key(department.name()), department.name().to_owned()))
+        .collect()
+}
+
+// Employee names by key, with the names of the departments they are in by key
+fn employees(store: &Store, names: Names) -> BTreeMap<String, (String, BTreeMap<String, String>)> {
+    let mut employees: BTreeMap<String, (String, BTreeMap<String, String>)> = BTreeMap::new();
+    store.departments().iter().for_each(|department| {
+        department.employees().iter().for_each(|employee| {
+            employees
+                .entry(names.key(employee.name()))
+                .or_insert_with(|| (employee.name().to_owned(), BTreeMap::new()))
+                .1
+                .insert(names.key(department.name()), department.name().to_owned());
+        })
+    });
+    employees
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::{Database, Table};
+
+    fn query(db: &mut Database, queries: &[&str]) {
+        queries.iter().for_each(|query| {
+            db.query(query.to_string());
+        });
+    }
+
+    mod fn_diff {
+        use super::*;
+
+        #[test]
+        fn lists_departments_and_employees_that_changed() {
+            let mut before = Database::new();
+            query(
+                &mut before,
+                &[
+                    "Form Sales",
+                    "Form Design",
+                    "Form Legal",
+                    "Assign Bob to Sales",
+                    "Assign Alice to Sales",
+                    "Assign Carol to Design",
+                    "Assign Dan to Legal",
+                ],
+            );
+            let mut after = Database::new();
+            query(
+                &mut after,
+                &[
+                    "Form Sales",
+                    "Form Design",
+                    "Form Legal",
+                    "Form Support",
+                    "Assign Bob to Design",
+                    "Assign Alice to Sales",
+                    "Assign Carol to Design",
+                    "Assign Carol to Support",
+                ],
+            );
+
+            let table = Table::from(diff(&before.store, &after.store));
+
+            assert_eq!(
+                vec![
+                    vec!["Department added", "Support", "", ""],
+                    vec!["Employee transferred", "Bob", "Sales", "Design"],
+                    vec!["Employee added", "Carol", "", "Support"],
+                    vec!["Employee removed", "Dan", "Legal", ""],
+                ],
+                table
+                    .data
+                    .iter()
+                    .map(|row| {
+                        table
+                            .headers
+                            .iter()
+                            .map(|header| row[header].to_string())
+                            .collect::<Vec<String>>()
+                    })
+                    .collect::<Vec<Vec<String>>>()
+            );
+        }
+
+        #[test]
+        fn matches_names_ignoring_case() {
+            let mut before = Database::new();
+            query(&mut before, &["Form sales", "Assign bob to sales"]);
+            let mut after = Database::new();
+            query(&mut after, &["Form Sales", "Assign Bob to Sales"]);
+
+            assert!(diff(&before.store, &after.store).is_empty());
+        }
+    }
+}
@@ -1,6 +1,22 @@
+use std::fmt;
+
 #[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum QueryError {
     Conflict(String),
     NotFound(String),
+    PermissionDenied(String),
+}
+
+// Displays only the message, since each caller frames it differently
+impl fmt::Display for QueryError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            QueryError::Conflict(message)
+            | QueryError::NotFound(message)
+            | QueryError::PermissionDenied(message) => formatter.write_str(message),
+        }
+    }
 }
@@ -0,0 +1,260 @@
+#[cfg(feature = "export")]
+use super::date::Date;
+#[cfg(feature = "export")]
+use super::names::{Collation, NameCase};
+#[cfg(feature = "export")]
+use super::snapshot::{DepartmentSnapshot, EmployeeSnapshot, ManagerSnapshot, Snapshot};
+#[cfg(feature = "export")]
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// A document format that a whole database, or one of its departments, can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// A JSON object holding departments, their employees and users
+    Json,
+    /// A CSV table with one row per employee, and one row for each department without employees
+    ///
+    /// CSV holds departments and employees only, so users and name handling are left out, and
+    /// imported databases use the default name handling.
+    Csv,
+    /// A YAML document holding the same fields as JSON
+    Yaml,
+}
+
+impl ExportFormat {
+    /// Parses the name of a format, ignoring case, or returns `None` if it isn't supported.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::ExportFormat;
+    ///
+    /// assert_eq!(Some(ExportFormat::Yaml), ExportFormat::parse("yml"));
+    /// assert_eq!(None, ExportFormat::parse("xlsx"));
+    /// ```
+    pub fn parse(format_name: &str) -> Option<ExportFormat> {
+        match format_name.to_uppercase().as_str() {
+            "JSON" => Some(ExportFormat::Json),
+            "CSV" => Some(ExportFormat::Csv),
+            "YAML" | "YML" => Some(ExportFormat::Yaml),
+            _ => None,
+        }
+    }
+
+    /// Tells the format of a file from its extension, ignoring case, or returns `None` if the
+    /// file has no supported extension.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::ExportFormat;
+    ///
+    /// assert_eq!(Some(ExportFormat::Csv), ExportFormat::from_file_name("org/2026.CSV"));
+    /// assert_eq!(None, ExportFormat::from_file_name("org"));
+    /// ```
+    pub fn from_file_name(file_name: &str) -> Option<ExportFormat> {
+        Path::new(file_name)
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(ExportFormat::parse)
+    }
+
+    /// The name of the format, as written in queries.
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "JSON",
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Yaml => "YAML",
+        }
+    }
+}
+
+// Department details are repeated on every row of their employees
+#[cfg(feature = "export")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CsvRow {
+    department: String,
+    parent: Option<String>,
+    head: Option<String>,
+    cost_center: Option<String>,
+    location: Option<String>,
+    description: Option<String>,
+    created: Option<Date>,
+    employee: Option<String>,
+    manager: Option<String>,
+    manager_department: Option<String>,
+}
+
+#[cfg(feature = "export")]
+pub fn write<W: Write>(snapshot: &Snapshot, format: ExportFormat, mut writer: W) -> io::Result<()> {
+    match format {
+        ExportFormat::Json => serde_json::to_writer_pretty(&mut writer, snapshot)?,
+        ExportFormat::Yaml => serde_yaml::to_writer(&mut writer, snapshot).map_err(invalid_data)?,
+        ExportFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(&mut writer);
+            for department in &snapshot.departments {
+                let row = |employee: Option<&EmployeeSnapshot>| CsvRow {
+                    department: department.name.clone(),
+                    parent: department.parent.clone(),
+                    head: department.head.clone(),
+                    cost_center: department.cost_center.clone(),
+                    location: department.location.clone(),
+                    description: department.description.clone(),
+                    created: department.created,
+                    employee: employee.map(|employee| employee.name.clone()),
+                    manager: employee
+                        .and_then(|employee| employee.manager.as_ref())
+                        .map(|manager| manager.employee.clone()),
+                    manager_department: employee
+                        .and_then(|employee| employee.manager.as_ref())
+                        .map(|manager| manager.department.clone()),
+                };
+                if department.employees.is_empty() {
+                    csv_writer.serialize(row(None))?;
+                }
+                for employee in &department.employees {
+                    csv_writer.serialize(row(Some(employee)))?;
+                }
+            }
+            csv_writer.flush()?;
+        }
+    }
+    writer.flush()
+}
+
+#[cfg(feature = "export")]
+pub fn read<R: Read>(format: ExportFormat, reader: R) -> io::Result<Snapshot> {
+    match format {
+        ExportFormat::Json => Ok(serde_json::from_reader(reader)?),
+        ExportFormat::Yaml => serde_yaml::from_reader(reader).map_err(invalid_data),
+        ExportFormat::Csv => {
+            let mut departments: Vec<DepartmentSnapshot> = Vec::new();
+            for row in csv::Reader::from_reader(reader).deserialize() {
+                let row: CsvRow = row.map_err(invalid_data)?;
+                // Rows are written grouped by department, so only the last one can be continued
+                let department = match departments.last_mut() {
+                    Some(department) if department.name == row.department => department,
+                    _ => {
+                        departments.push(DepartmentSnapshot {
+                            name: row.department,
+                            parent: row.parent,
+                            head: row.head,
+                            cost_center: row.cost_center,
+                            location: row.location,
+                            description: row.description,
+                            created: row.created,
+                            employees: Vec::new(),
+                        });
+                        departments.last_mut().unwrap()
+                    }
+                };
+                if let Some(employee) = row.employee {
+                    department.employees.push(EmployeeSnapshot {
+                        name: employee,
+                        manager: match (row.manager, row.manager_department) {
+                            (Some(employee), Some(department)) => Some(ManagerSnapshot {
+                                employee,
+                                department,
+                            }),
+                            _ => None,
+                        },
+                    });
+                }
+            }
+            Ok(Snapshot {
+                case: NameCase::Title,
+                collation: Collation::Unicode,
+                departments,
+                users: Vec::new(),
+                history: Vec::new(),
+                aliases: Vec::new(),
+            })
+        }
+    }
+}
+
+#[cfg(feature = "export")]
+pub fn invalid_data<E: ToString>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error.to_string())
+}
+
+#[cfg(all(test, feature = "export"))]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn organization() -> Snapshot {
+        let mut db = Database::new();
+        db.seed();
+        [
+            "Form Legal",
+            "Form Payroll under Accounting",
+            "Set manager of Kirk Short to Atif Wells",
+            "Set description of Design to Pixels, mostly",
+            "Set created of Design to 1999-12-31",
+        ]
+        .iter()
+        .for_each(|query| {
+            db.query(query.to_string());
+        });
+        db.snapshot()
+    }
+
+    mod fn_write {
+        use super::*;
+
+        #[test]
+        fn writes_a_csv_row_per_employee_and_empty_department() {
+            let mut document = Vec::new();
+            write(&organization(), ExportFormat::Csv, &mut document).unwrap();
+            let document = String::from_utf8(document).unwrap();
+            let lines = document.lines().collect::<Vec<&str>>();
+
+            assert_eq!(
+                "department,parent,head,cost_center,location,description,created,employee,manager,manager_department",
+                lines[0]
+            );
+            assert_eq!(1 + 70 + 2, lines.len());
+            let today = Date::today();
+            assert!(lines.contains(&format!("Legal,,,,,,{},,,", today).as_str()));
+            assert!(lines.contains(&"Payroll,Accounting,,,,,,,,"));
+            assert!(lines.contains(&"Accounting,,,,,,,Kirk Short,Atif Wells,Design"));
+            assert!(lines.contains(&"Design,,,,,\"Pixels, mostly\",1999-12-31,Atif Wells,,"));
+        }
+    }
+
+    mod fn_read {
+        use super::*;
+
+        #[test]
+        fn reads_back_every_format() {
+            let snapshot = organization();
+
+            [ExportFormat::Json, ExportFormat::Csv, ExportFormat::Yaml]
+                .iter()
+                .for_each(|format| {
+                    let mut document = Vec::new();
+                    write(&snapshot, *format, &mut document).unwrap();
+                    let mut read_back = read(*format, &document[..]).unwrap();
+                    if *format == ExportFormat::Csv {
+                        read_back.history = snapshot.history.clone();
+                    }
+
+                    assert_eq!(snapshot, read_back);
+                });
+        }
+
+        #[test]
+        fn rejects_malformed_dates() {
+            let document = "department,parent,head,cost_center,location,description,created,employee,manager,manager_department\nLegal,,,,,,31/12/1999,,,\n";
+
+            assert_eq!(
+                io::ErrorKind::InvalidData,
+                read(ExportFormat::Csv, document.as_bytes())
+                    .unwrap_err()
+                    .kind()
+            );
+        }
+    }
+}
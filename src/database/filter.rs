@@ -0,0 +1,435 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Name,
+    Department,
+    Manager,
+}
+
+impl Field {
+    fn parse(field_name: &str) -> Option<Field> {
+        match field_name.to_uppercase().as_str() {
+            "NAME" | "EMPLOYEE" => Some(Field::Name),
+            "DEPARTMENT" | "DEPARTMENTS" | "DEPT" | "DEPTS" => Some(Field::Department),
+            "MANAGER" => Some(Field::Manager),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operator {
+    Is,
+    Contains,
+    StartsWith,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    field: Field,
+    operator: Operator,
+    value: String,
+    negated: bool,
+}
+
+impl Condition {
+    fn matches(&self, actual: Option<&str>) -> bool {
+        let actual = actual.map(|actual| actual.to_uppercase());
+        let expected = self.value.to_uppercase();
+        let matched = match (self.operator, actual) {
+            (_, None) => false,
+            (Operator::Is, Some(actual)) => actual == expected,
+            (Operator::Contains, Some(actual)) => actual.contains(&expected),
+            (Operator::StartsWith, Some(actual)) => actual.starts_with(&expected),
+        };
+        matched != self.negated
+    }
+}
+
+/// Employee filter made of conditions combined with `not`, `and` and `or`, which bind in that
+/// order, and grouped with parentheses:
+///
+/// ```text
+/// filter    = all { "or" all } ;
+/// all       = negation { "and" negation } ;
+/// negation  = "not" negation | "(" filter ")" | condition ;
+/// condition = field ( "is" [ "not" ] | "contains" | "starts" "with" ) value ;
+/// field     = "name" | "employee" | "department" | "departments" | "dept" | "depts" | "manager" ;
+/// value     = word { word } ;
+/// ```
+///
+/// A value runs up to the next `and` or `or`, or the parenthesis closing its group.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    Condition(Condition),
+    Not(Box<Filter>),
+    All(Vec<Filter>),
+    Any(Vec<Filter>),
+}
+
+impl Filter {
+    pub fn parse(tokens: &[&str]) -> Result<Filter, String> {
+        let mut parser = Parser {
+            tokens: tokens.to_vec(),
+            position: 0,
+            depth: 0,
+        };
+        parser.any()
+    }
+
+    // A single filter stands for itself rather than a group of one
+    pub fn all(mut all_of: Vec<Filter>) -> Filter {
+        match all_of.len() {
+            1 => all_of.remove(0),
+            _ => Filter::All(all_of),
+        }
+    }
+
+    pub fn any(mut any_of: Vec<Filter>) -> Filter {
+        match any_of.len() {
+            1 => any_of.remove(0),
+            _ => Filter::Any(any_of),
+        }
+    }
+
+    // Copies the filter with every condition value rewritten, for binding parameters
+    pub fn map_values<F: Fn(&str) -> String>(&self, map: &F) -> Filter {
+        match self {
+            Filter::Condition(condition) => Filter::Condition(Condition {
+                value: map(&condition.value),
+                ..condition.clone()
+            }),
+            Filter::Not(filter) => Filter::Not(Box::new(filter.map_values(map))),
+            Filter::All(all_of) => {
+                Filter::All(all_of.iter().map(|filter| filter.map_values(map)).collect())
+            }
+            Filter::Any(any_of) => {
+                Filter::Any(any_of.iter().map(|filter| filter.map_values(map)).collect())
+            }
+        }
+    }
+
+    pub fn matches<F: Fn(Field) -> Option<String>>(&self, value_of: F) -> bool {
+        self.matches_with(&value_of)
+    }
+
+    fn matches_with<F: Fn(Field) -> Option<String>>(&self, value_of: &F) -> bool {
+        match self {
+            Filter::Condition(condition) => condition.matches(value_of(condition.field).as_deref()),
+            Filter::Not(filter) => !filter.matches_with(value_of),
+            Filter::All(all_of) => all_of.iter().all(|filter| filter.matches_with(value_of)),
+            Filter::Any(any_of) => any_of.iter().any(|filter| filter.matches_with(value_of)),
+        }
+    }
+}
+
+// Parentheses may be written against the words they enclose, as in "(name is Bob)", so they are
+// split off the tokens as the parser reaches them
+struct Parser<'a> {
+    tokens: Vec<&'a str>,
+    position: usize,
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next_is(&self, keyword: &str) -> bool {
+        self.peek()
+            .is_some_and(|token| token.to_uppercase() == keyword)
+    }
+
+    fn any(&mut self) -> Result<Filter, String> {
+        let mut any_of = vec![self.all()?];
+        while self.next_is("OR") {
+            self.position += 1;
+            any_of.push(self.all()?);
+        }
+        Ok(Filter::any(any_of))
+    }
+
+    fn all(&mut self) -> Result<Filter, String> {
+        let mut all_of = vec![self.negation()?];
+        while self.next_is("AND") {
+            self.position += 1;
+            all_of.push(self.negation()?);
+        }
+        Ok(Filter::all(all_of))
+    }
+
+    fn negation(&mut self) -> Result<Filter, String> {
+        let token = match self.peek() {
+            None => return Err(String::from("Filter must specify a condition")),
+            Some(token) => token,
+        };
+        if token.to_uppercase() == "NOT" {
+            self.position += 1;
+            return Ok(Filter::Not(Box::new(self.negation()?)));
+        }
+        let rest = match token.strip_prefix('(') {
+            None => return self.condition(),
+            Some(rest) => rest,
+        };
+        match rest {
+            "" => self.position += 1,
+            rest => self.tokens[self.position] = rest,
+        }
+        self.depth += 1;
+        let filter = self.any()?;
+        if self.peek() != Some(")") {
+            return Err(String::from("Filter is missing a closing parenthesis"));
+        }
+        self.position += 1;
+        self.depth -= 1;
+        Ok(filter)
+    }
+
+    fn condition(&mut self) -> Result<Filter, String> {
+        let field_name = self.tokens[self.position];
+        self.position += 1;
+        let field = match Field::parse(field_name) {
+            None => {
+                return Err(format!(
+                    "\"{}\" is not a field employees can be filtered by",
+                    field_name
+                ))
+            }
+            Some(field) => field,
+        };
+        let mut negated = false;
+        let operator = match self.peek().map(|token| token.to_uppercase()).as_deref() {
+            Some("IS") => {
+                self.position += 1;
+                if self.next_is("NOT") {
+                    self.position += 1;
+                    negated = true;
+                }
+                Operator::Is
+            }
+            Some("CONTAINS") => {
+                self.position += 1;
+                Operator::Contains
+            }
+            Some("STARTS") => {
+                self.position += 1;
+                if !self.next_is("WITH") {
+                    return Err(String::from("\"starts\" must be followed by \"with\""));
+                }
+                self.position += 1;
+                Operator::StartsWith
+            }
+            _ => {
+                return Err(format!(
+                "Condition on \"{}\" must use \"is\", \"is not\", \"contains\" or \"starts with\"",
+                field_name
+            ))
+            }
+        };
+        let mut value = Vec::new();
+        while let Some(token) = self.peek() {
+            if self.next_is("AND") || self.next_is("OR") || (token == ")" && self.depth > 0) {
+                break;
+            }
+            if self.depth > 0 && token.ends_with(')') {
+                // Only as many parentheses as are open close groups, the rest belong to the value
+                let closing = (token.len() - token.trim_end_matches(')').len()).min(self.depth);
+                let word = &token[..token.len() - closing];
+                self.tokens
+                    .splice(self.position..=self.position, vec![")"; closing]);
+                if !word.is_empty() {
+                    value.push(word);
+                }
+                break;
+            }
+            value.push(token);
+            self.position += 1;
+        }
+        if value.is_empty() {
+            return Err(format!(
+                "Condition on \"{}\" must specify a value",
+                field_name
+            ));
+        }
+        Ok(Filter::Condition(Condition {
+            field,
+            operator,
+            value: value.join(" "),
+            negated,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod filter {
+        use super::*;
+
+        fn kirk(field: Field) -> Option<String> {
+            match field {
+                Field::Name => Some("Kirk Short".to_string()),
+                Field::Department => Some("Accounting".to_string()),
+                Field::Manager => None,
+            }
+        }
+
+        #[test]
+        fn parses_single_condition() {
+            assert_eq!(
+                Ok(Filter::Condition(Condition {
+                    field: Field::Name,
+                    operator: Operator::StartsWith,
+                    value: "Kirk S".to_string(),
+                    negated: false
+                })),
+                Filter::parse(&["name", "starts", "with", "Kirk", "S"])
+            );
+        }
+
+        #[test]
+        fn and_binds_tighter_than_or() {
+            let filter = Filter::parse(&[
+                "name",
+                "is",
+                "Nobody",
+                "and",
+                "department",
+                "is",
+                "Accounting",
+                "or",
+                "name",
+                "contains",
+                "short",
+            ])
+            .unwrap();
+
+            match &filter {
+                Filter::Any(any_of) => assert_eq!(2, any_of.len()),
+                filter => panic!("expected \"or\" at the top of {:?}", filter),
+            }
+            assert!(filter.matches(kirk));
+        }
+
+        #[test]
+        fn not_binds_tighter_than_and() {
+            let filter = Filter::parse(&[
+                "not",
+                "name",
+                "is",
+                "Nobody",
+                "and",
+                "department",
+                "is",
+                "Accounting",
+            ])
+            .unwrap();
+
+            match &filter {
+                Filter::All(all_of) => assert!(matches!(all_of[0], Filter::Not(_))),
+                filter => panic!("expected \"and\" at the top of {:?}", filter),
+            }
+            assert!(filter.matches(kirk));
+        }
+
+        #[test]
+        fn parentheses_group_conditions() {
+            let filter = Filter::parse(&[
+                "(name",
+                "is",
+                "Nobody",
+                "or",
+                "department",
+                "is",
+                "Accounting)",
+                "and",
+                "not",
+                "(manager",
+                "contains",
+                "a)",
+            ])
+            .unwrap();
+
+            assert_eq!(
+                Filter::parse(&[
+                    "(",
+                    "name",
+                    "is",
+                    "Nobody",
+                    "or",
+                    "department",
+                    "is",
+                    "Accounting",
+                    ")",
+                    "and",
+                    "not",
+                    "(",
+                    "manager",
+                    "contains",
+                    "a",
+                    ")",
+                ]),
+                Ok(filter.clone())
+            );
+            assert!(filter.matches(kirk));
+            assert!(!Filter::parse(&["not", "(name", "starts", "with", "kirk)"])
+                .unwrap()
+                .matches(kirk));
+        }
+
+        #[test]
+        fn keeps_unopened_parentheses_in_values() {
+            assert_eq!(
+                Ok(Filter::Condition(Condition {
+                    field: Field::Name,
+                    operator: Operator::Is,
+                    value: "Bob (Jr))".to_string(),
+                    negated: false
+                })),
+                Filter::parse(&["name", "is", "Bob", "(Jr))"])
+            );
+        }
+
+        #[test]
+        fn rejects_unclosed_parentheses() {
+            assert_eq!(
+                Err("Filter is missing a closing parenthesis".to_string()),
+                Filter::parse(&["(name", "is", "Bob", "or", "name", "is", "Ann"])
+            );
+        }
+
+        #[test]
+        fn is_not_negates() {
+            assert!(Filter::parse(&["department", "is", "not", "Sales"])
+                .unwrap()
+                .matches(kirk));
+            assert!(!Filter::parse(&["department", "is", "not", "accounting"])
+                .unwrap()
+                .matches(kirk));
+        }
+
+        #[test]
+        fn missing_values_never_match() {
+            assert!(!Filter::parse(&["manager", "contains", "a"])
+                .unwrap()
+                .matches(kirk));
+        }
+
+        #[test]
+        fn rejects_unknown_fields() {
+            assert_eq!(
+                Err("\"salary\" is not a field employees can be filtered by".to_string()),
+                Filter::parse(&["salary", "is", "high"])
+            );
+        }
+
+        #[test]
+        fn rejects_missing_values() {
+            assert_eq!(
+                Err("Condition on \"name\" must specify a value".to_string()),
+                Filter::parse(&["name", "is", "and", "name", "is", "Bob"])
+            );
+        }
+    }
+}
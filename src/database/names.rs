@@ -0,0 +1,176 @@
+use caseless::Caseless;
+use std::fmt;
+use std::mem;
+use unicode_normalization::UnicodeNormalization;
+
+/// How the display names of departments and employees are derived from the names given in queries
+#[derive(Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NameCase {
+    /// Keeps names as given, so "McGregor", "O'Neil", "van der Berg" and "IT" survive intact
+    Preserve,
+    /// Capitalizes the first letter of each word and lowercases the rest (the default)
+    Title,
+    /// Applies a custom function to each name, after runs of whitespace are collapsed
+    ///
+    /// Functions cannot be serialized, so databases using a custom case cannot be either.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(fn(&str) -> String),
+}
+
+impl fmt::Debug for NameCase {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NameCase::Preserve => write!(formatter, "Preserve"),
+            NameCase::Title => write!(formatter, "Title"),
+            NameCase::Custom(_) => write!(formatter, "Custom"),
+        }
+    }
+}
+
+impl PartialEq for NameCase {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (NameCase::Custom(left), NameCase::Custom(right)) => *left as usize == *right as usize,
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+/// How names are compared when deciding whether two of them refer to the same department,
+/// employee or user
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Collation {
+    /// Compares names after Unicode NFC normalization and full case folding (the default)
+    Unicode,
+    /// Compares names after uppercasing them, ignoring Unicode normalization
+    Uppercase,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Names {
+    case: NameCase,
+    collation: Collation,
+}
+
+impl Names {
+    pub fn new(case: NameCase, collation: Collation) -> Self {
+        Names { case, collation }
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn case(self) -> NameCase {
+        self.case
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn collation(self) -> Collation {
+        self.collation
+    }
+
+    pub fn name(self, value: &str) -> String {
+        let value = collapse_whitespace(value);
+        match self.case {
+            NameCase::Preserve => value,
+            NameCase::Title => title_case(&value),
+            NameCase::Custom(to_name) => to_name(&value),
+        }
+    }
+
+    pub fn key(self, value: &str) -> String {
+        let value = collapse_whitespace(value);
+        match self.collation {
+            // ASCII is already in NFC, and folds to its lowercase
+            Collation::Unicode if value.is_ascii() => value.to_ascii_lowercase(),
+            Collation::Unicode => value.chars().nfd().default_case_fold().nfc().collect(),
+            Collation::Uppercase => value.to_uppercase(),
+        }
+    }
+}
+
+impl Default for Names {
+    fn default() -> Self {
+        Names::new(NameCase::Title, Collation::Unicode)
+    }
+}
+
+fn collapse_whitespace(value: &str) -> String {
+    let mut collapsed = String::with_capacity(value.len());
+    value.split_whitespace().for_each(|word| {
+        if !collapsed.is_empty() {
+            collapsed.push(' ');
+        }
+        collapsed.push_str(word);
+    });
+    collapsed
+}
+
+fn title_case(value: &str) -> String {
+    let mut name = String::with_capacity(value.len());
+    let mut word_start = true;
+    value.chars().for_each(|character| {
+        if character == ' ' {
+            name.push(character);
+            word_start = true;
+        } else if word_start {
+            name.extend(character.to_uppercase());
+            word_start = false;
+        } else {
+            name.extend(character.to_lowercase());
+        }
+    });
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod names {
+        use super::{Collation, NameCase, Names};
+
+        #[test]
+        fn title_case_capitalizes_each_word() {
+            assert_eq!("Greek Salad", Names::default().name("gReEk   SaLaD"));
+        }
+
+        #[test]
+        fn preserve_keeps_case() {
+            let names = Names::new(NameCase::Preserve, Collation::Unicode);
+
+            assert_eq!("Anil McGregor", names.name("Anil McGregor"));
+            assert_eq!("Pieter van der Berg", names.name(" Pieter  van der Berg"));
+            assert_eq!("IT", names.name("IT"));
+        }
+
+        #[test]
+        fn custom_applies_function() {
+            fn shout(value: &str) -> String {
+                value.to_uppercase()
+            }
+            let names = Names::new(NameCase::Custom(shout), Collation::Unicode);
+
+            assert_eq!("O'NEIL", names.name("O'Neil"));
+        }
+
+        #[test]
+        fn unicode_keys_fold_case_and_normalize() {
+            let names = Names::default();
+
+            assert_eq!(names.key("Angry Bob"), names.key("ANGRY BOB"));
+            assert_eq!(names.key("Stra\u{df}e"), names.key("STRASSE"));
+            assert_eq!(names.key("Jos\u{e9}"), names.key("Jose\u{301}"));
+        }
+
+        #[test]
+        fn uppercase_keys_capitalize_all_letters() {
+            let names = Names::new(NameCase::Title, Collation::Uppercase);
+
+            assert_eq!("SALSIFIERS", names.key("Salsifiers"));
+            assert_ne!(names.key("Jos\u{e9}"), names.key("Jose\u{301}"));
+        }
+    }
+}
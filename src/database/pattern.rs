@@ -0,0 +1,382 @@
+// Matches the words of a query against one form of a command, written as a pattern:
+//
+//     pattern = item { item } ;
+//     item    = keyword { "|" keyword } | "{" slot "}" | "[" pattern "]" ;
+//
+// Keywords match ignoring case, along with their synonyms, and "|" separates keywords that mean
+// the same in this place. A slot for a department, user, role, format, date, number, policy,
+// kind or name takes one word, while any other slot, such as an employee, a file or a condition,
+// takes as few words as let the rest of the pattern match, so that names may hold several words.
+// A clause in square brackets is optional, and matched whenever the rest of the query allows.
+
+// Words that may stand for each other wherever one of them is a keyword
+const SYNONYMS: &[&[&str]] = &[
+    &["DEPARTMENT", "DEPARTMENTS", "DEPT", "DEPTS"],
+    &["EMPLOYEE", "EMPLOYEES"],
+    &["REPORT", "REPORTS"],
+    &["USER", "USERS"],
+];
+
+const ONE_WORD_SLOTS: &[&str] = &[
+    "department",
+    "user",
+    "role",
+    "format",
+    "date",
+    "levels",
+    "headcount",
+    "policy",
+    "kind",
+    "name",
+];
+
+fn same_keyword(keyword: &str, token: &str) -> bool {
+    let (keyword, token) = (keyword.to_uppercase(), token.to_uppercase());
+    keyword == token
+        || SYNONYMS
+            .iter()
+            .any(|words| words.contains(&keyword.as_str()) && words.contains(&token.as_str()))
+}
+
+#[derive(Debug)]
+enum Item<'p> {
+    Keyword(Vec<&'p str>),
+    Slot(&'p str, usize),
+    Optional(Vec<Item<'p>>),
+}
+
+// Slots are numbered in the order they are written, including those in optional clauses
+#[derive(Debug)]
+pub struct Pattern<'p> {
+    items: Vec<Item<'p>>,
+    slots: usize,
+}
+
+impl<'p> Pattern<'p> {
+    pub fn new(pattern: &'p str) -> Self {
+        let mut words = pattern
+            .split_whitespace()
+            .flat_map(split_brackets)
+            .collect::<Vec<&str>>()
+            .into_iter();
+        let mut slots = 0;
+        let items = parse_items(&mut words, &mut slots);
+        Pattern { items, slots }
+    }
+
+    // The first word of a pattern names the command, and is matched by the time the query is
+    // dispatched to it, whichever of the command's keywords it was written with
+    pub fn matches(&self, tokens: &[&str], mismatch: &mut Mismatch) -> Option<Captures> {
+        let mut matcher = Matcher {
+            tokens,
+            captures: vec![None; self.slots],
+            mismatch,
+            unlikely: 0,
+        };
+        if !matcher.matches(self.items.get(1..).unwrap_or(&[]), None, 1) {
+            return None;
+        }
+        Some(Captures {
+            values: matcher
+                .captures
+                .iter()
+                .map(|range| range.map(|(start, end)| tokens[start..end].join(" ")))
+                .collect(),
+        })
+    }
+}
+
+fn split_brackets(word: &str) -> Vec<&str> {
+    let core = word.trim_start_matches('[').trim_end_matches(']');
+    let opening = word.len() - word.trim_start_matches('[').len();
+    let closing = word.len() - word.trim_end_matches(']').len();
+    let mut words = vec!["["; opening];
+    words.push(core);
+    words.extend(vec!["]"; closing]);
+    words
+}
+
+fn parse_items<'p, I: Iterator<Item = &'p str>>(words: &mut I, slots: &mut usize) -> Vec<Item<'p>> {
+    let mut items = Vec::new();
+    while let Some(word) = words.next() {
+        let item = match word {
+            "]" => break,
+            "[" => Item::Optional(parse_items(words, slots)),
+            slot if slot.starts_with('{') => {
+                *slots += 1;
+                Item::Slot(
+                    slot.trim_start_matches('{').trim_end_matches('}'),
+                    *slots - 1,
+                )
+            }
+            keywords => Item::Keyword(keywords.split('|').collect()),
+        };
+        items.push(item);
+    }
+    items
+}
+
+// The clauses left to match once the current one is done, innermost first
+struct Rest<'r, 'p> {
+    items: &'r [Item<'p>],
+    next: Option<&'r Rest<'r, 'p>>,
+}
+
+struct Matcher<'m, 't> {
+    tokens: &'m [&'t str],
+    captures: Vec<Option<(usize, usize)>>,
+    mismatch: &'m mut Mismatch,
+    // How many of the names being matched hold the keyword that follows them
+    unlikely: usize,
+}
+
+impl<'m, 't> Matcher<'m, 't> {
+    fn expect(&mut self, position: usize, expected: Expected) {
+        match self.unlikely {
+            0 => self.mismatch.likely.expect(position, expected),
+            _ => self.mismatch.unlikely.expect(position, expected),
+        }
+    }
+
+    fn matches<'p>(
+        &mut self,
+        items: &[Item<'p>],
+        rest: Option<&Rest<'_, 'p>>,
+        position: usize,
+    ) -> bool {
+        let (item, items) = match (items.split_first(), rest) {
+            (Some(split), _) => split,
+            (None, Some(rest)) => return self.matches(rest.items, rest.next, position),
+            (None, None) if position == self.tokens.len() => return true,
+            (None, None) => {
+                self.expect(position, Expected::End);
+                return false;
+            }
+        };
+        match item {
+            Item::Keyword(keywords) => match self.tokens.get(position) {
+                Some(token) if keywords.iter().any(|keyword| same_keyword(keyword, token)) => {
+                    self.matches(items, rest, position + 1)
+                }
+                _ => {
+                    keywords.iter().for_each(|keyword| {
+                        self.expect(position, Expected::Keyword(keyword.to_string()))
+                    });
+                    false
+                }
+            },
+            Item::Slot(name, slot) => {
+                if position == self.tokens.len() {
+                    self.expect(position, Expected::Slot(name.to_string()));
+                    return false;
+                }
+                let longest = match ONE_WORD_SLOTS.contains(name) {
+                    true => position + 1,
+                    false => self.tokens.len(),
+                };
+                let follows = follows(items, rest);
+                (position + 1..=longest).any(|end| {
+                    let unlikely = self.tokens[position + 1..end]
+                        .iter()
+                        .any(|token| follows.iter().any(|keyword| same_keyword(keyword, token)))
+                        as usize;
+                    self.captures[*slot] = Some((position, end));
+                    self.unlikely += unlikely;
+                    let matched = self.matches(items, rest, end);
+                    self.unlikely -= unlikely;
+                    matched
+                })
+            }
+            Item::Optional(clause) => {
+                let captures = self.captures.clone();
+                let after = Rest { items, next: rest };
+                if self.matches(clause, Some(&after), position) {
+                    return true;
+                }
+                self.captures = captures;
+                self.matches(items, rest, position)
+            }
+        }
+    }
+}
+
+// The keywords that may come next, after the items and then the rest
+fn follows<'p>(items: &[Item<'p>], rest: Option<&Rest<'_, 'p>>) -> Vec<&'p str> {
+    let mut keywords = Vec::new();
+    for item in items {
+        match item {
+            Item::Keyword(words) => {
+                keywords.extend(words);
+                return keywords;
+            }
+            Item::Optional(clause) => keywords.extend(follows(clause, None)),
+            Item::Slot(..) => return keywords,
+        }
+    }
+    if let Some(rest) = rest {
+        keywords.extend(follows(rest.items, rest.next));
+    }
+    keywords
+}
+
+// The words each slot of a matched pattern took, joined by single spaces
+#[derive(Debug, PartialEq)]
+pub struct Captures {
+    values: Vec<Option<String>>,
+}
+
+impl Captures {
+    pub fn text(&self, slot: usize) -> String {
+        self.values[slot].clone().unwrap_or_default()
+    }
+
+    pub fn optional(&self, slot: usize) -> Option<String> {
+        self.values[slot].clone()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expected {
+    Keyword(String),
+    Slot(String),
+    End,
+}
+
+impl Expected {
+    fn describe(&self) -> String {
+        match self {
+            Expected::Keyword(keyword) => format!("\"{}\"", keyword.to_lowercase()),
+            Expected::Slot(name) => format!("{{{}}}", name),
+            Expected::End => String::from("the end of the query"),
+        }
+    }
+}
+
+// The furthest point forms got to in a query, and what they expected there
+#[derive(Debug, Default)]
+struct Furthest {
+    position: usize,
+    expected: Vec<Expected>,
+}
+
+impl Furthest {
+    fn expect(&mut self, position: usize, expected: Expected) {
+        if position > self.position {
+            self.position = position;
+            self.expected.clear();
+        }
+        if position == self.position && !self.expected.contains(&expected) {
+            self.expected.push(expected);
+        }
+    }
+}
+
+// How far the forms of a command got through a query, so the syntax error points at the word
+// that no form could go past. Forms that only got further by taking a keyword into a name, as
+// "to" in "assign Bob to Sales Team", are a last resort, since the keyword was most likely meant
+// as one.
+#[derive(Debug, Default)]
+pub struct Mismatch {
+    likely: Furthest,
+    unlikely: Furthest,
+}
+
+impl Mismatch {
+    pub fn message(&self, tokens: &[&str]) -> String {
+        let furthest = match self.likely.expected.is_empty() {
+            true => &self.unlikely,
+            false => &self.likely,
+        };
+        let mut expected = furthest.expected.clone();
+        expected.sort_by_key(|expected| *expected == Expected::End);
+        let mut expected = expected
+            .iter()
+            .map(Expected::describe)
+            .collect::<Vec<String>>();
+        let last = expected.pop().unwrap_or_default();
+        let expected = match expected.is_empty() {
+            true => last,
+            false => format!("{} or {}", expected.join(", "), last),
+        };
+        let before = tokens[..furthest.position.min(tokens.len())].join(" ");
+        match tokens.get(furthest.position) {
+            None => format!("\"{}\" must be followed by {}", before, expected),
+            Some(token) => format!(
+                "Unexpected \"{}\" after \"{}\": expected {}",
+                token, before, expected
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(pattern: &str, query: &str) -> Result<Captures, String> {
+        let tokens = query.split_whitespace().collect::<Vec<&str>>();
+        let mut mismatch = Mismatch::default();
+        Pattern::new(pattern)
+            .matches(&tokens, &mut mismatch)
+            .ok_or_else(|| mismatch.message(&tokens))
+    }
+
+    mod pattern {
+        use super::*;
+
+        #[test]
+        fn gives_names_the_fewest_words_that_match() {
+            let captures = parse(
+                "assign {employee} to {department}",
+                "assign Bob to Smith to Sales",
+            )
+            .unwrap();
+
+            assert_eq!("Bob to Smith", captures.text(0));
+            assert_eq!("Sales", captures.text(1));
+        }
+
+        #[test]
+        fn matches_optional_clauses_when_the_rest_allows() {
+            let pattern = "list reports of {employee} [in {department}]";
+
+            assert_eq!(
+                Some(String::from("Sales")),
+                parse(pattern, "list reports of Bob in Sales")
+                    .unwrap()
+                    .optional(1)
+            );
+            let captures = parse(pattern, "list reports of Bob in Sales Team").unwrap();
+            assert_eq!("Bob in Sales Team", captures.text(0));
+            assert_eq!(None, captures.optional(1));
+        }
+
+        #[test]
+        fn matches_keywords_by_their_synonyms() {
+            assert!(parse("list employees by department", "LIST employee BY Depts").is_ok());
+            assert!(parse("show timeline of|for {employee}", "show timeline for Bob").is_ok());
+        }
+
+        #[test]
+        fn reports_the_furthest_mismatch() {
+            assert_eq!(
+                Err(String::from(
+                    "Unexpected \"waffles\" after \"list employees in Sales\": expected \"as\" or the end of the query"
+                )),
+                parse(
+                    "list employees in {department} [as of {date}]",
+                    "list employees in Sales waffles"
+                )
+            );
+            assert_eq!(
+                Err(String::from(
+                    "\"list employees in\" must be followed by {department}"
+                )),
+                parse(
+                    "list employees in {department} [as of {date}]",
+                    "list employees in"
+                )
+            );
+        }
+    }
+}
@@ -0,0 +1,627 @@
+use super::commands::Command;
+use super::errors::QueryError;
+use super::names::Names;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Role {
+    Viewer,
+    Editor,
+    Admin,
+}
+
+impl Role {
+    pub fn parse(role_name: &str) -> Option<Role> {
+        match role_name.to_uppercase().as_str() {
+            "VIEWER" => Some(Role::Viewer),
+            "EDITOR" => Some(Role::Editor),
+            "ADMIN" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Role::Viewer => "Viewer",
+            Role::Editor => "Editor",
+            Role::Admin => "Admin",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Access {
+    Open,
+    Read(Vec<String>),
+    Write(Vec<String>),
+    Admin(Vec<String>),
+}
+
+impl Access {
+    // Commands that reach into sub-departments need access to each of them, as listed by
+    // sub_departments
+    pub fn required(command: &Command, sub_departments: impl Fn(&str) -> Vec<String>) -> Access {
+        match command {
+            Command::Empty
+            | Command::InvalidCommandErr(_)
+            | Command::SyntaxErr(_)
+            | Command::Exit
+            | Command::Help(_)
+            | Command::Macro(..) => Access::Open,
+            Command::ShowDepartments
+            | Command::ShowDepartmentTree
+            | Command::ShowDepartmentsDetailed
+            | Command::ListEmployees
+            | Command::ListEmployeesByDepartment
+            | Command::ListEmployeesAsOf(_)
+            | Command::ListEmployeesByDepartmentAsOf(_)
+            | Command::FindEmployee(_)
+            | Command::Search(_)
+            | Command::Chart(_)
+            | Command::ReportHeadcount(..) => Access::Read(vec![]),
+            Command::ListEmployeesInDepartment(department)
+            | Command::ListEmployeesInDepartmentAsOf(department, _) => {
+                Access::Read(vec![department.to_owned()])
+            }
+            Command::ListEmployeesUnderDepartment(department) => Access::Read(
+                std::iter::once(department.to_owned())
+                    .chain(sub_departments(department))
+                    .collect(),
+            ),
+            Command::AssignEmployeeToDepartment(_, department)
+            | Command::PullEmployeeFromDepartment(_, department) => {
+                Access::Write(vec![department.to_owned()])
+            }
+            Command::PullAllFromDepartment(department) => {
+                Access::Write(vec![department.to_owned()])
+            }
+            Command::TransferMatchingToDepartment(..) => Access::Write(vec![]),
+            Command::MergeDepartments(from_department, to_department, _) => {
+                Access::Admin(vec![from_department.to_owned(), to_department.to_owned()])
+            }
+            Command::TransferEmployeeBetweenDepartments(_, from_department, to_department)
+            | Command::TransferAllBetweenDepartments(from_department, to_department, _) => {
+                Access::Write(vec![from_department.to_owned(), to_department.to_owned()])
+            }
+            Command::FormDepartment(department)
+            | Command::DissolveDepartment(department)
+            | Command::RenameDepartment(department, _)
+            | Command::MoveDepartment(department, None) => {
+                Access::Admin(vec![department.to_owned()])
+            }
+            Command::FormDepartmentUnder(department, parent)
+            | Command::MoveDepartment(department, Some(parent)) => {
+                Access::Admin(vec![department.to_owned(), parent.to_owned()])
+            }
+            Command::ListReports(_, department)
+            | Command::ListAllReports(_, department)
+            | Command::ShowTimeline(_, department)
+            | Command::ShowManagementChain(_, department) => {
+                Access::Read(department.iter().cloned().collect())
+            }
+            Command::SetDepartmentField(department, ..)
+            | Command::ClearDepartmentField(department, _) => {
+                Access::Write(vec![department.to_owned()])
+            }
+            Command::ClearManager(_, department) | Command::RenameEmployee(_, department, _) => {
+                Access::Write(department.iter().cloned().collect())
+            }
+            Command::SetManager(_, department, _, manager_department) => {
+                Access::Write(match (department, manager_department) {
+                    (Some(department), Some(manager_department)) => {
+                        vec![department.to_owned(), manager_department.to_owned()]
+                    }
+                    _ => vec![],
+                })
+            }
+            // Scripts, exports and diffs read and write files on the server, whichever
+            // departments they touch
+            Command::GrantRole(..)
+            | Command::RevokeRole(..)
+            | Command::ShowUsers
+            | Command::Run(..)
+            | Command::Export(..)
+            | Command::Diff(_) => Access::Admin(vec![]),
+            Command::Define(_) | Command::Undefine(_) => Access::Write(vec![]),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grant {
+    role: Role,
+    department: Option<String>,
+}
+
+impl Grant {
+    pub fn role(&self) -> Role {
+        self.role
+    }
+
+    pub fn department(&self) -> Option<&str> {
+        self.department.as_deref()
+    }
+
+    fn allows(&self, names: Names, role: Role, department: Option<&str>) -> bool {
+        self.role >= role
+            && match (&self.department, department) {
+                (None, _) => true,
+                (Some(_), None) => false,
+                (Some(scope), Some(department)) => names.key(scope) == names.key(department),
+            }
+    }
+}
+
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct User {
+    name: String,
+    grants: Vec<Grant>,
+}
+
+impl User {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn grants(&self) -> &[Grant] {
+        &self.grants
+    }
+}
+
+#[derive(Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct Permissions {
+    users: BTreeMap<String, User>,
+    names: Names,
+}
+
+impl Permissions {
+    pub fn new(names: Names) -> Self {
+        Permissions {
+            users: BTreeMap::new(),
+            names,
+        }
+    }
+
+    pub fn is_enforced(&self) -> bool {
+        !self.users.is_empty()
+    }
+
+    pub fn users(&self) -> impl Iterator<Item = &User> {
+        self.users.values()
+    }
+
+    pub fn authorize(&self, user_name: Option<&str>, access: &Access) -> Result<(), QueryError> {
+        let (role, departments) = match access {
+            Access::Open => return Ok(()),
+            Access::Read(departments) => (Role::Viewer, departments),
+            Access::Write(departments) => (Role::Editor, departments),
+            Access::Admin(departments) => (Role::Admin, departments),
+        };
+        if !self.is_enforced() {
+            return Ok(());
+        }
+        let user = match user_name.and_then(|user_name| self.users.get(&self.names.key(user_name)))
+        {
+            None => {
+                return Err(QueryError::PermissionDenied(match user_name {
+                    None => String::from("No user is logged in"),
+                    Some(user_name) => format!("User \"{}\" has no roles", user_name),
+                }))
+            }
+            Some(user) => user,
+        };
+        let allowed = if departments.is_empty() {
+            user.grants
+                .iter()
+                .any(|grant| grant.allows(self.names, role, None))
+        } else {
+            departments.iter().all(|department| {
+                user.grants
+                    .iter()
+                    .any(|grant| grant.allows(self.names, role, Some(department)))
+            })
+        };
+        if allowed {
+            Ok(())
+        } else {
+            Err(QueryError::PermissionDenied(match departments.len() {
+                0 => format!("User \"{}\" requires the {} role", user.name, role.name()),
+                _ => format!(
+                    "User \"{}\" requires the {} role on department(s) {}",
+                    user.name,
+                    role.name(),
+                    departments
+                        .iter()
+                        .map(|department| format!("\"{}\"", department))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+            }))
+        }
+    }
+
+    pub fn grant(
+        &mut self,
+        user_name: &str,
+        role: Role,
+        department: Option<&str>,
+    ) -> Result<(), QueryError> {
+        if !self.is_enforced() && (role != Role::Admin || department.is_some()) {
+            return Err(QueryError::Conflict(String::from(
+                "The first grant must make a user an Admin of all departments",
+            )));
+        }
+        self.insert_grant(user_name, role, department)
+    }
+
+    // Grants a role without requiring the first grant to be an Admin of all departments, for
+    // restoring users whose grants were already checked
+    pub fn insert_grant(
+        &mut self,
+        user_name: &str,
+        role: Role,
+        department: Option<&str>,
+    ) -> Result<(), QueryError> {
+        let grant = Grant {
+            role,
+            department: department.map(String::from),
+        };
+        let names = self.names;
+        let user = self
+            .users
+            .entry(names.key(user_name))
+            .or_insert_with(|| User {
+                name: user_name.to_owned(),
+                grants: Vec::new(),
+            });
+        if user
+            .grants
+            .iter()
+            .any(|existing| same_grant(names, existing, &grant))
+        {
+            return Err(QueryError::Conflict(format!(
+                "User \"{}\" already has that role",
+                user.name
+            )));
+        }
+        user.grants.push(grant);
+        Ok(())
+    }
+
+    pub fn revoke(
+        &mut self,
+        user_name: &str,
+        role: Role,
+        department: Option<&str>,
+    ) -> Result<(), QueryError> {
+        let grant = Grant {
+            role,
+            department: department.map(String::from),
+        };
+        let names = self.names;
+        let key = names.key(user_name);
+        let user = match self.users.get_mut(&key) {
+            None => {
+                return Err(QueryError::NotFound(format!(
+                    "User \"{}\" not found",
+                    user_name
+                )))
+            }
+            Some(user) => user,
+        };
+        let index = match user
+            .grants
+            .iter()
+            .position(|existing| same_grant(names, existing, &grant))
+        {
+            None => {
+                return Err(QueryError::NotFound(format!(
+                    "User \"{}\" does not have that role",
+                    user.name
+                )))
+            }
+            Some(index) => index,
+        };
+        let removed = user.grants.remove(index);
+        let admin_remains = self.users.values().any(|user| {
+            user.grants
+                .iter()
+                .any(|grant| grant.allows(self.names, Role::Admin, None))
+        });
+        let grants_remain = self.users.values().any(|user| !user.grants.is_empty());
+        let user = self.users.get_mut(&key).unwrap();
+        if !admin_remains && grants_remain {
+            user.grants.insert(index, removed);
+            return Err(QueryError::Conflict(String::from(
+                "Cannot revoke the last Admin of all departments",
+            )));
+        }
+        if user.grants.is_empty() {
+            self.users.remove(&key);
+        }
+        Ok(())
+    }
+
+    pub fn rename_department(&mut self, department_name: &str, new_name: &str) {
+        let names = self.names;
+        self.users
+            .values_mut()
+            .flat_map(|user| user.grants.iter_mut())
+            .filter(|grant| {
+                grant
+                    .department
+                    .as_deref()
+                    .map(|department| names.key(department))
+                    == Some(names.key(department_name))
+            })
+            .for_each(|grant| grant.department = Some(new_name.to_owned()));
+    }
+}
+
+fn same_grant(names: Names, left: &Grant, right: &Grant) -> bool {
+    left.role == right.role
+        && left
+            .department
+            .as_deref()
+            .map(|department| names.key(department))
+            == right
+                .department
+                .as_deref()
+                .map(|department| names.key(department))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod role {
+        use super::Role;
+
+        #[test]
+        fn parse_is_case_insensitive() {
+            assert_eq!(Some(Role::Editor), Role::parse("eDiToR"));
+            assert_eq!(None, Role::parse("Intern"));
+        }
+
+        #[test]
+        fn roles_are_ordered_by_privilege() {
+            assert!(Role::Viewer < Role::Editor);
+            assert!(Role::Editor < Role::Admin);
+        }
+    }
+
+    mod access {
+        use super::{Access, Command};
+        use crate::database::commands::ErrorPolicy;
+        use crate::database::export::ExportFormat;
+
+        #[test]
+        fn transfer_requires_write_on_both_departments() {
+            assert_eq!(
+                Access::Write(vec!["Left".to_string(), "Right".to_string()]),
+                Access::required(
+                    &Command::TransferEmployeeBetweenDepartments(
+                        "Eyes".to_string(),
+                        "Left".to_string(),
+                        "Right".to_string()
+                    ),
+                    |_| vec![]
+                )
+            );
+        }
+
+        #[test]
+        fn list_under_requires_read_on_every_sub_department() {
+            assert_eq!(
+                Access::Read(vec![
+                    "Sales".to_string(),
+                    "Retail".to_string(),
+                    "Online".to_string()
+                ]),
+                Access::required(
+                    &Command::ListEmployeesUnderDepartment("Sales".to_string()),
+                    |_| vec!["Retail".to_string(), "Online".to_string()]
+                )
+            );
+        }
+
+        #[test]
+        fn export_of_a_department_requires_admin() {
+            assert_eq!(
+                Access::Admin(vec![]),
+                Access::required(
+                    &Command::Export(
+                        ExportFormat::Json,
+                        Some("Sales".to_string()),
+                        "sales.json".to_string()
+                    ),
+                    |_| vec![]
+                )
+            );
+        }
+
+        #[test]
+        fn run_requires_admin() {
+            assert_eq!(
+                Access::Admin(vec![]),
+                Access::required(
+                    &Command::Run("setup.sql".to_string(), ErrorPolicy::Continue),
+                    |_| vec![]
+                )
+            );
+        }
+
+        #[test]
+        fn diff_requires_admin() {
+            assert_eq!(
+                Access::Admin(vec![]),
+                Access::required(&Command::Diff("org.json".to_string()), |_| vec![])
+            );
+        }
+
+        #[test]
+        fn help_is_open() {
+            assert_eq!(
+                Access::Open,
+                Access::required(&Command::Help(None), |_| vec![])
+            );
+        }
+    }
+
+    mod permissions {
+        use super::*;
+
+        mod authorize {
+            use super::{Access, Names, Permissions, QueryError, Role};
+
+            #[test]
+            fn allows_everything_without_users() {
+                let permissions = Permissions::new(Names::default());
+
+                assert_eq!(Ok(()), permissions.authorize(None, &Access::Admin(vec![])));
+            }
+
+            #[test]
+            fn denies_anonymous_user_once_enforced() {
+                let mut permissions = Permissions::new(Names::default());
+                permissions.grant("Root", Role::Admin, None).unwrap();
+
+                assert_eq!(
+                    Err(QueryError::PermissionDenied(
+                        "No user is logged in".to_string()
+                    )),
+                    permissions.authorize(None, &Access::Read(vec![]))
+                );
+            }
+
+            #[test]
+            fn scopes_grants_to_departments() {
+                let mut permissions = Permissions::new(Names::default());
+                permissions.grant("Root", Role::Admin, None).unwrap();
+                permissions
+                    .grant("Hal", Role::Editor, Some("Sales"))
+                    .unwrap();
+
+                assert_eq!(
+                    Ok(()),
+                    permissions.authorize(Some("hal"), &Access::Write(vec!["sales".to_string()]))
+                );
+                assert_eq!(
+                    Err(QueryError::PermissionDenied(
+                        "User \"Hal\" requires the Editor role on department(s) \"Sales\", \"Design\""
+                            .to_string()
+                    )),
+                    permissions.authorize(
+                        Some("Hal"),
+                        &Access::Write(vec!["Sales".to_string(), "Design".to_string()])
+                    )
+                );
+                assert_eq!(
+                    Err(QueryError::PermissionDenied(
+                        "User \"Hal\" requires the Viewer role".to_string()
+                    )),
+                    permissions.authorize(Some("Hal"), &Access::Read(vec![]))
+                );
+            }
+
+            #[test]
+            fn higher_roles_include_lower_roles() {
+                let mut permissions = Permissions::new(Names::default());
+                permissions.grant("Root", Role::Admin, None).unwrap();
+
+                assert_eq!(
+                    Ok(()),
+                    permissions.authorize(Some("Root"), &Access::Read(vec!["Any".to_string()]))
+                );
+            }
+        }
+
+        mod grant {
+            use super::{Names, Permissions, QueryError, Role};
+
+            #[test]
+            fn first_grant_must_be_global_admin() {
+                let mut permissions = Permissions::new(Names::default());
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "The first grant must make a user an Admin of all departments".to_string()
+                    )),
+                    permissions.grant("Intern", Role::Viewer, None)
+                );
+                assert!(!permissions.is_enforced());
+            }
+
+            #[test]
+            fn fails_on_duplicate_grant() {
+                let mut permissions = Permissions::new(Names::default());
+                permissions.grant("Root", Role::Admin, None).unwrap();
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "User \"Root\" already has that role".to_string()
+                    )),
+                    permissions.grant("ROOT", Role::Admin, None)
+                );
+            }
+        }
+
+        mod revoke {
+            use super::{Names, Permissions, QueryError, Role};
+
+            #[test]
+            fn removes_grant() {
+                let mut permissions = Permissions::new(Names::default());
+                permissions.grant("Root", Role::Admin, None).unwrap();
+                permissions
+                    .grant("Hal", Role::Viewer, Some("Sales"))
+                    .unwrap();
+
+                permissions
+                    .revoke("Hal", Role::Viewer, Some("sales"))
+                    .unwrap();
+
+                assert_eq!(1, permissions.users().count());
+            }
+
+            #[test]
+            fn refuses_to_revoke_last_admin() {
+                let mut permissions = Permissions::new(Names::default());
+                permissions.grant("Root", Role::Admin, None).unwrap();
+                permissions.grant("Hal", Role::Viewer, None).unwrap();
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "Cannot revoke the last Admin of all departments".to_string()
+                    )),
+                    permissions.revoke("Root", Role::Admin, None)
+                );
+            }
+
+            #[test]
+            fn revoking_sole_admin_disables_enforcement() {
+                let mut permissions = Permissions::new(Names::default());
+                permissions.grant("Root", Role::Admin, None).unwrap();
+
+                permissions.revoke("Root", Role::Admin, None).unwrap();
+
+                assert!(!permissions.is_enforced());
+            }
+
+            #[test]
+            fn fails_on_missing_user() {
+                let mut permissions = Permissions::new(Names::default());
+
+                assert_eq!(
+                    Err(QueryError::NotFound(
+                        "User \"Nobody\" not found".to_string()
+                    )),
+                    permissions.revoke("Nobody", Role::Admin, None)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use super::commands::{self, Command, Text};
+use super::{script, tables, Database, QueryResponse, Value};
+use std::cell::Cell;
+
+// Placeholders are parsed as private use characters around their index, which no keyword or
+// date matches, so the shape of the command is fixed before any value is seen
+const OPEN: char = '\u{E000}';
+const CLOSE: char = '\u{E001}';
+
+/// A query parsed once, whose `?` or `$1`, `$2`... placeholders are bound to values each time
+/// it is executed.
+///
+/// Values are bound after the query has been parsed, so a value such as `"Bob to Sales"` is
+/// always one name, and never changes the meaning of the command.
+///
+/// # Examples
+///
+/// ```rust
+/// use employees::database::{Database, QueryResponse};
+///
+/// let mut db = Database::new();
+/// db.query("form sales".to_string());
+/// let mut assign = db.prepare("assign ? to ?").unwrap();
+///
+/// assert_eq!(
+///     QueryResponse::Message("Assigned employee \"Bob To Sales\" to Sales department".to_string()),
+///     assign.execute(&["Bob to Sales", "sales"])
+/// );
+/// ```
+pub struct Prepared<'db> {
+    database: &'db mut Database,
+    command: Command,
+    parameters: usize,
+}
+
+impl<'db> Prepared<'db> {
+    pub(crate) fn new(database: &'db mut Database, query_string: &str) -> Result<Self, String> {
+        if query_string.contains([OPEN, CLOSE]) {
+            return Err(String::from(
+                "Prepared queries must not contain the private use characters U+E000 and U+E001",
+            ));
+        }
+        let mut statements = script::split(query_string);
+        let statement = match (statements.pop(), statements.is_empty()) {
+            (Some(statement), true) => statement,
+            _ => {
+                return Err(String::from(
+                    "Prepared queries must hold exactly one command",
+                ))
+            }
+        };
+        let mut placeholders = Vec::new();
+        let tokens = statement
+            .text
+            .split_whitespace()
+            .map(|token| {
+                let index = match token {
+                    "?" => placeholders
+                        .iter()
+                        .filter(|(_, numbered)| !numbered)
+                        .count(),
+                    _ => match token.strip_prefix('$').map(str::parse::<usize>) {
+                        Some(Ok(number)) if number > 0 => number - 1,
+                        _ => return token.to_owned(),
+                    },
+                };
+                placeholders.push((index, token != "?"));
+                format!("{}{}{}", OPEN, index, CLOSE)
+            })
+            .collect::<Vec<String>>();
+        if placeholders.iter().any(|(_, numbered)| *numbered)
+            && placeholders.iter().any(|(_, numbered)| !numbered)
+        {
+            return Err(String::from(
+                "Placeholders must either all be \"?\" or all be numbered \"$1\", \"$2\"...",
+            ));
+        }
+        let numbered = placeholders.iter().any(|(_, numbered)| *numbered);
+        let shown = |text: &str| {
+            bind(text, |index| match numbered {
+                true => format!("${}", index + 1),
+                false => String::from("?"),
+            })
+        };
+        let command = commands::parse_with(tokens.join(" "), &database.aliases);
+        match command {
+            Command::InvalidCommandErr(command) => Err(format!(
+                "Invalid command \"{}\". Please check your spelling, or type \"Help\" for the list of available commands",
+                shown(&command)
+            )),
+            Command::SyntaxErr(syntax_error_message) => Err(format!(
+                "Invalid command syntax: {}",
+                shown(&syntax_error_message)
+            )),
+            // Macro statements are only parsed as they run, after values would have been pasted
+            // into their text
+            Command::Macro(name, _) => Err(format!(
+                "Macro \"{}\" cannot be prepared, as its statements are only parsed as it runs",
+                name
+            )),
+            command => Ok(Prepared {
+                database,
+                command,
+                parameters: placeholders
+                    .iter()
+                    .map(|(index, _)| index + 1)
+                    .max()
+                    .unwrap_or(0),
+            }),
+        }
+    }
+
+    /// The number of values each execution must bind.
+    pub fn parameters(&self) -> usize {
+        self.parameters
+    }
+
+    /// Binds values to the placeholders in order, or by number, and runs the query.
+    ///
+    /// Numbers, dates and flags are bound as they are written in queries. Responses are the same
+    /// as those of `Database::query`, including errors for missing or null values, and for
+    /// department or user names of more than one word.
+    pub fn execute<'v, V: Clone + Into<Value<&'v str>>>(&mut self, values: &[V]) -> QueryResponse {
+        if values.len() != self.parameters {
+            return QueryResponse::Message(format!(
+                "ERROR: Invalid command syntax: Query expects {} parameters but {} were given",
+                self.parameters,
+                values.len()
+            ));
+        }
+        let mut texts = Vec::new();
+        for (index, value) in values.iter().enumerate() {
+            let text = match value.clone().into() {
+                Value::Text(text) => text.split_whitespace().collect::<Vec<&str>>().join(" "),
+                Value::Null => String::new(),
+                value => value.to_string(),
+            };
+            if text.is_empty() {
+                return QueryResponse::Message(format!(
+                    "ERROR: Invalid command syntax: Parameter {} must not be empty or null",
+                    index + 1
+                ));
+            }
+            texts.push(text);
+        }
+        // Department and user names are one word in queries, and so must be when bound
+        let split_name = Cell::new(None);
+        let command = self.command.map_text(&|text, kind| {
+            bind(text, |index| {
+                if kind == Text::Word && texts[index].contains(' ') {
+                    split_name.set(split_name.get().or(Some(index)));
+                }
+                texts[index].clone()
+            })
+        });
+        if let Some(index) = split_name.get() {
+            return QueryResponse::Message(format!(
+                "ERROR: Invalid command syntax: Parameter {} must be one word, as department and user names are",
+                index + 1
+            ));
+        }
+        tables(self.database.execute_command(command))
+    }
+}
+
+// Markers that are not a whole placeholder are kept as they are
+fn bind<F: Fn(usize) -> String>(text: &str, value_of: F) -> String {
+    let mut bound = String::new();
+    let mut rest = text;
+    while let Some(open) = rest.find(OPEN) {
+        bound.push_str(&rest[..open]);
+        let placeholder = &rest[open + OPEN.len_utf8()..];
+        let index = placeholder.find(CLOSE).and_then(|close| {
+            placeholder[..close]
+                .parse::<usize>()
+                .ok()
+                .map(|index| (index, close))
+        });
+        rest = match index {
+            None => {
+                bound.push(OPEN);
+                placeholder
+            }
+            Some((index, close)) => {
+                bound.push_str(&value_of(index));
+                &placeholder[close + CLOSE.len_utf8()..]
+            }
+        };
+    }
+    bound.push_str(rest);
+    bound
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod prepared {
+        use super::*;
+
+        #[test]
+        fn counts_parameters() {
+            let mut db = Database::new();
+
+            assert_eq!(2, db.prepare("assign ? to ?").unwrap().parameters());
+            assert_eq!(
+                3,
+                db.prepare("transfer $1 from $3 to $2")
+                    .unwrap()
+                    .parameters()
+            );
+            assert_eq!(0, db.prepare("list employees").unwrap().parameters());
+        }
+
+        #[test]
+        fn never_reads_values_as_keywords() {
+            let mut db = Database::new();
+            db.query(String::from("form sales"));
+            db.query(String::from("form design"));
+            db.prepare("assign ? to ?")
+                .unwrap()
+                .execute(&["from to", "sales"]);
+            let mut transfer = db.prepare("transfer ? from ? to ?").unwrap();
+
+            assert_eq!(
+                QueryResponse::Message(String::from(
+                    "Transferred employee \"From To\" from \"Sales\" to \"Design\" department"
+                )),
+                transfer.execute(&["from to", "sales", "design"])
+            );
+        }
+
+        #[test]
+        fn binds_numbered_and_typed_values() {
+            let mut db = Database::new();
+            db.query(String::from("form sales"));
+            let mut set_cost_center = db.prepare("set cost center of $2 to $1").unwrap();
+
+            assert_eq!(
+                QueryResponse::Message(String::from(
+                    "Set cost center of \"Sales\" department to \"4100\""
+                )),
+                set_cost_center.execute(&[Value::Integer(4100), Value::Text("sales")])
+            );
+            assert_eq!(
+                QueryResponse::Message(String::from(
+                    "ERROR: Invalid command syntax: Parameter 1 must not be empty or null"
+                )),
+                set_cost_center.execute(&[Value::Null, Value::Text("sales")])
+            );
+        }
+
+        #[test]
+        fn rejects_malformed_queries() {
+            let mut db = Database::new();
+
+            assert_eq!(
+                Some(String::from(
+                    "Invalid command syntax: \"?\" is not an export format: formats are JSON, CSV and YAML"
+                )),
+                db.prepare("export ? to org.json").err()
+            );
+            assert_eq!(
+                Some(String::from(
+                    "Placeholders must either all be \"?\" or all be numbered \"$1\", \"$2\"..."
+                )),
+                db.prepare("assign ? to $1").err()
+            );
+            assert_eq!(
+                Some(String::from(
+                    "Prepared queries must hold exactly one command"
+                )),
+                db.prepare("form sales; form design").err()
+            );
+            assert_eq!(
+                Some(String::from(
+                    "Prepared queries must not contain the private use characters U+E000 and U+E001"
+                )),
+                db.prepare("assign a\u{E000}b to ?").err()
+            );
+        }
+
+        #[test]
+        fn rejects_macros() {
+            let mut db = Database::new();
+            db.query(String::from("define macro boss as set manager of $1 to $2"));
+
+            assert_eq!(
+                Some(String::from(
+                    "Macro \"boss\" cannot be prepared, as its statements are only parsed as it runs"
+                )),
+                db.prepare("boss ? , ?").err()
+            );
+        }
+
+        #[test]
+        fn binds_one_word_to_department_and_user_names() {
+            let mut db = Database::new();
+            let mut form = db.prepare("form ?").unwrap();
+
+            assert_eq!(
+                QueryResponse::Message(String::from(
+                    "ERROR: Invalid command syntax: Parameter 1 must be one word, as department and user names are"
+                )),
+                form.execute(&["big team"])
+            );
+            assert_eq!(
+                QueryResponse::Message(String::from("Formed \"Team\" department")),
+                form.execute(&[" team "])
+            );
+        }
+
+        #[test]
+        fn binds_only_whole_placeholders() {
+            let value_of = |index| format!("<{}>", index);
+
+            assert_eq!(
+                "a<0>b<1>",
+                bind("a\u{E000}0\u{E001}b\u{E000}1\u{E001}", value_of)
+            );
+            assert_eq!("a\u{E000}b", bind("a\u{E000}b", value_of));
+            assert_eq!("\u{E000}x\u{E001}", bind("\u{E000}x\u{E001}", value_of));
+        }
+
+        #[test]
+        fn checks_the_number_of_values() {
+            let mut db = Database::new();
+            let mut assign = db.prepare("assign ? to ?").unwrap();
+
+            assert_eq!(
+                QueryResponse::Message(String::from(
+                    "ERROR: Invalid command syntax: Query expects 2 parameters but 1 were given"
+                )),
+                assign.execute(&["bob"])
+            );
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use super::date::Date;
+use super::store::Store;
+use super::{ColumnType, Report, Rows, Table, Value};
+use std::collections::{BTreeMap, HashSet};
+
+#[derive(Default)]
+struct Movement {
+    department: String,
+    assigned: i64,
+    transferred_in: i64,
+    transferred_out: i64,
+    pulled: i64,
+}
+
+// Shares of the total count memberships, so an employee in two departments counts in both and
+// the shares add up to 100%
+pub fn headcount(store: &Store, from: Option<Date>, to: Option<Date>) -> Report {
+    let headcounts = store
+        .departments()
+        .iter()
+        .map(|department| {
+            (
+                department.name(),
+                department.employees().iter().count() as i64,
+            )
+        })
+        .collect::<Vec<(&str, i64)>>();
+    let total = headcounts
+        .iter()
+        .map(|(_, headcount)| headcount)
+        .sum::<i64>();
+
+    let mut by_department = Rows::with_types(
+        String::from("Headcount by Department"),
+        vec![
+            String::from("Department"),
+            String::from("Headcount"),
+            String::from("Share (%)"),
+        ],
+        vec![ColumnType::Text, ColumnType::Integer, ColumnType::Decimal],
+    );
+    headcounts.iter().for_each(|(department_name, headcount)| {
+        let share = match total {
+            0 => 0.0,
+            _ => (*headcount as f64 * 1000.0 / total as f64).round() / 10.0,
+        };
+        by_department.push_values(&[
+            Value::Text(department_name),
+            Value::Integer(*headcount),
+            Value::Decimal(share),
+        ]);
+    });
+
+    let mut extremes = Rows::with_types(
+        String::from("Largest and Smallest Departments"),
+        vec![
+            String::from("Measure"),
+            String::from("Department"),
+            String::from("Headcount"),
+        ],
+        vec![ColumnType::Text, ColumnType::Text, ColumnType::Integer],
+    );
+    extremes.push_values(&[Value::Text("Total"), Value::Null, Value::Integer(total)]);
+    let staffed = headcounts
+        .iter()
+        .filter(|(_, headcount)| *headcount > 0)
+        .collect::<Vec<&(&str, i64)>>();
+    let largest = staffed.iter().map(|(_, headcount)| *headcount).max();
+    let smallest = staffed.iter().map(|(_, headcount)| *headcount).min();
+    [("Largest", largest), ("Smallest", smallest)]
+        .iter()
+        .for_each(|(measure, extreme)| {
+            staffed
+                .iter()
+                .filter(|(_, headcount)| Some(*headcount) == *extreme)
+                .for_each(|(department_name, headcount)| {
+                    extremes.push_values(&[
+                        Value::Text(measure),
+                        Value::Text(department_name),
+                        Value::Integer(*headcount),
+                    ])
+                })
+        });
+
+    let mut empty = Rows::new(
+        String::from("Empty Departments"),
+        vec![String::from("Department")],
+    );
+    headcounts
+        .iter()
+        .filter(|(_, headcount)| *headcount == 0)
+        .for_each(|(department_name, _)| empty.push(&[department_name]));
+
+    let mut sections = vec![
+        Table::from(by_department),
+        Table::from(extremes),
+        Table::from(empty),
+    ];
+    if store.history().iter().next().is_some() {
+        sections.push(Table::from(movement(store, from, to)));
+    }
+    Report {
+        title: String::from("Headcount Report"),
+        sections,
+    }
+}
+
+// A membership opened on the day another membership of the same employee was closed is a
+// transfer, and any other opening or closing is an assignment or a pull
+fn movement(store: &Store, from: Option<Date>, to: Option<Date>) -> Rows {
+    let names = store.names();
+    let history = store.history();
+    let in_period =
+        |date: Date| from.is_none_or(|from| from <= date) && to.is_none_or(|to| date <= to);
+    let opened = history
+        .iter()
+        .map(|membership| (names.key(membership.employee()), membership.from()))
+        .collect::<HashSet<(String, Date)>>();
+    let closed = history
+        .iter()
+        .filter_map(|membership| {
+            membership
+                .to()
+                .map(|to| (names.key(membership.employee()), to))
+        })
+        .collect::<HashSet<(String, Date)>>();
+
+    let mut movements: BTreeMap<String, Movement> = BTreeMap::new();
+    history.iter().for_each(|membership| {
+        let employee_key = names.key(membership.employee());
+        let joined = Some(membership.from())
+            .filter(|joined| in_period(*joined))
+            .map(|joined| closed.contains(&(employee_key.clone(), joined)));
+        let left = membership
+            .to()
+            .filter(|left| in_period(*left))
+            .map(|left| opened.contains(&(employee_key.clone(), left)));
+        if joined.is_none() && left.is_none() {
+            return;
+        }
+        let movement = movements
+            .entry(names.key(membership.department()))
+            .or_default();
+        movement.department = membership.department().to_owned();
+        match joined {
+            Some(true) => movement.transferred_in += 1,
+            Some(false) => movement.assigned += 1,
+            None => (),
+        }
+        match left {
+            Some(true) => movement.transferred_out += 1,
+            Some(false) => movement.pulled += 1,
+            None => (),
+        }
+    });
+
+    let mut rows = Rows::with_types(
+        match (from, to) {
+            (None, None) => String::from("Movement"),
+            (Some(from), None) => format!("Movement since {}", from),
+            (None, Some(to)) => format!("Movement until {}", to),
+            (Some(from), Some(to)) => format!("Movement from {} to {}", from, to),
+        },
+        vec![
+            String::from("Department"),
+            String::from("Assigned"),
+            String::from("Transferred In"),
+            String::from("Transferred Out"),
+            String::from("Pulled"),
+            String::from("Net Change"),
+        ],
+        vec![
+            ColumnType::Text,
+            ColumnType::Integer,
+            ColumnType::Integer,
+            ColumnType::Integer,
+            ColumnType::Integer,
+            ColumnType::Integer,
+        ],
+    );
+    movements.values().for_each(|movement| {
+        let department_name = store
+            .departments()
+            .department(&movement.department)
+            .map_or(movement.department.as_str(), |department| department.name());
+        rows.push_values(&[
+            Value::Text(department_name),
+            Value::Integer(movement.assigned),
+            Value::Integer(movement.transferred_in),
+            Value::Integer(movement.transferred_out),
+            Value::Integer(movement.pulled),
+            Value::Integer(
+                movement.assigned + movement.transferred_in
+                    - movement.transferred_out
+                    - movement.pulled,
+            ),
+        ])
+    });
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::Database;
+
+    fn organization() -> Database {
+        let mut db = Database::new();
+        db.set_date(Date::new(2026, 1, 5));
+        [
+            "Form Sales",
+            "Form Design",
+            "Form Legal",
+            "Assign Bob to Sales",
+            "Assign Alice to Sales",
+            "Assign Carol to Design",
+        ]
+        .iter()
+        .for_each(|query| {
+            db.query(query.to_string());
+        });
+        db.set_date(Date::new(2026, 2, 9));
+        db.query(String::from("Transfer Bob from Sales to Design"));
+        db.query(String::from("Pull Alice from Sales"));
+        db.query(String::from("Assign Dan to Sales"));
+        db
+    }
+
+    fn cells(table: &Table) -> Vec<Vec<String>> {
+        table
+            .data
+            .iter()
+            .map(|row| {
+                table
+                    .headers
+                    .iter()
+                    .map(|header| row[header].to_string())
+                    .collect()
+            })
+            .collect()
+    }
+
+    mod fn_headcount {
+        use super::*;
+
+        #[test]
+        fn counts_departments_and_their_extremes() {
+            let report = headcount(&organization().store, None, None);
+
+            assert_eq!(
+                vec![
+                    vec!["Design", "2", "66.7"],
+                    vec!["Legal", "0", "0"],
+                    vec!["Sales", "1", "33.3"],
+                ],
+                cells(&report.sections[0])
+            );
+            assert_eq!(
+                vec![
+                    vec!["Total", "", "3"],
+                    vec!["Largest", "Design", "2"],
+                    vec!["Smallest", "Sales", "1"],
+                ],
+                cells(&report.sections[1])
+            );
+            assert_eq!(vec![vec!["Legal"]], cells(&report.sections[2]));
+        }
+
+        #[test]
+        fn tells_transfers_from_assignments_and_pulls() {
+            let report = headcount(&organization().store, Date::new(2026, 2, 1), None);
+
+            assert_eq!("Movement since 2026-02-01", report.sections[3].title);
+            assert_eq!(
+                vec![
+                    vec!["Design", "0", "1", "0", "0", "1"],
+                    vec!["Sales", "1", "0", "1", "1", "-1"],
+                ],
+                cells(&report.sections[3])
+            );
+        }
+
+        #[test]
+        fn leaves_out_movement_without_history() {
+            let mut db = Database::new();
+            db.query(String::from("Form Sales"));
+
+            assert_eq!(3, headcount(&db.store, None, None).sections.len());
+        }
+    }
+}
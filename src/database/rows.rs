@@ -0,0 +1,329 @@
+use super::{ColumnType, Table, Value};
+use std::collections::HashMap;
+
+/// Tabular data stored in a single buffer, for writing out row by row.
+///
+/// Unlike `Table`, which allocates a map and a `String` per cell, `Rows` keep the text of every
+/// cell in one buffer, so listing a large database only allocates a handful of times. A `Table`
+/// can be collected from `Rows` when random access by column name is more convenient.
+///
+/// # Examples
+///
+/// ```rust
+/// use employees::database::{ColumnType, Rows, Table, Value};
+///
+/// let mut rows = Rows::with_types(
+///     "Showing Departments".to_string(),
+///     vec!["Department".to_string(), "Headcount".to_string()],
+///     vec![ColumnType::Text, ColumnType::Integer],
+/// );
+/// rows.push_values(&[Value::Text("Sales"), Value::Integer(10)]);
+/// rows.push_values(&[Value::Text("Design"), Value::Null]);
+///
+/// assert_eq!(
+///     vec![Some(Value::Integer(10)), Some(Value::Null)],
+///     rows.iter().map(|row| row.get("Headcount")).collect::<Vec<_>>()
+/// );
+/// assert_eq!(2, Table::from(rows).data.len());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Table", from = "Table")
+)]
+pub struct Rows {
+    title: String,
+    headers: Vec<String>,
+    types: Vec<ColumnType>,
+    text: String,
+    // One value per cell, row after row, with text held as its start and end within `text`
+    cells: Vec<Value<(usize, usize)>>,
+}
+
+impl Rows {
+    /// Creates empty rows with the given title and text columns.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::Rows;
+    ///
+    /// let rows = Rows::new("Showing Users".to_string(), vec!["User".to_string()]);
+    ///
+    /// assert!(rows.is_empty());
+    /// ```
+    pub fn new(title: String, headers: Vec<String>) -> Self {
+        let types = vec![ColumnType::Text; headers.len()];
+        Rows::with_types(title, headers, types)
+    }
+
+    /// Creates empty rows with the given title, column names and column types.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there isn't exactly one type per column name.
+    pub fn with_types(title: String, headers: Vec<String>, types: Vec<ColumnType>) -> Self {
+        assert_eq!(
+            headers.len(),
+            types.len(),
+            "Rows must have one type per column"
+        );
+        Rows {
+            title,
+            headers,
+            types,
+            text: String::new(),
+            cells: Vec::new(),
+        }
+    }
+
+    /// Appends a row of text, with one cell per column in header order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row doesn't have exactly one cell per column, or a column isn't text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::Rows;
+    ///
+    /// let mut rows = Rows::new(
+    ///     "Showing Employees".to_string(),
+    ///     vec!["Department".to_string(), "Employee".to_string()],
+    /// );
+    /// rows.push(&["Sales", "Bob"]);
+    ///
+    /// assert_eq!(1, rows.len());
+    /// ```
+    pub fn push(&mut self, row: &[&str]) {
+        self.check_width(row.len());
+        row.iter()
+            .for_each(|cell| self.push_value(Value::Text(cell)));
+    }
+
+    /// Appends a row of typed values, with one cell per column in header order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row doesn't have exactly one cell per column, or a value other than
+    /// `Value::Null` doesn't match the type of its column.
+    pub fn push_values(&mut self, row: &[Value<&str>]) {
+        self.check_width(row.len());
+        row.iter().for_each(|cell| self.push_value(cell.clone()));
+    }
+
+    fn check_width(&self, width: usize) {
+        assert_eq!(
+            self.headers.len(),
+            width,
+            "Row must have one cell per column"
+        );
+    }
+
+    fn push_value(&mut self, value: Value<&str>) {
+        let column = self.cells.len() % self.headers.len();
+        assert!(
+            value.is_null() || value.kind() == self.types[column],
+            "Value of column \"{}\" must be {:?}",
+            self.headers[column],
+            self.types[column]
+        );
+        self.cells.push(match value {
+            Value::Null => Value::Null,
+            Value::Text(text) => {
+                let start = self.text.len();
+                self.text.push_str(text);
+                Value::Text((start, self.text.len()))
+            }
+            Value::Integer(number) => Value::Integer(number),
+            Value::Decimal(number) => Value::Decimal(number),
+            Value::Date(date) => Value::Date(date),
+            Value::Boolean(flag) => Value::Boolean(flag),
+        });
+    }
+
+    /// The data set name.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Replaces the data set name.
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+
+    /// Column names / header labels, in default order.
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// Column types, one per header.
+    pub fn types(&self) -> &[ColumnType] {
+        &self.types
+    }
+
+    /// The number of rows.
+    pub fn len(&self) -> usize {
+        match self.headers.len() {
+            0 => 0,
+            columns => self.cells.len() / columns,
+        }
+    }
+
+    /// Whether there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the rows in order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Database, QueryResponse};
+    ///
+    /// let mut db = Database::new();
+    /// db.seed();
+    ///
+    /// if let QueryResponse::Rows(rows) = db.query_rows("list employees in sales".to_string()) {
+    ///     rows.iter().for_each(|row| println!("{}", row.get("Employee").unwrap()));
+    /// }
+    /// ```
+    pub fn iter(&self) -> impl Iterator<Item = Row<'_>> {
+        (0..self.len()).map(move |index| Row { rows: self, index })
+    }
+}
+
+impl From<Rows> for Table {
+    fn from(rows: Rows) -> Self {
+        let data = rows
+            .iter()
+            .map(|row| {
+                rows.headers
+                    .iter()
+                    .zip(row.cells())
+                    .map(|(header, cell)| (header.to_owned(), cell.into_owned()))
+                    .collect::<HashMap<String, Value>>()
+            })
+            .collect();
+        Table {
+            title: rows.title,
+            headers: rows.headers,
+            types: rows.types,
+            data,
+        }
+    }
+}
+
+impl From<Table> for Rows {
+    fn from(table: Table) -> Self {
+        let mut rows = Rows::with_types(table.title, table.headers, table.types);
+        let headers = rows.headers.clone();
+        table.data.iter().for_each(|row| {
+            headers.iter().for_each(|header| {
+                rows.push_value(row.get(header).map_or(Value::Null, Value::as_borrowed));
+            });
+        });
+        rows
+    }
+}
+
+/// A single row of `Rows`, borrowed from them.
+#[derive(Debug, Clone, Copy)]
+pub struct Row<'r> {
+    rows: &'r Rows,
+    index: usize,
+}
+
+impl<'r> Row<'r> {
+    /// Returns the cell in the named column, or `None` if there is no such column.
+    pub fn get(&self, header: &str) -> Option<Value<&'r str>> {
+        let column = self.rows.headers.iter().position(|name| name == header)?;
+        Some(self.cell(column))
+    }
+
+    /// Iterates over the cells in header order.
+    pub fn cells(&self) -> impl Iterator<Item = Value<&'r str>> {
+        let row = *self;
+        (0..self.rows.headers.len()).map(move |column| row.cell(column))
+    }
+
+    fn cell(&self, column: usize) -> Value<&'r str> {
+        let rows = self.rows;
+        match &rows.cells[self.index * rows.headers.len() + column] {
+            Value::Null => Value::Null,
+            Value::Text((start, end)) => Value::Text(&rows.text[*start..*end]),
+            Value::Integer(number) => Value::Integer(*number),
+            Value::Decimal(number) => Value::Decimal(*number),
+            Value::Date(date) => Value::Date(*date),
+            Value::Boolean(flag) => Value::Boolean(*flag),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod rows {
+        use super::*;
+
+        fn employees() -> Rows {
+            let mut rows = Rows::with_types(
+                String::from("Showing Employees"),
+                vec![String::from("Employee"), String::from("Reports")],
+                vec![ColumnType::Text, ColumnType::Integer],
+            );
+            rows.push_values(&[Value::Text("Bob"), Value::Integer(2)]);
+            rows.push_values(&[Value::Text(""), Value::Null]);
+            rows
+        }
+
+        #[test]
+        fn iterates_cells_in_order() {
+            let rows = employees();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(
+                vec![
+                    vec![Value::Text("Bob"), Value::Integer(2)],
+                    vec![Value::Text(""), Value::Null]
+                ],
+                rows.iter()
+                    .map(|row| row.cells().collect::<Vec<Value<&str>>>())
+                    .collect::<Vec<Vec<Value<&str>>>>()
+            );
+            assert_eq!(None, rows.iter().next().unwrap().get("Salary"));
+        }
+
+        #[test]
+        #[should_panic(expected = "Row must have one cell per column")]
+        fn rejects_rows_of_wrong_width() {
+            employees().push_values(&[Value::Text("Alice")]);
+        }
+
+        #[test]
+        #[should_panic(expected = "Value of column \"Reports\" must be Integer")]
+        fn rejects_values_of_wrong_type() {
+            employees().push(&["Alice", "none"]);
+        }
+
+        #[test]
+        fn round_trips_through_table_filling_missing_cells_with_null() {
+            let mut table = Table::from(employees());
+            table.data[0].remove("Reports");
+
+            let rows = Rows::from(table);
+
+            assert_eq!("Showing Employees", rows.title());
+            assert_eq!(vec![ColumnType::Text, ColumnType::Integer], rows.types());
+            assert_eq!(
+                Some(Value::Null),
+                rows.iter().next().unwrap().get("Reports")
+            );
+            assert_eq!("Bob", Table::from(rows).data[0]["Employee"]);
+        }
+    }
+}
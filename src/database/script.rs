@@ -0,0 +1,139 @@
+#[derive(Debug, PartialEq)]
+pub struct Statement {
+    // Line of the script the statement starts on, counting from 1
+    pub line: usize,
+    pub text: String,
+}
+
+// Statements end at semicolons and comments run from "--" or "#" to the end of the line. Markers
+// only count at the start of a word, so names such as "C#" and "Jean-Luc" are left alone.
+// Macro definitions hold several statements, so they run on to an "End" statement or to the end
+// of the script.
+pub fn split(script: &str) -> Vec<Statement> {
+    let mut statements = Vec::new();
+    let mut text = String::new();
+    let mut start = 1;
+    script.lines().enumerate().for_each(|(index, line)| {
+        let mut previous = ' ';
+        let mut characters = line.chars().peekable();
+        while let Some(character) = characters.next() {
+            let starts_word = previous.is_whitespace() || previous == ';';
+            if starts_word
+                && (character == '#' || (character == '-' && characters.peek() == Some(&'-')))
+            {
+                break;
+            }
+            if character == ';' {
+                push(&mut statements, &mut text, start);
+            } else {
+                if text.trim().is_empty() {
+                    start = index + 1;
+                }
+                text.push(character);
+            }
+            previous = character;
+        }
+        text.push('\n');
+    });
+    push(&mut statements, &mut text, start);
+    join_macros(statements)
+}
+
+fn join_macros(statements: Vec<Statement>) -> Vec<Statement> {
+    let mut joined: Vec<Statement> = Vec::new();
+    let mut in_macro = false;
+    for statement in statements {
+        if in_macro {
+            let definition = joined.last_mut().unwrap();
+            if statement.text.to_uppercase() == "END" {
+                in_macro = false;
+            } else {
+                definition.text = format!("{}; {}", definition.text, statement.text);
+            }
+            continue;
+        }
+        let mut words = statement.text.split_whitespace().map(str::to_uppercase);
+        in_macro =
+            words.next().as_deref() == Some("DEFINE") && words.next().as_deref() == Some("MACRO");
+        joined.push(statement);
+    }
+    joined
+}
+
+fn push(statements: &mut Vec<Statement>, text: &mut String, line: usize) {
+    let statement = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+    if !statement.is_empty() {
+        statements.push(Statement {
+            line,
+            text: statement,
+        });
+    }
+    text.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement(line: usize, text: &str) -> Statement {
+        Statement {
+            line,
+            text: text.to_string(),
+        }
+    }
+
+    mod fn_split {
+        use super::*;
+
+        #[test]
+        fn splits_statements_on_semicolons() {
+            assert_eq!(
+                vec![
+                    statement(1, "form sales"),
+                    statement(1, "assign bob to sales")
+                ],
+                split("form sales; assign bob to sales;")
+            );
+        }
+
+        #[test]
+        fn drops_comments_and_blank_statements() {
+            let script = "# Setup\nform sales -- the first department\n;;\n  -- nothing here\nassign bob\n  to sales # trailing";
+
+            assert_eq!(
+                vec![
+                    statement(2, "form sales"),
+                    statement(5, "assign bob to sales")
+                ],
+                split(script)
+            );
+        }
+
+        #[test]
+        fn keeps_markers_inside_words() {
+            assert_eq!(
+                vec![statement(1, "assign Jean--Luc to C#")],
+                split("assign Jean--Luc to C#")
+            );
+        }
+
+        #[test]
+        fn keeps_macro_definitions_whole() {
+            let script =
+                "define macro hire as\n  form $2;\n  assign $1 to $2;\nend;\nlist employees";
+
+            assert_eq!(
+                vec![
+                    statement(1, "define macro hire as form $2; assign $1 to $2"),
+                    statement(5, "list employees")
+                ],
+                split(script)
+            );
+        }
+
+        #[test]
+        fn returns_nothing_for_comments_only() {
+            assert!(split("  # just a comment\n-- and another").is_empty());
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use super::names::Names;
+
+const EXACT_SCORE: u32 = 100;
+const PREFIX_SCORE: u32 = 75;
+const SUBSTRING_SCORE: u32 = 50;
+const TYPO_SCORE: u32 = 40;
+const TYPO_PENALTY: u32 = 15;
+
+/// Free-text search terms, scored against the words of employee and department names, which
+/// are compared under the same collation as names in the store.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Search {
+    terms: Vec<String>,
+    names: Names,
+}
+
+impl Search {
+    pub fn new(names: Names, text: &str) -> Self {
+        Search {
+            terms: text
+                .split_whitespace()
+                .map(|term| names.key(term))
+                .collect(),
+            names,
+        }
+    }
+
+    /// Sums the best score of each term against any word of the given names; 0 means no match.
+    pub fn score(&self, names: &[&str]) -> u32 {
+        let words = names
+            .iter()
+            .flat_map(|name| {
+                name.split(|character: char| character.is_whitespace() || character == '-')
+            })
+            .filter(|word| !word.is_empty())
+            .map(|word| self.names.key(word))
+            .collect::<Vec<String>>();
+        self.terms
+            .iter()
+            .map(|term| {
+                words
+                    .iter()
+                    .map(|word| term_score(term, word))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .sum()
+    }
+}
+
+fn term_score(term: &str, word: &str) -> u32 {
+    let term_length = term.chars().count();
+    if word == term {
+        EXACT_SCORE
+    } else if word.starts_with(term) {
+        PREFIX_SCORE
+    } else if term_length >= 3 && word.contains(term) {
+        SUBSTRING_SCORE
+    } else if term_length >= 4 {
+        let allowed_typos = if term_length >= 8 { 2 } else { 1 };
+        match edit_distance(term, word) {
+            typos if typos <= allowed_typos => TYPO_SCORE - TYPO_PENALTY * (typos as u32 - 1),
+            _ => 0,
+        }
+    } else {
+        0
+    }
+}
+
+// Optimal string alignment distance: insertions, deletions, substitutions and adjacent swaps
+fn edit_distance(from: &str, to: &str) -> usize {
+    let from = from.chars().collect::<Vec<char>>();
+    let to = to.chars().collect::<Vec<char>>();
+    let mut distances = vec![vec![0; to.len() + 1]; from.len() + 1];
+    (0..=from.len()).for_each(|row| distances[row][0] = row);
+    (0..=to.len()).for_each(|column| distances[0][column] = column);
+    for row in 1..=from.len() {
+        for column in 1..=to.len() {
+            let substitution = if from[row - 1] == to[column - 1] {
+                0
+            } else {
+                1
+            };
+            let mut distance = (distances[row - 1][column] + 1)
+                .min(distances[row][column - 1] + 1)
+                .min(distances[row - 1][column - 1] + substitution);
+            if row > 1
+                && column > 1
+                && from[row - 1] == to[column - 2]
+                && from[row - 2] == to[column - 1]
+            {
+                distance = distance.min(distances[row - 2][column - 2] + 1);
+            }
+            distances[row][column] = distance;
+        }
+    }
+    distances[from.len()][to.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod search {
+        use super::*;
+        use crate::database::names::{Collation, NameCase};
+
+        #[test]
+        fn ranks_exact_over_prefix_over_substring() {
+            let search = Search::new(Names::default(), "cal");
+
+            assert_eq!(EXACT_SCORE, search.score(&["Cal", "Accounting"]));
+            assert_eq!(
+                PREFIX_SCORE,
+                search.score(&["Shyam Calderon", "Accounting"])
+            );
+            assert_eq!(SUBSTRING_SCORE, search.score(&["Pascal Smith", "Design"]));
+        }
+
+        #[test]
+        fn tolerates_typos_in_longer_terms() {
+            assert_eq!(
+                TYPO_SCORE,
+                Search::new(Names::default(), "caldreon").score(&["Kingsley Calderon"])
+            );
+            assert_eq!(
+                TYPO_SCORE - TYPO_PENALTY,
+                Search::new(Names::default(), "kalderin").score(&["Kingsley Calderon"])
+            );
+            assert_eq!(
+                0,
+                Search::new(Names::default(), "kirl").score(&["Kurt Short"])
+            );
+            assert_eq!(
+                0,
+                Search::new(Names::default(), "bob").score(&["Rob Short"])
+            );
+        }
+
+        #[test]
+        fn sums_terms_across_employee_and_department() {
+            let search = Search::new(Names::default(), "the calderon in logistics");
+
+            assert!(
+                search.score(&["Kingsley Calderon", "Logistics"])
+                    > search.score(&["Shyam Calderon", "Accounting"])
+            );
+        }
+
+        #[test]
+        fn compares_names_under_their_collation() {
+            assert_eq!(
+                EXACT_SCORE,
+                Search::new(Names::default(), "STRASSE").score(&["Ida Straße"])
+            );
+            assert_eq!(
+                EXACT_SCORE,
+                Search::new(Names::default(), "Jose\u{301}").score(&["José Schwartz"])
+            );
+            assert_eq!(
+                0,
+                Search::new(
+                    Names::new(NameCase::Title, Collation::Uppercase),
+                    "Jose\u{301}"
+                )
+                .score(&["José Schwartz"])
+            );
+        }
+
+        #[test]
+        fn splits_hyphenated_names() {
+            assert_eq!(
+                EXACT_SCORE,
+                Search::new(Names::default(), "mae").score(&["Olivia-Mae Schneider"])
+            );
+        }
+    }
+
+    mod edit_distance {
+        use super::edit_distance;
+
+        #[test]
+        fn counts_edits() {
+            assert_eq!(0, edit_distance("SHORT", "SHORT"));
+            assert_eq!(1, edit_distance("SHORT", "SHIRT"));
+            assert_eq!(1, edit_distance("SHORT", "SHOTR"));
+            assert_eq!(2, edit_distance("SHORT", "HORTS"));
+            assert_eq!(3, edit_distance("", "ABC"));
+        }
+    }
+}
@@ -0,0 +1,451 @@
+use super::aliases::{Alias, AliasKind, Aliases};
+use super::commands;
+use super::date::Date;
+use super::errors::QueryError;
+use super::names::{Collation, NameCase, Names};
+use super::permissions::{Permissions, Role};
+use super::store::{DepartmentField, EmployeeRef, History, Membership, Store};
+use super::Database;
+
+// A copy of everything stored in a database, by display name rather than by key, so that it can
+// be read back through the same checks as queries
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub case: NameCase,
+    pub collation: Collation,
+    pub departments: Vec<DepartmentSnapshot>,
+    pub users: Vec<UserSnapshot>,
+    // Documents without a history restore with every employee joining on the day of the restore
+    #[serde(default)]
+    pub history: Vec<MembershipSnapshot>,
+    #[serde(default)]
+    pub aliases: Vec<AliasSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DepartmentSnapshot {
+    pub name: String,
+    pub parent: Option<String>,
+    pub head: Option<String>,
+    pub cost_center: Option<String>,
+    pub location: Option<String>,
+    pub description: Option<String>,
+    pub created: Option<Date>,
+    pub employees: Vec<EmployeeSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EmployeeSnapshot {
+    pub name: String,
+    pub manager: Option<ManagerSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ManagerSnapshot {
+    pub employee: String,
+    pub department: String,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct MembershipSnapshot {
+    pub employee: String,
+    pub department: String,
+    pub from: Date,
+    pub to: Option<Date>,
+    #[serde(default)]
+    pub dissolved: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct UserSnapshot {
+    pub name: String,
+    pub grants: Vec<GrantSnapshot>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct GrantSnapshot {
+    pub role: Role,
+    pub department: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct AliasSnapshot {
+    pub name: String,
+    pub kind: AliasKind,
+    pub statements: Vec<String>,
+}
+
+impl Snapshot {
+    pub fn capture(store: &Store, permissions: &Permissions, aliases: &Aliases) -> Self {
+        let names = store.names();
+        Snapshot {
+            case: names.case(),
+            collation: names.collation(),
+            departments: capture_departments(store, |_| true),
+            users: permissions
+                .users()
+                .map(|user| UserSnapshot {
+                    name: user.name().to_owned(),
+                    grants: user
+                        .grants()
+                        .iter()
+                        .map(|grant| GrantSnapshot {
+                            role: grant.role(),
+                            department: grant.department().map(String::from),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            history: capture_history(store, |_| true),
+            aliases: aliases
+                .iter()
+                .map(|alias| AliasSnapshot {
+                    name: alias.name().to_owned(),
+                    kind: alias.kind(),
+                    statements: alias.statements().to_vec(),
+                })
+                .collect(),
+        }
+    }
+
+    // Parents and managers outside the department are left out, so that the snapshot restores on
+    // its own, as are users, whose grants may cover other departments, and aliases, which may
+    // name any department
+    #[cfg(feature = "export")]
+    pub fn capture_department(store: &Store, department_name: &str) -> Result<Self, QueryError> {
+        let names = store.names();
+        let key = names.key(store.department(department_name)?.name());
+        Ok(Snapshot {
+            case: names.case(),
+            collation: names.collation(),
+            departments: capture_departments(store, |name| names.key(name) == key),
+            users: Vec::new(),
+            history: capture_history(store, |name| names.key(name) == key),
+            aliases: Vec::new(),
+        })
+    }
+
+    pub fn restore(&self) -> Result<(Store, Permissions, Aliases), QueryError> {
+        let names = Names::new(self.case, self.collation);
+        let mut store = Store::new(names);
+        for department in &self.departments {
+            store.departments_mut().create(&department.name)?;
+        }
+        for department in &self.departments {
+            store
+                .departments_mut()
+                .set_parent(&department.name, department.parent.as_deref())?;
+            for employee in &department.employees {
+                store.assign(&employee.name, &department.name)?;
+            }
+        }
+        // Managers and heads are only set once every employee exists, wherever they work
+        for department in &self.departments {
+            for employee in &department.employees {
+                if let Some(manager) = &employee.manager {
+                    let manager_ref =
+                        EmployeeRef::new(names, &manager.department, &manager.employee);
+                    if store.employee(&manager_ref).is_none() {
+                        return Err(QueryError::NotFound(format!(
+                            "Manager \"{}\" of employee \"{}\" not found",
+                            manager.employee, employee.name
+                        )));
+                    }
+                    let employee = EmployeeRef::new(names, &department.name, &employee.name);
+                    store.set_manager(&employee, Some(manager_ref))?;
+                }
+            }
+            let found = store.department_mut(&department.name)?;
+            found.set_field(DepartmentField::Head, department.head.as_deref())?;
+            found.set_field(
+                DepartmentField::CostCenter,
+                department.cost_center.as_deref(),
+            )?;
+            found.set_field(DepartmentField::Location, department.location.as_deref())?;
+            found.set_field(
+                DepartmentField::Description,
+                department.description.as_deref(),
+            )?;
+            if let Some(created) = department.created {
+                found.set_created(created);
+            }
+        }
+        if !self.history.is_empty() {
+            let mut history = History::new(names);
+            for membership in &self.history {
+                let mut restored = Membership::new(
+                    &membership.employee,
+                    &membership.department,
+                    membership.from,
+                    membership.to,
+                );
+                if membership.dissolved {
+                    restored.dissolve();
+                }
+                history.insert(restored)?;
+            }
+            store.set_history(history);
+        }
+        let mut permissions = Permissions::new(names);
+        for user in &self.users {
+            for grant in &user.grants {
+                permissions.insert_grant(&user.name, grant.role, grant.department.as_deref())?;
+            }
+        }
+        let has_admin = permissions
+            .users()
+            .flat_map(|user| user.grants())
+            .any(|grant| grant.role() == Role::Admin && grant.department().is_none());
+        if permissions.is_enforced() && !has_admin {
+            return Err(QueryError::Conflict(String::from(
+                "Users must include an Admin of all departments",
+            )));
+        }
+        let mut aliases = Aliases::new(names);
+        for alias in &self.aliases {
+            commands::check_alias_name(&alias.name).map_err(QueryError::Conflict)?;
+            aliases.define(Alias::new(
+                &alias.name,
+                alias.kind,
+                alias.statements.clone(),
+            ));
+        }
+        Ok((store, permissions, aliases))
+    }
+}
+
+fn capture_departments<F: Fn(&str) -> bool>(store: &Store, included: F) -> Vec<DepartmentSnapshot> {
+    store
+        .departments()
+        .iter()
+        .filter(|department| included(department.name()))
+        .map(|department| DepartmentSnapshot {
+            name: department.name().to_owned(),
+            parent: department
+                .parent()
+                .and_then(|parent| store.department(parent).ok())
+                .map(|parent| parent.name().to_owned())
+                .filter(|parent| included(parent)),
+            head: department.head().map(String::from),
+            cost_center: department.field(DepartmentField::CostCenter),
+            location: department.field(DepartmentField::Location),
+            description: department.field(DepartmentField::Description),
+            created: department.created(),
+            employees: department
+                .employees()
+                .iter()
+                .map(|employee| EmployeeSnapshot {
+                    name: employee.name().to_owned(),
+                    manager: employee
+                        .manager()
+                        .map(|manager| ManagerSnapshot {
+                            employee: store.employee_name(manager),
+                            department: store.department_name(manager),
+                        })
+                        .filter(|manager| included(&manager.department)),
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn capture_history<F: Fn(&str) -> bool>(store: &Store, included: F) -> Vec<MembershipSnapshot> {
+    store
+        .history()
+        .iter()
+        .filter(|membership| included(membership.department()))
+        .map(|membership| MembershipSnapshot {
+            employee: membership.employee().to_owned(),
+            department: membership.department().to_owned(),
+            from: membership.from(),
+            to: membership.to(),
+            dissolved: membership.dissolved(),
+        })
+        .collect()
+}
+
+impl Database {
+    pub(crate) fn snapshot(&self) -> Snapshot {
+        Snapshot::capture(&self.store, &self.permissions, &self.aliases)
+    }
+
+    #[cfg(feature = "export")]
+    pub(crate) fn snapshot_of(
+        &self,
+        department_name: Option<&str>,
+    ) -> Result<Snapshot, QueryError> {
+        match department_name {
+            None => Ok(self.snapshot()),
+            Some(department_name) => Snapshot::capture_department(&self.store, department_name),
+        }
+    }
+
+    pub(crate) fn from_snapshot(snapshot: &Snapshot) -> Result<Self, QueryError> {
+        let (store, permissions, aliases) = snapshot.restore()?;
+        Ok(Database {
+            store,
+            permissions,
+            user: None,
+            aliases,
+            running_macros: Vec::new(),
+        })
+    }
+}
+
+// Databases are serialized as a snapshot of their departments, employees and users, leaving out
+// the logged in user
+impl serde::Serialize for Database {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.snapshot().serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Database {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let snapshot = Snapshot::deserialize(deserializer)?;
+        Database::from_snapshot(&snapshot).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn organization() -> Database {
+        let mut db = Database::new();
+        db.seed();
+        [
+            "Form Payroll under Accounting",
+            "Assign Anil McGregor to Payroll",
+            "Set manager of Kirk Short to Kaci Costa",
+            "Set manager of Anil McGregor in Payroll to Atif Wells",
+            "Set head of Accounting to Kaci Costa",
+            "Set location of Accounting to Building 7",
+            "Set created of Design to 1999-12-31",
+            "Define macro hire as assign $1 to $2; set manager of $1 in $2 to $3",
+            "Grant Admin to Root",
+        ]
+        .iter()
+        .for_each(|query| {
+            db.query(query.to_string());
+        });
+        db.login("Root");
+        db.query(String::from("Grant Editor to Ellen on Design"));
+        db.logout();
+        db
+    }
+
+    mod snapshot {
+        use super::*;
+
+        #[test]
+        fn restores_the_database_it_was_captured_from() {
+            let db = organization();
+
+            assert_eq!(db, Database::from_snapshot(&db.snapshot()).unwrap());
+        }
+
+        #[test]
+        fn captures_names_rather_than_keys() {
+            let snapshot = organization().snapshot();
+            let payroll = snapshot
+                .departments
+                .iter()
+                .find(|department| department.name == "Payroll")
+                .unwrap();
+
+            assert_eq!(Some("Accounting"), payroll.parent.as_deref());
+            assert_eq!(
+                Some(ManagerSnapshot {
+                    employee: String::from("Atif Wells"),
+                    department: String::from("Design"),
+                }),
+                payroll.employees[0].manager
+            );
+        }
+
+        #[test]
+        fn rejects_missing_managers() {
+            let mut snapshot = organization().snapshot();
+            snapshot.departments[0].employees[0].manager = Some(ManagerSnapshot {
+                employee: String::from("Nobody"),
+                department: String::from("Design"),
+            });
+
+            assert_eq!(
+                Err(QueryError::NotFound(String::from(
+                    "Manager \"Nobody\" of employee \"Anil Mcgregor\" not found"
+                ))),
+                snapshot.restore().map(|_| ())
+            );
+        }
+
+        #[test]
+        fn rejects_memberships_ending_before_they_start() {
+            let mut snapshot = organization().snapshot();
+            snapshot.history[0].from = Date::new(2026, 3, 2).unwrap();
+            snapshot.history[0].to = Date::new(2026, 3, 1);
+            let employee = snapshot.history[0].employee.clone();
+            let department = snapshot.history[0].department.clone();
+
+            assert_eq!(
+                Err(QueryError::Conflict(format!(
+                    "Membership of employee \"{}\" in department \"{}\" cannot end on 2026-03-01, before it starts on 2026-03-02",
+                    employee, department
+                ))),
+                snapshot.restore().map(|_| ())
+            );
+        }
+
+        #[test]
+        fn rejects_aliases_named_after_commands() {
+            let mut snapshot = organization().snapshot();
+            snapshot.aliases[0].name = String::from("List");
+
+            assert_eq!(
+                Err(QueryError::Conflict(String::from(
+                    "\"List\" is already a command, and can't be redefined"
+                ))),
+                snapshot.restore().map(|_| ())
+            );
+        }
+
+        #[test]
+        fn rejects_users_without_an_admin() {
+            let mut snapshot = organization().snapshot();
+            snapshot.users.retain(|user| user.name != "Root");
+
+            assert_eq!(
+                Err(QueryError::Conflict(String::from(
+                    "Users must include an Admin of all departments"
+                ))),
+                snapshot.restore().map(|_| ())
+            );
+        }
+
+        #[test]
+        fn round_trips_through_json() {
+            let db = organization();
+
+            let json = serde_json::to_string(&db).unwrap();
+
+            assert_eq!(db, serde_json::from_str::<Database>(&json).unwrap());
+        }
+
+        #[test]
+        fn serializes_stable_field_names() {
+            let json = serde_json::to_value(organization()).unwrap();
+
+            assert_eq!("title", json["case"]);
+            assert_eq!("unicode", json["collation"]);
+            assert_eq!("1999-12-31", json["departments"][1]["created"]);
+            assert_eq!("Building 7", json["departments"][0]["location"]);
+            assert_eq!("Ellen", json["users"][0]["name"]);
+            assert_eq!("editor", json["users"][0]["grants"][0]["role"]);
+            assert_eq!("Design", json["users"][0]["grants"][0]["department"]);
+            assert_eq!("hire", json["aliases"][0]["name"]);
+            assert_eq!("macro", json["aliases"][0]["kind"]);
+            assert_eq!("assign $1 to $2", json["aliases"][0]["statements"][0]);
+        }
+    }
+}
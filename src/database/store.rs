@@ -1,26 +1,73 @@
 mod departments;
 mod dummy_data;
 mod employees;
+mod generator;
+mod history;
+use super::date::Date;
 use super::errors::QueryError;
+use super::names::Names;
+pub use departments::DepartmentField;
 use departments::{Department, Departments};
+use employees::Employee;
+pub use employees::EmployeeRef;
+pub use generator::{Generated, Generator, SizeDistribution};
+pub use history::History;
+#[cfg(feature = "serde")]
+pub use history::Membership;
+use std::collections::{BTreeMap, BTreeSet};
 
 #[derive(Default)]
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Store {
     index: Departments,
+    // Secondary index from employee key to the keys of every department they are in
+    directory: BTreeMap<String, BTreeSet<String>>,
+    history: History,
+    // The date changes take effect on, when it isn't today
+    today: Option<Date>,
+    names: Names,
 }
 
 impl Store {
-    pub fn new() -> Self {
+    pub fn new(names: Names) -> Self {
         Store {
-            index: Departments::new(),
+            index: Departments::new(names),
+            directory: BTreeMap::new(),
+            history: History::new(names),
+            today: None,
+            names,
         }
     }
 
+    pub fn names(&self) -> Names {
+        self.names
+    }
+
     pub fn seed(&mut self) {
         dummy_data::populate(self);
     }
 
+    pub fn generate(&mut self, generator: &Generator) -> Generated {
+        generator::generate(self, generator)
+    }
+
+    pub fn today(&self) -> Date {
+        self.today.unwrap_or_else(Date::today)
+    }
+
+    pub fn set_today(&mut self, today: Option<Date>) {
+        self.today = today;
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn set_history(&mut self, history: History) {
+        self.history = history;
+    }
+
     pub fn departments(&self) -> &Departments {
         &self.index
     }
@@ -36,6 +83,350 @@ impl Store {
     pub fn department_mut(&mut self, department_name: &str) -> Result<&mut Department, QueryError> {
         self.index.department_mut(department_name)
     }
+
+    pub fn delete_department(&mut self, department_name: &str) -> Result<String, QueryError> {
+        let department = self.department(department_name)?;
+        let removed = department
+            .employees()
+            .iter()
+            .map(|employee| EmployeeRef::new(self.names, department.name(), employee.name()))
+            .collect::<Vec<EmployeeRef>>();
+        let name = self.index.delete(department_name)?;
+        let today = self.today();
+        removed.iter().for_each(|employee| {
+            self.remove_from_directory(employee);
+            self.history.close(employee, today);
+        });
+        self.history.dissolve_department(&name);
+        Ok(name)
+    }
+
+    pub fn assign(
+        &mut self,
+        employee_name: &str,
+        department_name: &str,
+    ) -> Result<String, QueryError> {
+        let department = self.department_mut(department_name)?;
+        let name = department.assign(employee_name)?;
+        let department_name = department.name().to_owned();
+        self.open_membership(&name, &department_name);
+        Ok(name)
+    }
+
+    pub fn insert_employee(
+        &mut self,
+        department_name: &str,
+        employee: Employee,
+    ) -> Result<String, QueryError> {
+        let department = self.department_mut(department_name)?;
+        let name = department.employees_mut().insert(employee)?;
+        let department_name = department.name().to_owned();
+        self.open_membership(&name, &department_name);
+        Ok(name)
+    }
+
+    fn open_membership(&mut self, employee_name: &str, department_name: &str) {
+        let today = self.today();
+        self.add_to_directory(&EmployeeRef::new(
+            self.names,
+            department_name,
+            employee_name,
+        ));
+        self.history.open(employee_name, department_name, today);
+    }
+
+    pub fn take_employee(&mut self, employee: &EmployeeRef) -> Result<Employee, QueryError> {
+        let taken = self
+            .department_mut(employee.department())?
+            .employees_mut()
+            .take(employee.employee())?;
+        self.remove_from_directory(employee);
+        self.history.close(employee, self.today());
+        Ok(taken)
+    }
+
+    pub fn delete_employee(&mut self, employee: &EmployeeRef) -> Result<(), QueryError> {
+        self.department_mut(employee.department())?
+            .employees_mut()
+            .delete(employee.employee())?;
+        self.remove_from_directory(employee);
+        self.history.close(employee, self.today());
+        Ok(())
+    }
+
+    // Changes backdated to before an employee joined their department would end their
+    // membership there before it started, so they are checked before anything is changed
+    pub fn check_leaving(&self, employees: &[EmployeeRef]) -> Result<(), QueryError> {
+        let today = self.today();
+        employees
+            .iter()
+            .try_for_each(|employee| self.history.check_close(employee, today))
+    }
+
+    pub fn find(&self, employee_name: &str) -> Vec<EmployeeRef> {
+        self.directory
+            .get(&self.names.key(employee_name))
+            .map_or_else(Vec::new, |departments| {
+                departments
+                    .iter()
+                    .map(|department| EmployeeRef::new(self.names, department, employee_name))
+                    .collect()
+            })
+    }
+
+    fn add_to_directory(&mut self, employee: &EmployeeRef) {
+        self.directory
+            .entry(employee.employee().to_owned())
+            .or_default()
+            .insert(employee.department().to_owned());
+    }
+
+    fn remove_from_directory(&mut self, employee: &EmployeeRef) {
+        if let Some(departments) = self.directory.get_mut(employee.employee()) {
+            departments.remove(employee.department());
+            if departments.is_empty() {
+                self.directory.remove(employee.employee());
+            }
+        }
+    }
+
+    pub fn locate(
+        &self,
+        employee_name: &str,
+        department_name: Option<&str>,
+    ) -> Result<EmployeeRef, QueryError> {
+        if let Some(department_name) = department_name {
+            let department = self.department(department_name)?;
+            let employee = department.employees().employee(employee_name)?;
+            return Ok(EmployeeRef::new(
+                self.names,
+                department.name(),
+                employee.name(),
+            ));
+        }
+        let mut found = self.find(employee_name);
+        match found.len() {
+            0 => Err(QueryError::NotFound(format!(
+                "Employee \"{}\" does not exist",
+                employee_name
+            ))),
+            1 => Ok(found.remove(0)),
+            _ => Err(QueryError::Conflict(format!(
+                "Employee \"{}\" is in more than one department ({}); specify one with \"in {{department}}\"",
+                employee_name,
+                found
+                    .iter()
+                    .map(|employee| self.department_name(employee))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ))),
+        }
+    }
+
+    pub fn employee(&self, employee: &EmployeeRef) -> Option<&Employee> {
+        self.department(employee.department())
+            .ok()?
+            .employees()
+            .employee(employee.employee())
+            .ok()
+    }
+
+    fn employee_mut(&mut self, employee: &EmployeeRef) -> Option<&mut Employee> {
+        self.department_mut(employee.department())
+            .ok()?
+            .employees_mut()
+            .employee_mut(employee.employee())
+            .ok()
+    }
+
+    pub fn employee_name(&self, employee: &EmployeeRef) -> String {
+        self.employee(employee).map_or_else(
+            || employee.employee().to_owned(),
+            |found| found.name().to_owned(),
+        )
+    }
+
+    pub fn department_name(&self, employee: &EmployeeRef) -> String {
+        self.department(employee.department()).map_or_else(
+            |_| employee.department().to_owned(),
+            |found| found.name().to_owned(),
+        )
+    }
+
+    pub fn chain_of_command(&self, employee: &EmployeeRef) -> Vec<EmployeeRef> {
+        let mut chain: Vec<EmployeeRef> = Vec::new();
+        let mut current = self
+            .employee(employee)
+            .and_then(|found| found.manager().cloned());
+        while let Some(manager) = current {
+            if chain.contains(&manager) {
+                break;
+            }
+            current = self
+                .employee(&manager)
+                .and_then(|found| found.manager().cloned());
+            chain.push(manager);
+        }
+        chain
+    }
+
+    pub fn set_manager(
+        &mut self,
+        employee: &EmployeeRef,
+        manager: Option<EmployeeRef>,
+    ) -> Result<(), QueryError> {
+        if let Some(manager) = &manager {
+            if manager == employee || self.chain_of_command(manager).contains(employee) {
+                return Err(QueryError::Conflict(format!(
+                    "Employee \"{}\" cannot report to \"{}\": reporting lines cannot form a cycle",
+                    self.employee_name(employee),
+                    self.employee_name(manager)
+                )));
+            }
+        }
+        match self.employee_mut(employee) {
+            None => Err(QueryError::NotFound(format!(
+                "Employee \"{}\" does not exist",
+                employee.employee()
+            ))),
+            Some(found) => {
+                found.set_manager(manager);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn reports(&self, manager: &EmployeeRef) -> Vec<EmployeeRef> {
+        self.index
+            .iter()
+            .flat_map(|department| {
+                department
+                    .employees()
+                    .iter()
+                    .filter(|employee| employee.manager() == Some(manager))
+                    .map(move |employee| {
+                        EmployeeRef::new(self.names, department.name(), employee.name())
+                    })
+            })
+            .collect::<Vec<EmployeeRef>>()
+    }
+
+    pub fn all_reports(&self, manager: &EmployeeRef) -> Vec<EmployeeRef> {
+        let mut reports = Vec::new();
+        self.reports(manager).into_iter().for_each(|report| {
+            let indirect_reports = self.all_reports(&report);
+            reports.push(report);
+            reports.extend(indirect_reports);
+        });
+        reports
+    }
+
+    /// Reassigns anyone reporting to the removed employees to the nearest remaining manager above
+    /// them, returning each affected report with their new manager, if any.
+    pub fn release_reports(
+        &mut self,
+        removed: &[EmployeeRef],
+    ) -> Vec<(EmployeeRef, Option<EmployeeRef>)> {
+        let removed_set = removed.iter().collect::<BTreeSet<&EmployeeRef>>();
+        // One pass over the store finds the reports of every removed employee at once
+        let mut reports = BTreeMap::<EmployeeRef, Vec<EmployeeRef>>::new();
+        self.index.iter().for_each(|department| {
+            department.employees().iter().for_each(|employee| {
+                if let Some(manager) = employee
+                    .manager()
+                    .filter(|manager| removed_set.contains(manager))
+                {
+                    reports
+                        .entry(manager.clone())
+                        .or_default()
+                        .push(EmployeeRef::new(
+                            self.names,
+                            department.name(),
+                            employee.name(),
+                        ));
+                }
+            })
+        });
+        let mut released = Vec::new();
+        for manager in removed {
+            let new_manager = self
+                .chain_of_command(manager)
+                .into_iter()
+                .find(|above| !removed_set.contains(above));
+            reports
+                .remove(manager)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|report| !removed_set.contains(report))
+                .for_each(|report| released.push((report, new_manager.clone())));
+        }
+        released.iter().for_each(|(report, new_manager)| {
+            self.employee_mut(report)
+                .unwrap()
+                .set_manager(new_manager.clone());
+        });
+        released
+    }
+
+    pub fn rename_department(
+        &mut self,
+        department_name: &str,
+        new_name: &str,
+    ) -> Result<String, QueryError> {
+        let names = self.names;
+        let name = self.index.rename(department_name, new_name)?;
+        let old_key = names.key(department_name);
+        let new_key = names.key(&name);
+        self.history.rename_department(department_name, &name);
+        let moved = self.index.department(&name)?.employees().list();
+        moved.iter().for_each(|employee_name| {
+            let departments = self.directory.get_mut(&names.key(employee_name)).unwrap();
+            departments.remove(&old_key);
+            departments.insert(new_key.clone());
+        });
+        self.index.iter_mut().for_each(|department| {
+            department
+                .employees_mut()
+                .iter_mut()
+                .for_each(|employee| match employee.manager() {
+                    Some(manager)
+                        if *manager
+                            == EmployeeRef::new(names, department_name, manager.employee()) =>
+                    {
+                        let manager = EmployeeRef::new(names, &name, manager.employee());
+                        employee.set_manager(Some(manager));
+                    }
+                    _ => {}
+                })
+        });
+        Ok(name)
+    }
+
+    pub fn rename_employee(
+        &mut self,
+        employee: &EmployeeRef,
+        new_name: &str,
+    ) -> Result<String, QueryError> {
+        let name = self
+            .department_mut(employee.department())?
+            .rename_employee(employee.employee(), new_name)?;
+        let renamed = EmployeeRef::new(self.names, employee.department(), &name);
+        self.remove_from_directory(employee);
+        self.add_to_directory(&renamed);
+        self.history.rename_employee(employee, &name);
+        self.retarget_reports(employee, &renamed);
+        Ok(name)
+    }
+
+    pub fn retarget_reports(&mut self, from: &EmployeeRef, to: &EmployeeRef) {
+        self.index.iter_mut().for_each(|department| {
+            department
+                .employees_mut()
+                .iter_mut()
+                .filter(|employee| employee.manager() == Some(from))
+                .for_each(|employee| employee.set_manager(Some(to.clone())));
+        });
+    }
 }
 
 #[cfg(test)]
@@ -46,11 +437,11 @@ mod tests {
         use super::*;
 
         mod seed {
-            use super::Store;
+            use super::{Names, Store};
 
             #[test]
             fn populates_store() {
-                let mut store = Store::new();
+                let mut store = Store::new(Names::default());
 
                 store.seed();
 
@@ -59,42 +450,48 @@ mod tests {
         }
 
         mod departments {
-            use super::{Departments, Store};
+            use super::{Departments, Names, Store};
 
             #[test]
             fn returns_departments() {
-                let store = Store::new();
+                let store = Store::new(Names::default());
 
-                assert_eq!(&(Departments::new()), store.departments());
+                assert_eq!(&(Departments::new(Names::default())), store.departments());
             }
         }
 
         mod departments_mut {
-            use super::{Departments, Store};
+            use super::{Departments, Names, Store};
 
             #[test]
             fn returns_departments() {
-                let mut store = Store::new();
+                let mut store = Store::new(Names::default());
 
-                assert_eq!(&mut (Departments::new()), store.departments_mut());
+                assert_eq!(
+                    &mut (Departments::new(Names::default())),
+                    store.departments_mut()
+                );
             }
         }
 
         mod department {
-            use super::{Department, QueryError, Store};
+            use super::{Department, Names, QueryError, Store};
 
             #[test]
             fn department_exists() {
-                let mut store = Store::new();
+                let mut store = Store::new(Names::default());
 
                 store.departments_mut().create("Assets").unwrap();
 
-                assert_eq!(Ok(&(Department::new("Assets"))), store.department("Assets"));
+                assert_eq!(
+                    Ok(&(Department::new(Names::default(), "Assets"))),
+                    store.department("Assets")
+                );
             }
 
             #[test]
             fn department_doesnt_exist() {
-                let store = Store::new();
+                let store = Store::new(Names::default());
 
                 assert_eq!(
                     Err(QueryError::NotFound(
@@ -106,23 +503,23 @@ mod tests {
         }
 
         mod department_mut {
-            use super::{Department, QueryError, Store};
+            use super::{Department, Names, QueryError, Store};
 
             #[test]
             fn department_exists() {
-                let mut store = Store::new();
+                let mut store = Store::new(Names::default());
 
                 store.departments_mut().create("Liabilities").unwrap();
 
                 assert_eq!(
-                    Ok(&mut (Department::new("Liabilities"))),
+                    Ok(&mut (Department::new(Names::default(), "Liabilities"))),
                     store.department_mut("Liabilities")
                 );
             }
 
             #[test]
             fn department_doesnt_exist() {
-                let mut store = Store::new();
+                let mut store = Store::new(Names::default());
 
                 assert_eq!(
                     Err(QueryError::NotFound(
@@ -132,5 +529,142 @@ mod tests {
                 );
             }
         }
+
+        mod locate {
+            use super::{EmployeeRef, Names, QueryError, Store};
+
+            #[test]
+            fn finds_employee_in_only_department() {
+                let mut store = Store::new(Names::default());
+                store.departments_mut().create("Bakery").unwrap();
+                store.assign("Muffin Man", "Bakery").unwrap();
+
+                assert_eq!(
+                    Ok(EmployeeRef::new(Names::default(), "Bakery", "Muffin Man")),
+                    store.locate("muffin man", None)
+                );
+            }
+
+            #[test]
+            fn fails_on_ambiguous_employee() {
+                let mut store = Store::new(Names::default());
+                store.departments_mut().create("Bakery").unwrap();
+                store.departments_mut().create("Lane").unwrap();
+                store.assign("Muffin Man", "Bakery").unwrap();
+                store.assign("Muffin Man", "Lane").unwrap();
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "Employee \"Muffin Man\" is in more than one department (Bakery, Lane); specify one with \"in {department}\"".to_string()
+                    )),
+                    store.locate("Muffin Man", None)
+                );
+                assert_eq!(
+                    Ok(EmployeeRef::new(Names::default(), "Lane", "Muffin Man")),
+                    store.locate("Muffin Man", Some("lane"))
+                );
+            }
+        }
+
+        mod find {
+            use super::{EmployeeRef, Names, Store};
+
+            #[test]
+            fn tracks_employees_across_changes() {
+                let mut store = Store::new(Names::default());
+                store.departments_mut().create("Bakery").unwrap();
+                store.departments_mut().create("Lane").unwrap();
+                store.assign("Muffin Man", "Bakery").unwrap();
+                store.assign("Muffin Man", "Lane").unwrap();
+
+                assert_eq!(
+                    vec![
+                        EmployeeRef::new(Names::default(), "Bakery", "Muffin Man"),
+                        EmployeeRef::new(Names::default(), "Lane", "Muffin Man")
+                    ],
+                    store.find("muffin man")
+                );
+
+                let muffin_man = store
+                    .take_employee(&EmployeeRef::new(Names::default(), "Lane", "Muffin Man"))
+                    .unwrap();
+                store.rename_department("Bakery", "Kitchen").unwrap();
+                store.insert_employee("Kitchen", muffin_man).unwrap_err();
+                store
+                    .rename_employee(
+                        &EmployeeRef::new(Names::default(), "Kitchen", "Muffin Man"),
+                        "Baker",
+                    )
+                    .unwrap();
+
+                assert_eq!(Vec::<EmployeeRef>::new(), store.find("Muffin Man"));
+                assert_eq!(
+                    vec![EmployeeRef::new(Names::default(), "Kitchen", "Baker")],
+                    store.find("Baker")
+                );
+
+                store.delete_department("Kitchen").unwrap();
+
+                assert_eq!(Vec::<EmployeeRef>::new(), store.find("Baker"));
+            }
+        }
+
+        mod set_manager {
+            use super::{EmployeeRef, Names, QueryError, Store};
+
+            #[test]
+            fn rejects_reporting_cycles() {
+                let mut store = Store::new(Names::default());
+                store.departments_mut().create("Court").unwrap();
+                let court = store.department_mut("Court").unwrap();
+                court.assign("King").unwrap();
+                court.assign("Knight").unwrap();
+                let king = EmployeeRef::new(Names::default(), "Court", "King");
+                let knight = EmployeeRef::new(Names::default(), "Court", "Knight");
+
+                store.set_manager(&knight, Some(king.clone())).unwrap();
+
+                assert_eq!(vec![king.clone()], store.chain_of_command(&knight));
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "Employee \"King\" cannot report to \"Knight\": reporting lines cannot form a cycle".to_string()
+                    )),
+                    store.set_manager(&king, Some(knight))
+                );
+            }
+        }
+
+        mod release_reports {
+            use super::{EmployeeRef, Names, Store};
+
+            #[test]
+            fn reassigns_reports_to_nearest_remaining_manager() {
+                let mut store = Store::new(Names::default());
+                store.departments_mut().create("Court").unwrap();
+                let court = store.department_mut("Court").unwrap();
+                court.assign("King").unwrap();
+                court.assign("Duke").unwrap();
+                court.assign("Baron").unwrap();
+                court.assign("Knight").unwrap();
+                let king = EmployeeRef::new(Names::default(), "Court", "King");
+                let duke = EmployeeRef::new(Names::default(), "Court", "Duke");
+                let baron = EmployeeRef::new(Names::default(), "Court", "Baron");
+                let knight = EmployeeRef::new(Names::default(), "Court", "Knight");
+                store.set_manager(&duke, Some(king.clone())).unwrap();
+                store.set_manager(&baron, Some(duke.clone())).unwrap();
+                store.set_manager(&knight, Some(baron.clone())).unwrap();
+
+                assert_eq!(
+                    vec![(knight.clone(), Some(king.clone()))],
+                    store.release_reports(&[duke.clone(), baron])
+                );
+                assert_eq!(vec![king.clone()], store.chain_of_command(&knight));
+
+                assert_eq!(
+                    vec![(duke, None), (knight, None)],
+                    store.release_reports(&[king])
+                );
+            }
+        }
     }
 }
@@ -1,19 +1,65 @@
+use super::super::date::Date;
 use super::super::errors::QueryError;
+use super::super::names::Names;
 use super::employees::Employees;
 use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DepartmentField {
+    Head,
+    CostCenter,
+    Location,
+    Description,
+    Created,
+}
+
+impl DepartmentField {
+    pub fn parse(field_name: &str) -> Option<DepartmentField> {
+        match field_name.to_uppercase().as_str() {
+            "HEAD" | "LEAD" => Some(DepartmentField::Head),
+            "COST CENTER" | "COSTCENTER" | "COST CENTRE" => Some(DepartmentField::CostCenter),
+            "LOCATION" => Some(DepartmentField::Location),
+            "DESCRIPTION" => Some(DepartmentField::Description),
+            "CREATED" | "CREATED DATE" => Some(DepartmentField::Created),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DepartmentField::Head => "Head",
+            DepartmentField::CostCenter => "Cost Center",
+            DepartmentField::Location => "Location",
+            DepartmentField::Description => "Description",
+            DepartmentField::Created => "Created",
+        }
+    }
+}
+
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Department {
     name: String,
     employees: Employees,
+    parent: Option<String>,
+    head: Option<String>,
+    cost_center: Option<String>,
+    location: Option<String>,
+    description: Option<String>,
+    created: Option<Date>,
 }
 
 impl Department {
-    pub fn new(name: &str) -> Self {
+    pub fn new(names: Names, name: &str) -> Self {
         Department {
-            name: to_name(name),
-            employees: Employees::new(),
+            name: names.name(name),
+            employees: Employees::new(names),
+            parent: None,
+            head: None,
+            cost_center: None,
+            location: None,
+            description: None,
+            created: None,
         }
     }
 
@@ -21,6 +67,66 @@ impl Department {
         &self.name
     }
 
+    pub fn parent(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    pub fn head(&self) -> Option<&str> {
+        self.head
+            .as_deref()
+            .and_then(|head| self.employees.employee(head).ok())
+            .map(|head| head.name())
+    }
+
+    pub fn created(&self) -> Option<Date> {
+        self.created
+    }
+
+    pub fn set_created(&mut self, created: Date) {
+        self.created = Some(created);
+    }
+
+    pub fn field(&self, field: DepartmentField) -> Option<String> {
+        match field {
+            DepartmentField::Head => self.head().map(String::from),
+            DepartmentField::CostCenter => self.cost_center.clone(),
+            DepartmentField::Location => self.location.clone(),
+            DepartmentField::Description => self.description.clone(),
+            DepartmentField::Created => self.created.map(|created| created.to_string()),
+        }
+    }
+
+    pub fn set_field(
+        &mut self,
+        field: DepartmentField,
+        value: Option<&str>,
+    ) -> Result<(), QueryError> {
+        match field {
+            DepartmentField::Head => {
+                self.head = match value {
+                    None => None,
+                    Some(head) => Some(
+                        self.employees
+                            .names()
+                            .key(self.employees.employee(head)?.name()),
+                    ),
+                }
+            }
+            DepartmentField::CostCenter => self.cost_center = value.map(String::from),
+            DepartmentField::Location => self.location = value.map(String::from),
+            DepartmentField::Description => self.description = value.map(String::from),
+            DepartmentField::Created => {
+                self.created = match value {
+                    None => None,
+                    Some(created) => Some(Date::parse(created).ok_or_else(|| {
+                        QueryError::Conflict(format!("\"{}\" is not a YYYY-MM-DD date", created))
+                    })?),
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn employees(&self) -> &Employees {
         &self.employees
     }
@@ -29,6 +135,20 @@ impl Department {
         &mut self.employees
     }
 
+    pub fn rename_employee(
+        &mut self,
+        employee_name: &str,
+        new_name: &str,
+    ) -> Result<String, QueryError> {
+        let names = self.employees.names();
+        let was_head = self.head.as_deref() == Some(names.key(employee_name).as_str());
+        let name = self.employees.rename(employee_name, new_name)?;
+        if was_head {
+            self.head = Some(names.key(&name));
+        }
+        Ok(name)
+    }
+
     pub fn assign(&mut self, employee_name: &str) -> Result<String, QueryError> {
         self.employees_mut().create(employee_name)
     }
@@ -38,17 +158,19 @@ impl Department {
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Departments {
     index: BTreeMap<String, Department>,
+    names: Names,
 }
 
 impl Departments {
-    pub fn new() -> Self {
+    pub fn new(names: Names) -> Self {
         Departments {
             index: BTreeMap::new(),
+            names,
         }
     }
 
     pub fn department(&self, department_name: &str) -> Result<&Department, QueryError> {
-        match self.index.get(&to_key(department_name)) {
+        match self.index.get(&self.names.key(department_name)) {
             None => Err(QueryError::NotFound(format!(
                 "Department \"{}\" not found",
                 department_name
@@ -58,7 +180,7 @@ impl Departments {
     }
 
     pub fn department_mut(&mut self, department_name: &str) -> Result<&mut Department, QueryError> {
-        match self.index.get_mut(&to_key(department_name)) {
+        match self.index.get_mut(&self.names.key(department_name)) {
             None => Err(QueryError::NotFound(format!(
                 "Department \"{}\" not found",
                 department_name
@@ -67,19 +189,27 @@ impl Departments {
         }
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Department> {
+        self.index.values()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Department> {
+        self.index.values_mut()
+    }
+
     pub fn list(&self) -> Vec<String> {
         self.index
-            .iter()
-            .map(|(_key, department)| department.name().to_string())
+            .values()
+            .map(|department| department.name().to_string())
             .collect::<Vec<String>>()
     }
 
     pub fn create(&mut self, department: &str) -> Result<String, QueryError> {
-        match self.index.entry(to_key(department)) {
-            Entry::Vacant(entry) => {
-                entry.insert(Department::new(department));
-                Ok(to_name(department))
-            }
+        match self.index.entry(self.names.key(department)) {
+            Entry::Vacant(entry) => Ok(entry
+                .insert(Department::new(self.names, department))
+                .name()
+                .to_owned()),
             Entry::Occupied(_) => Err(QueryError::Conflict(format!(
                 "Department \"{}\" already exists",
                 department
@@ -87,89 +217,214 @@ impl Departments {
         }
     }
 
+    pub fn create_under(&mut self, department: &str, parent: &str) -> Result<String, QueryError> {
+        let parent_key = self.names.key(self.department(parent)?.name());
+        let name = self.create(department)?;
+        self.index
+            .get_mut(&self.names.key(department))
+            .unwrap()
+            .parent = Some(parent_key);
+        Ok(name)
+    }
+
     pub fn delete(&mut self, department: &str) -> Result<String, QueryError> {
-        match self.index.remove(&to_key(department)) {
+        let key = self.names.key(department);
+        match self.index.remove(&key) {
             None => Err(QueryError::NotFound(format!(
                 "Department \"{}\" not found",
                 department
             ))),
-            Some(_) => Ok(to_name(department)),
+            Some(removed) => {
+                self.index
+                    .values_mut()
+                    .filter(|child| child.parent.as_deref() == Some(key.as_str()))
+                    .for_each(|child| child.parent = removed.parent.clone());
+                Ok(removed.name)
+            }
         }
     }
-}
 
-fn to_key(value: &str) -> String {
-    value.to_uppercase()
-}
+    pub fn rename(&mut self, department: &str, new_name: &str) -> Result<String, QueryError> {
+        let key = self.names.key(department);
+        let new_key = self.names.key(new_name);
+        if !self.index.contains_key(&key) {
+            return Err(QueryError::NotFound(format!(
+                "Department \"{}\" not found",
+                department
+            )));
+        }
+        if new_key != key && self.index.contains_key(&new_key) {
+            return Err(QueryError::Conflict(format!(
+                "Department \"{}\" already exists",
+                new_name
+            )));
+        }
+        let mut renamed = self.index.remove(&key).unwrap();
+        renamed.name = self.names.name(new_name);
+        let name = renamed.name.clone();
+        self.index.insert(new_key.clone(), renamed);
+        self.index
+            .values_mut()
+            .filter(|child| child.parent.as_deref() == Some(key.as_str()))
+            .for_each(|child| child.parent = Some(new_key.clone()));
+        Ok(name)
+    }
 
-fn to_name(value: &str) -> String {
-    value
-        .chars()
-        .enumerate()
-        .map(|(index, character)| {
-            if index == 0 {
-                character.to_uppercase().next().unwrap()
-            } else {
-                character.to_lowercase().next().unwrap()
+    pub fn set_parent(&mut self, department: &str, parent: Option<&str>) -> Result<(), QueryError> {
+        self.department(department)?;
+        let key = self.names.key(department);
+        let parent_key = match parent {
+            None => None,
+            Some(parent) => {
+                self.department(parent)?;
+                let parent_key = self.names.key(parent);
+                if parent_key == key || self.ancestors(&parent_key).contains(&key) {
+                    return Err(QueryError::Conflict(format!(
+                        "Department \"{}\" cannot be placed under itself or its own sub-department \"{}\"",
+                        department, parent
+                    )));
+                }
+                Some(parent_key)
             }
-        })
-        .collect::<String>()
-}
+        };
+        self.index.get_mut(&key).unwrap().parent = parent_key;
+        Ok(())
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    pub fn children(&self, department: &str) -> Vec<String> {
+        let key = self.names.key(department);
+        self.index
+            .values()
+            .filter(|child| child.parent.as_deref() == Some(key.as_str()))
+            .map(|child| child.name().to_string())
+            .collect::<Vec<String>>()
+    }
 
-    mod fn_to_key {
-        use super::to_key;
+    pub fn descendants(&self, department: &str) -> Vec<String> {
+        let mut descendants = Vec::new();
+        self.children(department).into_iter().for_each(|child| {
+            let grandchildren = self.descendants(&child);
+            descendants.push(child);
+            descendants.extend(grandchildren);
+        });
+        descendants
+    }
 
-        #[test]
-        fn capitalizes_all_letters() {
-            assert_eq!("SALSIFIERS".to_string(), to_key("Salsifiers"));
+    pub fn tree(&self) -> Vec<(usize, String)> {
+        fn visit(
+            departments: &Departments,
+            name: &str,
+            depth: usize,
+            nodes: &mut Vec<(usize, String)>,
+        ) {
+            nodes.push((depth, name.to_string()));
+            departments
+                .children(name)
+                .iter()
+                .for_each(|child| visit(departments, child, depth + 1, nodes));
         }
+        let mut nodes = Vec::new();
+        self.index
+            .values()
+            .filter(|department| department.parent.is_none())
+            .for_each(|root| visit(self, root.name(), 0, &mut nodes));
+        nodes
     }
 
-    mod fn_to_name {
-        use super::to_name;
-
-        #[test]
-        fn capitalizes_word() {
-            assert_eq!("Chippers".to_string(), to_name("cHiPpErS"));
+    fn ancestors(&self, key: &str) -> Vec<String> {
+        let mut ancestors = Vec::new();
+        let mut current = self
+            .index
+            .get(key)
+            .and_then(|department| department.parent.clone());
+        while let Some(parent_key) = current {
+            current = self
+                .index
+                .get(&parent_key)
+                .and_then(|department| department.parent.clone());
+            ancestors.push(parent_key);
         }
+        ancestors
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     mod department {
-        use super::{Department, Employees};
+        use super::{Department, DepartmentField, Employees, Names, QueryError};
 
         #[test]
         fn name_returns_name() {
-            let dept = Department::new("Mail");
+            let dept = Department::new(Names::default(), "Mail");
             assert_eq!("Mail", dept.name());
         }
 
         #[test]
         fn employees_returns_employees() {
-            let dept = Department::new("Staffing");
-            assert_eq!(&(Employees::new()), dept.employees());
+            let dept = Department::new(Names::default(), "Staffing");
+            assert_eq!(&(Employees::new(Names::default())), dept.employees());
         }
 
         #[test]
         fn employees_mut_returns_mut_employees() {
-            let mut dept = Department::new("Playdoh");
+            let mut dept = Department::new(Names::default(), "Playdoh");
             dept.employees_mut().create("Ice Cream").unwrap();
 
-            let mut employees = Employees::new();
+            let mut employees = Employees::new(Names::default());
             employees.create("Ice Cream").unwrap();
 
             assert_eq!(&employees, dept.employees());
         }
 
+        #[test]
+        fn set_field_stores_metadata() {
+            let mut dept = Department::new(Names::default(), "Vault");
+            dept.set_field(DepartmentField::Location, Some("Basement"))
+                .unwrap();
+            dept.set_field(DepartmentField::Created, Some("2026-03-01"))
+                .unwrap();
+
+            assert_eq!(
+                Some("Basement".to_string()),
+                dept.field(DepartmentField::Location)
+            );
+            assert_eq!(
+                Some("2026-03-01".to_string()),
+                dept.field(DepartmentField::Created)
+            );
+
+            dept.set_field(DepartmentField::Location, None).unwrap();
+            assert_eq!(None, dept.field(DepartmentField::Location));
+        }
+
+        #[test]
+        fn head_must_be_member() {
+            let mut dept = Department::new(Names::default(), "Vault");
+            dept.assign("Scrooge").unwrap();
+
+            assert_eq!(
+                Err(QueryError::NotFound(
+                    "Employee \"Magica\" does not exist".to_string()
+                )),
+                dept.set_field(DepartmentField::Head, Some("Magica"))
+            );
+
+            dept.set_field(DepartmentField::Head, Some("scrooge"))
+                .unwrap();
+            assert_eq!(Some("Scrooge"), dept.head());
+
+            dept.employees_mut().delete("Scrooge").unwrap();
+            assert_eq!(None, dept.head());
+        }
+
         #[test]
         fn assign_adds_new_employee() {
-            let mut dept = Department::new("Quests");
+            let mut dept = Department::new(Names::default(), "Quests");
             dept.assign("Johnny").unwrap();
 
-            let mut employees = Employees::new();
+            let mut employees = Employees::new(Names::default());
             employees.create("Johnny").unwrap();
 
             assert_eq!(&employees, dept.employees());
@@ -180,20 +435,23 @@ mod tests {
         use super::*;
 
         mod department {
-            use super::{Department, Departments, QueryError};
+            use super::{Department, Departments, Names, QueryError};
 
             #[test]
             fn department_exists() {
-                let mut depts = Departments::new();
+                let mut depts = Departments::new(Names::default());
 
                 depts.create("Babies").unwrap();
 
-                assert_eq!(Ok(&(Department::new("Babies"))), depts.department("Babies"));
+                assert_eq!(
+                    Ok(&(Department::new(Names::default(), "Babies"))),
+                    depts.department("Babies")
+                );
             }
 
             #[test]
             fn department_doesnt_exist() {
-                let depts = Departments::new();
+                let depts = Departments::new(Names::default());
 
                 assert_eq!(
                     Err(QueryError::NotFound(
@@ -205,20 +463,23 @@ mod tests {
         }
 
         mod department_mut {
-            use super::{Department, Departments, QueryError};
+            use super::{Department, Departments, Names, QueryError};
 
             #[test]
             fn department_exists() {
-                let mut depts = Departments::new();
+                let mut depts = Departments::new(Names::default());
                 depts.create("Timekeepers").unwrap();
 
                 let dept = depts.department_mut("Timekeepers").unwrap();
-                assert_eq!(&mut (Department::new("Timekeepers")), dept);
+                assert_eq!(
+                    &mut (Department::new(Names::default(), "Timekeepers")),
+                    dept
+                );
             }
 
             #[test]
             fn department_doesnt_exist() {
-                let mut dept = Departments::new();
+                let mut dept = Departments::new(Names::default());
 
                 assert_eq!(
                     Err(QueryError::NotFound(
@@ -230,11 +491,11 @@ mod tests {
         }
 
         mod list {
-            use super::Departments;
+            use super::{Departments, Names};
 
             #[test]
             fn returns_dept_names() {
-                let mut depts = Departments::new();
+                let mut depts = Departments::new(Names::default());
 
                 assert_eq!(Vec::<String>::new(), depts.list());
 
@@ -254,11 +515,11 @@ mod tests {
         }
 
         mod create {
-            use super::{Departments, QueryError};
+            use super::{Departments, Names, QueryError};
 
             #[test]
             fn creates_dept() {
-                let mut depts = Departments::new();
+                let mut depts = Departments::new(Names::default());
 
                 assert_eq!(Ok("Recovery".to_string()), depts.create("Recovery"));
 
@@ -267,7 +528,7 @@ mod tests {
 
             #[test]
             fn fails_on_creating_duplicate() {
-                let mut depts = Departments::new();
+                let mut depts = Departments::new(Names::default());
 
                 depts.create("Rolling").unwrap();
 
@@ -280,12 +541,175 @@ mod tests {
             }
         }
 
+        mod create_under {
+            use super::{Departments, Names, QueryError};
+
+            #[test]
+            fn creates_child_dept() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Division").unwrap();
+
+                assert_eq!(
+                    Ok("Team".to_string()),
+                    depts.create_under("team", "division")
+                );
+
+                assert_eq!(
+                    Some(Names::default().key("Division").as_str()),
+                    depts.department("Team").unwrap().parent()
+                );
+            }
+
+            #[test]
+            fn fails_on_missing_parent() {
+                let mut depts = Departments::new(Names::default());
+
+                assert_eq!(
+                    Err(QueryError::NotFound(
+                        "Department \"Nowhere\" not found".to_string()
+                    )),
+                    depts.create_under("Team", "Nowhere")
+                );
+                assert_eq!(Vec::<String>::new(), depts.list());
+            }
+        }
+
+        mod rename {
+            use super::{Departments, Names, QueryError};
+
+            #[test]
+            fn rekeys_dept_and_keeps_children() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Divison").unwrap();
+                depts.create_under("Team", "Divison").unwrap();
+                depts
+                    .department_mut("Divison")
+                    .unwrap()
+                    .assign("Ann")
+                    .unwrap();
+
+                assert_eq!(
+                    Ok("Division".to_string()),
+                    depts.rename("divison", "division")
+                );
+
+                assert_eq!(
+                    vec!["Division".to_string(), "Team".to_string()],
+                    depts.list()
+                );
+                assert_eq!(vec!["Team".to_string()], depts.children("Division"));
+                assert_eq!(
+                    vec!["Ann".to_string()],
+                    depts.department("Division").unwrap().employees().list()
+                );
+            }
+
+            #[test]
+            fn fails_on_collision() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Red").unwrap();
+                depts.create("Blue").unwrap();
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "Department \"blue\" already exists".to_string()
+                    )),
+                    depts.rename("Red", "blue")
+                );
+            }
+        }
+
+        mod set_parent {
+            use super::{Departments, Names, QueryError};
+
+            #[test]
+            fn moves_dept() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Left").unwrap();
+                depts.create("Right").unwrap();
+                depts.create_under("Team", "Left").unwrap();
+
+                depts.set_parent("Team", Some("Right")).unwrap();
+                assert_eq!(vec!["Team".to_string()], depts.children("Right"));
+
+                depts.set_parent("Team", None).unwrap();
+                assert_eq!(None, depts.department("Team").unwrap().parent());
+            }
+
+            #[test]
+            fn rejects_cycles() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Division").unwrap();
+                depts.create_under("Group", "Division").unwrap();
+                depts.create_under("Team", "Group").unwrap();
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "Department \"Division\" cannot be placed under itself or its own sub-department \"Team\"".to_string()
+                    )),
+                    depts.set_parent("Division", Some("Team"))
+                );
+                assert!(depts.set_parent("Team", Some("Team")).is_err());
+            }
+        }
+
+        mod descendants {
+            use super::{Departments, Names};
+
+            #[test]
+            fn returns_all_levels_depth_first() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Division").unwrap();
+                depts.create_under("Beta", "Division").unwrap();
+                depts.create_under("Alpha", "Division").unwrap();
+                depts.create_under("Team", "Alpha").unwrap();
+
+                assert_eq!(
+                    vec!["Alpha".to_string(), "Team".to_string(), "Beta".to_string()],
+                    depts.descendants("Division")
+                );
+            }
+        }
+
+        mod tree {
+            use super::{Departments, Names};
+
+            #[test]
+            fn returns_depth_of_each_dept() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Zoo").unwrap();
+                depts.create("Division").unwrap();
+                depts.create_under("Team", "Division").unwrap();
+
+                assert_eq!(
+                    vec![
+                        (0, "Division".to_string()),
+                        (1, "Team".to_string()),
+                        (0, "Zoo".to_string())
+                    ],
+                    depts.tree()
+                );
+            }
+        }
+
         mod delete {
-            use super::{Departments, QueryError};
+            use super::{Departments, Names, QueryError};
+
+            #[test]
+            fn moves_children_to_grandparent() {
+                let mut depts = Departments::new(Names::default());
+                depts.create("Company").unwrap();
+                depts.create_under("Division", "Company").unwrap();
+                depts.create_under("Team", "Division").unwrap();
+
+                depts.delete("Division").unwrap();
+
+                assert_eq!(vec!["Team".to_string()], depts.children("Company"));
+            }
 
             #[test]
             fn deletes_dept() {
-                let mut depts = Departments::new();
+                let mut depts = Departments::new(Names::default());
 
                 depts.create("Temps").unwrap();
 
@@ -298,7 +722,7 @@ mod tests {
 
             #[test]
             fn fails_on_missing_dept() {
-                let mut depts = Departments::new();
+                let mut depts = Departments::new(Names::default());
 
                 assert_eq!(
                     Err(QueryError::NotFound(
@@ -9,7 +9,7 @@ const DATA: [(&str, [&str; 10]); 7] = [
             "Kaci Costa",
             "Kirk Short",
             "Olivia-Mae Schneider",
-            "Anil Mcgregor",
+            "Anil McGregor",
             "Benjamin Cotton",
             "Madison Wyatt",
             "Shyam Calderon",
@@ -113,15 +113,12 @@ pub fn populate(store: &mut Store) {
     .for_each(|(department_name, employees)| {
         store
             .departments_mut()
-            .create(&(*department_name).to_string())
+            .create(department_name)
             .unwrap_or_else(|_| panic!("Dummy data failed to populate on forming department \"{}\"", department_name));
-        let department = store
-            .department_mut(department_name)
-            .unwrap_or_else(|_| panic!("Dummy data failed to retrieve department \"{}\"", department_name));
         employees.iter()
             .for_each(|employee_name| {
-                department
-                    .assign(employee_name)
+                store
+                    .assign(employee_name, department_name)
                     .unwrap_or_else(|_| panic!("Dummy data failed to populate on assigning employee \"{}\" to department \"{}\"", employee_name, department_name));
             });
     });
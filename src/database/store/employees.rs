@@ -1,38 +1,82 @@
 use super::super::errors::QueryError;
+use super::super::names::Names;
 use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct EmployeeRef {
+    department: String,
+    employee: String,
+}
+
+impl EmployeeRef {
+    pub fn new(names: Names, department_name: &str, employee_name: &str) -> Self {
+        EmployeeRef {
+            department: names.key(department_name),
+            employee: names.key(employee_name),
+        }
+    }
+
+    pub fn department(&self) -> &str {
+        &self.department
+    }
+
+    pub fn employee(&self) -> &str {
+        &self.employee
+    }
+}
+
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Employee {
     name: String,
+    manager: Option<EmployeeRef>,
 }
 
 impl Employee {
-    pub fn new(name: &str) -> Self {
+    pub fn new(names: Names, name: &str) -> Self {
         Employee {
-            name: to_name(name),
+            name: names.name(name),
+            manager: None,
         }
     }
 
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    pub fn rename(&mut self, names: Names, name: &str) {
+        self.name = names.name(name);
+    }
+
+    pub fn manager(&self) -> Option<&EmployeeRef> {
+        self.manager.as_ref()
+    }
+
+    pub fn set_manager(&mut self, manager: Option<EmployeeRef>) {
+        self.manager = manager;
+    }
 }
 
 #[cfg_attr(test, derive(Debug, PartialEq))]
 pub struct Employees {
     index: BTreeMap<String, Employee>,
+    names: Names,
 }
 
 impl Employees {
-    pub fn new() -> Self {
+    pub fn new(names: Names) -> Self {
         Employees {
             index: BTreeMap::new(),
+            names,
         }
     }
 
+    pub fn names(&self) -> Names {
+        self.names
+    }
+
     pub fn employee(&self, employee_name: &str) -> Result<&Employee, QueryError> {
-        match self.index.get(&to_key(employee_name)) {
+        match self.index.get(&self.names.key(employee_name)) {
             None => Err(QueryError::NotFound(format!(
                 "Employee \"{}\" does not exist",
                 employee_name
@@ -41,19 +85,44 @@ impl Employees {
         }
     }
 
-    pub fn list(&self) -> Vec<String> {
+    pub fn employee_mut(&mut self, employee_name: &str) -> Result<&mut Employee, QueryError> {
+        match self.index.get_mut(&self.names.key(employee_name)) {
+            None => Err(QueryError::NotFound(format!(
+                "Employee \"{}\" does not exist",
+                employee_name
+            ))),
+            Some(employee) => Ok(employee),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Employee> {
+        self.index.values()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Employee> {
+        self.index.values_mut()
+    }
+
+    // Pairs each employee with their collation key, so callers can sort without re-deriving it
+    pub fn keyed(&self) -> impl Iterator<Item = (&str, &Employee)> {
         self.index
             .iter()
-            .map(|(_key, employee)| employee.name().to_string())
+            .map(|(key, employee)| (key.as_str(), employee))
+    }
+
+    pub fn list(&self) -> Vec<String> {
+        self.index
+            .values()
+            .map(|employee| employee.name().to_string())
             .collect::<Vec<String>>()
     }
 
     pub fn create(&mut self, employee: &str) -> Result<String, QueryError> {
-        match self.index.entry(to_key(employee)) {
-            Entry::Vacant(entry) => {
-                entry.insert(Employee::new(employee));
-                Ok(to_name(employee))
-            }
+        match self.index.entry(self.names.key(employee)) {
+            Entry::Vacant(entry) => Ok(entry
+                .insert(Employee::new(self.names, employee))
+                .name()
+                .to_owned()),
             Entry::Occupied(_) => Err(QueryError::Conflict(format!(
                 "Employee \"{}\" already exists",
                 employee,
@@ -61,76 +130,71 @@ impl Employees {
         }
     }
 
-    pub fn delete(&mut self, employee: &str) -> Result<(), QueryError> {
-        match self.index.remove(&to_key(employee)) {
+    pub fn insert(&mut self, employee: Employee) -> Result<String, QueryError> {
+        match self.index.entry(self.names.key(employee.name())) {
+            Entry::Vacant(entry) => Ok(entry.insert(employee).name().to_owned()),
+            Entry::Occupied(_) => Err(QueryError::Conflict(format!(
+                "Employee \"{}\" already exists",
+                employee.name(),
+            ))),
+        }
+    }
+
+    pub fn take(&mut self, employee: &str) -> Result<Employee, QueryError> {
+        match self.index.remove(&self.names.key(employee)) {
             None => Err(QueryError::NotFound(format!(
                 "Employee \"{}\" could not be found",
                 employee
             ))),
-            Some(_) => Ok(()),
+            Some(employee) => Ok(employee),
         }
     }
-}
 
-fn to_key(value: &str) -> String {
-    value.to_uppercase()
-}
+    pub fn rename(&mut self, employee: &str, new_name: &str) -> Result<String, QueryError> {
+        let key = self.names.key(employee);
+        let new_key = self.names.key(new_name);
+        if !self.index.contains_key(&key) {
+            return Err(QueryError::NotFound(format!(
+                "Employee \"{}\" does not exist",
+                employee
+            )));
+        }
+        if new_key != key && self.index.contains_key(&new_key) {
+            return Err(QueryError::Conflict(format!(
+                "Employee \"{}\" already exists",
+                new_name
+            )));
+        }
+        let mut renamed = self.index.remove(&key).unwrap();
+        renamed.rename(self.names, new_name);
+        let name = renamed.name.clone();
+        self.index.insert(new_key, renamed);
+        Ok(name)
+    }
 
-fn to_name(value: &str) -> String {
-    value
-        .split_whitespace()
-        .map(|word| {
-            word.chars()
-                .enumerate()
-                .map(|(index, character)| {
-                    if index == 0 {
-                        character.to_uppercase().next().unwrap()
-                    } else {
-                        character.to_lowercase().next().unwrap()
-                    }
-                })
-                .collect::<String>()
-        })
-        .enumerate()
-        .fold(String::new(), |mut name, (index, word)| {
-            if index > 0 {
-                name.push(' ');
-            }
-            name.push_str(&word);
-            name
-        })
+    pub fn delete(&mut self, employee: &str) -> Result<(), QueryError> {
+        match self.index.remove(&self.names.key(employee)) {
+            None => Err(QueryError::NotFound(format!(
+                "Employee \"{}\" could not be found",
+                employee
+            ))),
+            Some(_) => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    mod fn_to_key {
-        use super::to_key;
-
-        #[test]
-        fn capitalizes_all_letters() {
-            assert_eq!("ANGRY BOB".to_string(), to_key("Angry Bob"));
-        }
-    }
-
-    mod fn_to_name {
-        use super::to_name;
-
-        #[test]
-        fn capitalizes_each_word() {
-            assert_eq!("Greek Salad".to_string(), to_name("gReEk SaLaD"));
-        }
-    }
-
     mod employee {
-        use super::{to_name, Employee};
+        use super::{Employee, Names};
 
         #[test]
         fn name_returns_name() {
-            let employee = Employee::new("Joe Mombo");
+            let employee = Employee::new(Names::default(), "joe mombo");
 
-            assert_eq!(to_name("Joe Mombo"), employee.name());
+            assert_eq!(Names::default().name("Joe Mombo"), employee.name());
         }
     }
 
@@ -138,15 +202,15 @@ mod tests {
         use super::*;
 
         mod employee {
-            use super::{Employee, Employees, QueryError};
+            use super::{Employee, Employees, Names, QueryError};
 
             #[test]
             fn employee_exists() {
-                let mut employees = Employees::new();
+                let mut employees = Employees::new(Names::default());
                 employees.create("James McGregor").unwrap();
 
                 assert_eq!(
-                    Ok(&(Employee::new("James McGregor"))),
+                    Ok(&(Employee::new(Names::default(), "James McGregor"))),
                     employees.employee("James McGregor")
                 );
             }
@@ -157,17 +221,17 @@ mod tests {
                     Err(QueryError::NotFound(
                         "Employee \"Slenderman\" does not exist".to_string()
                     )),
-                    Employees::new().employee("Slenderman")
+                    Employees::new(Names::default()).employee("Slenderman")
                 );
             }
         }
 
         mod list {
-            use super::Employees;
+            use super::{Employees, Names};
 
             #[test]
             fn gets_employee_names() {
-                let mut employees = Employees::new();
+                let mut employees = Employees::new(Names::default());
                 employees.create("Sally Simmerman").unwrap();
                 employees.create("Jose Schwartz").unwrap();
                 employees.create("Yun Balloon").unwrap();
@@ -180,11 +244,11 @@ mod tests {
         }
 
         mod create {
-            use super::Employees;
+            use super::{Employees, Names};
 
             #[test]
             fn adds_employee() {
-                let mut employees = Employees::new();
+                let mut employees = Employees::new(Names::default());
 
                 employees.create("Cheese Wheelin").unwrap();
                 assert_eq!(vec!["Cheese Wheelin"], employees.list());
@@ -195,7 +259,7 @@ mod tests {
 
             #[test]
             fn fails_on_duplicate_key() {
-                let mut employees = Employees::new();
+                let mut employees = Employees::new(Names::default());
                 employees.create("John Doe").unwrap();
                 assert_eq!(vec!["John Doe"], employees.list());
 
@@ -204,12 +268,102 @@ mod tests {
             }
         }
 
+        mod insert {
+            use super::{Employee, EmployeeRef, Employees, Names, QueryError};
+
+            #[test]
+            fn keeps_manager_of_moved_employee() {
+                let mut from = Employees::new(Names::default());
+                let mut to = Employees::new(Names::default());
+                from.create("Pat Sajak").unwrap();
+                from.employee_mut("Pat Sajak")
+                    .unwrap()
+                    .set_manager(Some(EmployeeRef::new(
+                        Names::default(),
+                        "Wheel",
+                        "Vanna White",
+                    )));
+
+                to.insert(from.take("pat sajak").unwrap()).unwrap();
+
+                assert_eq!(
+                    Some(&EmployeeRef::new(Names::default(), "wheel", "VANNA WHITE")),
+                    to.employee("Pat Sajak").unwrap().manager()
+                );
+                assert_eq!(Vec::<&str>::new(), from.list());
+            }
+
+            #[test]
+            fn fails_on_duplicate_key() {
+                let mut employees = Employees::new(Names::default());
+                employees.create("John Doe").unwrap();
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "Employee \"John Doe\" already exists".to_string()
+                    )),
+                    employees.insert(Employee::new(Names::default(), "john doe"))
+                );
+            }
+        }
+
+        mod rename {
+            use super::{EmployeeRef, Employees, Names, QueryError};
+
+            #[test]
+            fn rekeys_employee_and_keeps_attributes() {
+                let mut employees = Employees::new(Names::default());
+                employees.create("Jon Doe").unwrap();
+                employees
+                    .employee_mut("Jon Doe")
+                    .unwrap()
+                    .set_manager(Some(EmployeeRef::new(Names::default(), "Hq", "Boss")));
+
+                assert_eq!(
+                    Ok("John Doe".to_string()),
+                    employees.rename("jon doe", "john doe")
+                );
+
+                assert_eq!(vec!["John Doe"], employees.list());
+                assert_eq!(
+                    Some(&EmployeeRef::new(Names::default(), "Hq", "Boss")),
+                    employees.employee("John Doe").unwrap().manager()
+                );
+            }
+
+            #[test]
+            fn fails_on_collision() {
+                let mut employees = Employees::new(Names::default());
+                employees.create("Jon Doe").unwrap();
+                employees.create("John Doe").unwrap();
+
+                assert_eq!(
+                    Err(QueryError::Conflict(
+                        "Employee \"john doe\" already exists".to_string()
+                    )),
+                    employees.rename("Jon Doe", "john doe")
+                );
+            }
+
+            #[test]
+            fn fails_on_missing_employee() {
+                let mut employees = Employees::new(Names::default());
+
+                assert_eq!(
+                    Err(QueryError::NotFound(
+                        "Employee \"Nobody\" does not exist".to_string()
+                    )),
+                    employees.rename("Nobody", "Somebody")
+                );
+            }
+        }
+
         mod delete {
-            use super::{Employees, QueryError};
+            use super::{Employees, Names, QueryError};
 
             #[test]
             fn removes_employee() {
-                let mut employees = Employees::new();
+                let mut employees = Employees::new(Names::default());
 
                 employees.create("Draco Froot").unwrap();
                 employees.create("Gray P Froot").unwrap();
@@ -231,7 +385,7 @@ mod tests {
 
             #[test]
             fn fails_on_missing_employee() {
-                let mut employees = Employees::new();
+                let mut employees = Employees::new(Names::default());
 
                 assert_eq!(
                     QueryError::NotFound(
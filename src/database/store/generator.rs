@@ -0,0 +1,466 @@
+use super::super::date::Date;
+use super::{DepartmentField, EmployeeRef, Store};
+use std::collections::HashMap;
+
+const DEPARTMENT_NAMES: [&str; 24] = [
+    "Accounting",
+    "Design",
+    "Engineering",
+    "Logistics",
+    "Marketing",
+    "Operations",
+    "Sales",
+    "Support",
+    "Legal",
+    "Finance",
+    "Research",
+    "Security",
+    "Facilities",
+    "Procurement",
+    "Recruiting",
+    "Training",
+    "Compliance",
+    "Analytics",
+    "Infrastructure",
+    "Quality",
+    "Communications",
+    "Partnerships",
+    "Payroll",
+    "Product",
+];
+
+const FIRST_NAMES: [&str; 32] = [
+    "Aaliyah",
+    "Alejandro",
+    "Anil",
+    "Ariyan",
+    "Betty",
+    "Caspar",
+    "Catrin",
+    "Corey",
+    "Darin",
+    "Elouise",
+    "Fatema",
+    "Gaia",
+    "Hubert",
+    "Ibrar",
+    "Jordanna",
+    "Kaci",
+    "Kingsley",
+    "Kirk",
+    "Lacey",
+    "Liyah",
+    "Madison",
+    "Mehreen",
+    "Miranda",
+    "Monty",
+    "Olivia",
+    "Riaz",
+    "Samiha",
+    "Shyam",
+    "Stefanie",
+    "Wendy",
+    "Zahraa",
+    "Zara",
+];
+
+const LAST_NAMES: [&str; 32] = [
+    "Allman",
+    "Baldwin",
+    "Barnard",
+    "Bloom",
+    "Bowen",
+    "Burt",
+    "Calderon",
+    "Costa",
+    "Cotton",
+    "Cresswell",
+    "Dupont",
+    "Farley",
+    "Floyd",
+    "Garrison",
+    "Guest",
+    "Hatfield",
+    "Healy",
+    "Heath",
+    "Hull",
+    "James",
+    "Leon",
+    "Markham",
+    "Marriott",
+    "Meadows",
+    "Ortiz",
+    "Peters",
+    "Pollard",
+    "Redman",
+    "Roche",
+    "Russo",
+    "Short",
+    "Wyatt",
+];
+
+const LOCATIONS: [&str; 8] = [
+    "Headquarters",
+    "North Campus",
+    "South Campus",
+    "Downtown",
+    "Harbor Office",
+    "Remote",
+    "Warehouse",
+    "Lab Annex",
+];
+
+/// How generated employees are spread across generated departments
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SizeDistribution {
+    /// Every department gets the same number of employees, give or take one
+    Even,
+    /// Each employee joins a department chosen uniformly at random
+    Uniform,
+    /// Department sizes follow a Zipf distribution, so the first few departments are much
+    /// larger than the rest
+    Zipf,
+}
+
+/// Options for generating a reproducible synthetic organization with `Database::generate`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generator {
+    /// Seed for the random number generator; the same options always produce the same organization
+    pub seed: u64,
+    /// Number of departments to form
+    pub departments: usize,
+    /// Number of employees to assign across the departments
+    pub employees: usize,
+    /// How employees are spread across the departments
+    pub distribution: SizeDistribution,
+    /// Whether to also generate department heads, metadata and reporting lines
+    pub attributes: bool,
+}
+
+impl Generator {
+    /// Creates options for an organization of evenly sized departments without attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::{Generator, SizeDistribution};
+    ///
+    /// let generator = Generator {
+    ///     distribution: SizeDistribution::Zipf,
+    ///     attributes: true,
+    ///     ..Generator::new(42, 10, 1_000)
+    /// };
+    /// ```
+    pub fn new(seed: u64, departments: usize, employees: usize) -> Self {
+        Generator {
+            seed,
+            departments,
+            employees,
+            distribution: SizeDistribution::Even,
+            attributes: false,
+        }
+    }
+}
+
+/// Summary of a synthetic organization merged into a database
+#[derive(Debug, PartialEq)]
+pub struct Generated {
+    /// Number of departments formed
+    pub departments: usize,
+    /// Number of employees assigned
+    pub employees: usize,
+    /// Departments that already existed and were merged into, and employees that already
+    /// existed and were skipped
+    pub conflicts: Vec<String>,
+}
+
+// SplitMix64, which is small, fast and reproducible across platforms
+struct Random {
+    state: u64,
+}
+
+impl Random {
+    fn new(seed: u64) -> Self {
+        Random { state: seed }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+enum Sampler {
+    Even(usize),
+    Uniform(usize),
+    Weighted(Vec<f64>),
+}
+
+impl Sampler {
+    fn new(distribution: SizeDistribution, departments: usize) -> Self {
+        match distribution {
+            SizeDistribution::Even => Sampler::Even(departments),
+            SizeDistribution::Uniform => Sampler::Uniform(departments),
+            SizeDistribution::Zipf => Sampler::Weighted(
+                (1..=departments)
+                    .scan(0.0, |total, rank| {
+                        *total += 1.0 / rank as f64;
+                        Some(*total)
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn sample(&self, index: usize, random: &mut Random) -> usize {
+        match self {
+            Sampler::Even(departments) => index % departments,
+            Sampler::Uniform(departments) => random.below(*departments),
+            Sampler::Weighted(cumulative) => {
+                let target = random.unit() * cumulative[cumulative.len() - 1];
+                cumulative
+                    .binary_search_by(|total| total.partial_cmp(&target).unwrap())
+                    .unwrap_or_else(|position| position)
+                    .min(cumulative.len() - 1)
+            }
+        }
+    }
+}
+
+pub fn generate(store: &mut Store, generator: &Generator) -> Generated {
+    let mut random = Random::new(generator.seed);
+    let mut generated = Generated {
+        departments: 0,
+        employees: 0,
+        conflicts: Vec::new(),
+    };
+    let departments = (0..generator.departments)
+        .map(department_name)
+        .collect::<Vec<String>>();
+    let formed = departments
+        .iter()
+        .map(
+            |department_name| match store.departments_mut().create(department_name) {
+                Ok(_) => {
+                    generated.departments += 1;
+                    true
+                }
+                Err(_) => {
+                    generated.conflicts.push(format!(
+                        "Department \"{}\" already exists; merged generated employees into it",
+                        department_name
+                    ));
+                    false
+                }
+            },
+        )
+        .collect::<Vec<bool>>();
+    if departments.is_empty() {
+        return generated;
+    }
+    let names = store.names();
+    let sampler = Sampler::new(generator.distribution, departments.len());
+    let mut name_counts = vec![HashMap::<String, usize>::new(); departments.len()];
+    let mut members = vec![Vec::<String>::new(); departments.len()];
+    (0..generator.employees).for_each(|index| {
+        let department = sampler.sample(index, &mut random);
+        let base_name = format!(
+            "{} {}",
+            FIRST_NAMES[random.below(FIRST_NAMES.len())],
+            LAST_NAMES[random.below(LAST_NAMES.len())]
+        );
+        let count = name_counts[department]
+            .entry(base_name.clone())
+            .or_insert(0);
+        *count += 1;
+        let employee_name = match *count {
+            1 => base_name,
+            count => format!("{} {}", base_name, count),
+        };
+        // Draw the manager before assigning, so that skipped employees don't change the
+        // random sequence, and with it every employee generated after them
+        let manager = match members[department].len() {
+            0 => None,
+            count if generator.attributes => Some(random.below(count)),
+            _ => None,
+        };
+        match store.assign(&employee_name, &departments[department]) {
+            Err(_) => generated.conflicts.push(format!(
+                "Employee \"{}\" already exists in department \"{}\"; skipped",
+                employee_name, departments[department]
+            )),
+            Ok(employee_name) => {
+                generated.employees += 1;
+                if let Some(manager) = manager {
+                    store
+                        .employee_mut(&EmployeeRef::new(
+                            names,
+                            &departments[department],
+                            &employee_name,
+                        ))
+                        .unwrap()
+                        .set_manager(Some(EmployeeRef::new(
+                            names,
+                            &departments[department],
+                            &members[department][manager],
+                        )));
+                }
+            }
+        }
+        if generator.attributes {
+            members[department].push(employee_name);
+        }
+    });
+    if generator.attributes {
+        departments
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| formed[*index])
+            .for_each(|(index, department_name)| {
+                let department = store.department_mut(department_name).unwrap();
+                let fields = [
+                    (DepartmentField::Head, members[index].first().cloned()),
+                    (
+                        DepartmentField::CostCenter,
+                        Some(format!("CC-{:04}", random.below(10_000))),
+                    ),
+                    (
+                        DepartmentField::Location,
+                        Some(LOCATIONS[random.below(LOCATIONS.len())].to_owned()),
+                    ),
+                    (
+                        DepartmentField::Description,
+                        Some(format!("Generated {} team", department.name())),
+                    ),
+                ];
+                fields.iter().for_each(|(field, value)| {
+                    department.set_field(*field, value.as_deref()).unwrap();
+                });
+                department.set_created(
+                    Date::new(
+                        1990 + random.below(36) as i32,
+                        1 + random.below(12) as u32,
+                        1 + random.below(28) as u32,
+                    )
+                    .unwrap(),
+                );
+            });
+    }
+    generated
+}
+
+fn department_name(index: usize) -> String {
+    let base_name = DEPARTMENT_NAMES[index % DEPARTMENT_NAMES.len()];
+    match index / DEPARTMENT_NAMES.len() {
+        0 => base_name.to_owned(),
+        round => format!("{}{}", base_name, round + 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod generate {
+        use super::super::super::super::names::Names;
+        use super::*;
+
+        #[test]
+        fn is_reproducible() {
+            let generator = Generator {
+                distribution: SizeDistribution::Uniform,
+                attributes: true,
+                ..Generator::new(7, 30, 2_000)
+            };
+            let mut first = Store::new(Names::default());
+            let mut second = Store::new(Names::default());
+
+            let first_generated = generate(&mut first, &generator);
+
+            assert_eq!(first_generated, generate(&mut second, &generator));
+            assert_eq!(first, second);
+            assert_eq!(30, first_generated.departments);
+            assert_eq!(2_000, first_generated.employees);
+            assert!(first
+                .departments()
+                .list()
+                .contains(&"Accounting2".to_string()));
+        }
+
+        #[test]
+        fn merges_into_existing_store_reporting_conflicts() {
+            let generator = Generator::new(3, 4, 200);
+            let mut store = Store::new(Names::default());
+            store.seed();
+
+            let first = generate(&mut store, &generator);
+            let second = generate(&mut store, &generator);
+
+            assert_eq!(0, first.departments);
+            assert_eq!(200 + 4, first.employees + first.conflicts.len());
+            assert_eq!(0, second.employees);
+            assert_eq!(204, second.conflicts.len());
+        }
+
+        #[test]
+        fn zipf_favors_first_departments() {
+            let generator = Generator {
+                distribution: SizeDistribution::Zipf,
+                ..Generator::new(11, 10, 5_000)
+            };
+            let mut store = Store::new(Names::default());
+
+            generate(&mut store, &generator);
+
+            let sizes = store
+                .departments()
+                .iter()
+                .map(|department| {
+                    (
+                        department.name().to_owned(),
+                        department.employees().list().len(),
+                    )
+                })
+                .collect::<HashMap<String, usize>>();
+            assert!(sizes["Accounting"] > 3 * sizes["Finance"]);
+            assert_eq!(5_000, sizes.values().sum::<usize>());
+        }
+
+        #[test]
+        fn generates_attributes() {
+            let generator = Generator {
+                attributes: true,
+                ..Generator::new(5, 2, 50)
+            };
+            let mut store = Store::new(Names::default());
+
+            generate(&mut store, &generator);
+
+            let design = store.department("Design").unwrap();
+            let head = design.head().unwrap().to_owned();
+            assert!(design.field(DepartmentField::Location).is_some());
+            assert!(design.field(DepartmentField::Created).is_some());
+            let reports = design
+                .employees()
+                .iter()
+                .filter(|employee| employee.manager().is_some())
+                .count();
+            assert_eq!(design.employees().list().len() - 1, reports);
+            assert_eq!(
+                Vec::<EmployeeRef>::new(),
+                store.chain_of_command(&EmployeeRef::new(Names::default(), "Design", &head))
+            );
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use super::super::date::Date;
+use super::super::errors::QueryError;
+use super::super::names::Names;
+use super::employees::EmployeeRef;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Membership {
+    employee: String,
+    department: String,
+    from: Date,
+    to: Option<Date>,
+    // Memberships of a dissolved department stay with it, when a department of the same name is
+    // formed later and renamed
+    dissolved: bool,
+}
+
+impl Membership {
+    pub fn new(employee: &str, department: &str, from: Date, to: Option<Date>) -> Self {
+        Membership {
+            employee: employee.to_owned(),
+            department: department.to_owned(),
+            from,
+            to,
+            dissolved: false,
+        }
+    }
+
+    pub fn employee(&self) -> &str {
+        &self.employee
+    }
+
+    pub fn department(&self) -> &str {
+        &self.department
+    }
+
+    pub fn from(&self) -> Date {
+        self.from
+    }
+
+    pub fn to(&self) -> Option<Date> {
+        self.to
+    }
+
+    #[cfg(feature = "serde")]
+    pub fn dissolved(&self) -> bool {
+        self.dissolved
+    }
+
+    pub fn dissolve(&mut self) {
+        self.dissolved = true;
+    }
+
+    // Memberships start on their first day and end on the day they are closed, so an employee
+    // transferred on a date is only in the department they were transferred to as of that date
+    pub fn is_active(&self, date: Date) -> bool {
+        self.from <= date && self.to.is_none_or(|to| date < to)
+    }
+}
+
+#[derive(Default)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct History {
+    // Memberships by employee and department key, in the order they were opened, so that
+    // employees of the same name in different departments keep timelines of their own
+    memberships: BTreeMap<(String, String), Vec<Membership>>,
+    names: Names,
+}
+
+impl History {
+    pub fn new(names: Names) -> Self {
+        History {
+            memberships: BTreeMap::new(),
+            names,
+        }
+    }
+
+    fn key(&self, employee_name: &str, department_name: &str) -> (String, String) {
+        (
+            self.names.key(employee_name),
+            self.names.key(department_name),
+        )
+    }
+
+    // A range ending before it starts would never be active, so it is rejected rather than kept
+    #[cfg(feature = "serde")]
+    pub fn insert(&mut self, membership: Membership) -> Result<(), QueryError> {
+        if let Some(to) = membership.to.filter(|to| *to < membership.from) {
+            return Err(QueryError::Conflict(format!(
+                "Membership of employee \"{}\" in department \"{}\" cannot end on {}, before it starts on {}",
+                membership.employee, membership.department, to, membership.from
+            )));
+        }
+        self.memberships
+            .entry(self.key(&membership.employee, &membership.department))
+            .or_default()
+            .push(membership);
+        Ok(())
+    }
+
+    pub fn open(&mut self, employee_name: &str, department_name: &str, date: Date) {
+        self.memberships
+            .entry(self.key(employee_name, department_name))
+            .or_default()
+            .push(Membership::new(employee_name, department_name, date, None));
+    }
+
+    // An employee is in a department at most once at a time, so their open membership there is
+    // the last one opened
+    fn open_membership(&mut self, employee: &EmployeeRef) -> Option<&mut Membership> {
+        self.memberships
+            .get_mut(&ref_key(employee))
+            .and_then(|memberships| memberships.last_mut())
+            .filter(|membership| membership.to.is_none())
+    }
+
+    pub fn check_close(&self, employee: &EmployeeRef, date: Date) -> Result<(), QueryError> {
+        let open = self
+            .memberships
+            .get(&ref_key(employee))
+            .and_then(|memberships| memberships.last())
+            .filter(|membership| membership.to.is_none());
+        match open {
+            Some(open) if date < open.from => Err(QueryError::Conflict(format!(
+                "Employee \"{}\" cannot leave department \"{}\" on {}, before joining it on {}",
+                open.employee, open.department, date, open.from
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn close(&mut self, employee: &EmployeeRef, date: Date) {
+        if let Some(open) = self.open_membership(employee) {
+            open.to = Some(date);
+        }
+    }
+
+    pub fn dissolve_department(&mut self, department_name: &str) {
+        let key = self.names.key(department_name);
+        self.memberships
+            .iter_mut()
+            .filter(|((_, department), _)| *department == key)
+            .flat_map(|(_, memberships)| memberships.iter_mut())
+            .for_each(Membership::dissolve);
+    }
+
+    // Moves the memberships of the department as it exists now to new keys, leaving those of
+    // any dissolved department of the same name where they are
+    fn rekey<F: Fn(&mut Membership)>(
+        &mut self,
+        key: (String, String),
+        new_key: (String, String),
+        rename: F,
+    ) {
+        let (dissolved, mut current) = match self.memberships.remove(&key) {
+            None => return,
+            Some(memberships) => memberships
+                .into_iter()
+                .partition::<Vec<Membership>, _>(|membership| membership.dissolved),
+        };
+        if !dissolved.is_empty() {
+            self.memberships.entry(key).or_default().extend(dissolved);
+        }
+        current.iter_mut().for_each(rename);
+        self.memberships.entry(new_key).or_default().extend(current);
+    }
+
+    pub fn rename_department(&mut self, department_name: &str, new_name: &str) {
+        let key = self.names.key(department_name);
+        let new_key = self.names.key(new_name);
+        let renamed = self
+            .memberships
+            .keys()
+            .filter(|(_, department)| *department == key)
+            .map(|(employee, _)| employee.to_owned())
+            .collect::<Vec<String>>();
+        renamed.into_iter().for_each(|employee| {
+            self.rekey(
+                (employee.clone(), key.clone()),
+                (employee, new_key.clone()),
+                |membership| membership.department = new_name.to_owned(),
+            )
+        });
+    }
+
+    // Only the memberships in the renamed employee's department follow them to the new name
+    pub fn rename_employee(&mut self, employee: &EmployeeRef, new_name: &str) {
+        self.rekey(
+            ref_key(employee),
+            (self.names.key(new_name), employee.department().to_owned()),
+            |membership| membership.employee = new_name.to_owned(),
+        );
+    }
+
+    // Every membership of employees of the name, or only those in one department
+    pub fn timeline(&self, employee_name: &str, department_name: Option<&str>) -> Vec<&Membership> {
+        let key = self.names.key(employee_name);
+        let department_key = department_name.map(|department_name| self.names.key(department_name));
+        let mut timeline = self
+            .memberships
+            .range((key.clone(), String::new())..)
+            .take_while(|((employee, _), _)| *employee == key)
+            .filter(|((_, department), _)| {
+                department_key
+                    .as_ref()
+                    .is_none_or(|department_key| department == department_key)
+            })
+            .flat_map(|(_, memberships)| memberships.iter())
+            .collect::<Vec<&Membership>>();
+        timeline.sort_by_key(|membership| membership.from);
+        timeline
+    }
+
+    // Memberships active on a date, ordered by employee
+    pub fn as_of(&self, date: Date) -> impl Iterator<Item = &Membership> {
+        self.iter()
+            .filter(move |membership| membership.is_active(date))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Membership> {
+        self.memberships.values().flatten()
+    }
+}
+
+fn ref_key(employee: &EmployeeRef) -> (String, String) {
+    (
+        employee.employee().to_owned(),
+        employee.department().to_owned(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> Date {
+        Date::new(2026, 3, day).unwrap()
+    }
+
+    mod membership {
+        use super::*;
+
+        #[test]
+        fn is_active_from_its_first_day_until_it_is_closed() {
+            let membership = Membership::new("Bob", "Sales", date(2), Some(date(4)));
+
+            assert!(!membership.is_active(date(1)));
+            assert!(membership.is_active(date(2)));
+            assert!(membership.is_active(date(3)));
+            assert!(!membership.is_active(date(4)));
+        }
+    }
+
+    mod history {
+        use super::*;
+
+        fn transferred() -> History {
+            let mut history = History::new(Names::default());
+            history.open("Bob", "Sales", date(1));
+            history.open("Alice", "Sales", date(1));
+            history.close(&EmployeeRef::new(Names::default(), "sales", "bob"), date(5));
+            history.open("Bob", "Design", date(5));
+            history
+        }
+
+        #[test]
+        fn close_ends_the_open_membership_in_the_department() {
+            let history = transferred();
+
+            assert_eq!(
+                vec![
+                    &Membership::new("Bob", "Sales", date(1), Some(date(5))),
+                    &Membership::new("Bob", "Design", date(5), None)
+                ],
+                history.timeline("BOB", None)
+            );
+        }
+
+        #[test]
+        fn as_of_lists_active_memberships_by_employee() {
+            let history = transferred();
+            let on = |day| {
+                history
+                    .as_of(date(day))
+                    .map(|membership| (membership.employee(), membership.department()))
+                    .collect::<Vec<(&str, &str)>>()
+            };
+
+            assert_eq!(vec![("Alice", "Sales"), ("Bob", "Sales")], on(4));
+            assert_eq!(vec![("Alice", "Sales"), ("Bob", "Design")], on(5));
+        }
+
+        #[test]
+        fn renames_only_the_department_that_exists() {
+            let mut history = transferred();
+            history.close(
+                &EmployeeRef::new(Names::default(), "sales", "alice"),
+                date(6),
+            );
+            history.dissolve_department("Sales");
+            history.open("Alice", "Sales", date(7));
+            history.rename_department("sales", "Revenue");
+
+            assert_eq!(
+                vec![("Sales", date(1)), ("Revenue", date(7))],
+                history
+                    .timeline("Alice", None)
+                    .iter()
+                    .map(|membership| (membership.department(), membership.from()))
+                    .collect::<Vec<(&str, Date)>>()
+            );
+        }
+
+        #[test]
+        fn keeps_a_timeline_for_each_department() {
+            let mut history = transferred();
+            history.open("Alice", "Design", date(6));
+
+            assert_eq!(
+                vec![&Membership::new("Alice", "Design", date(6), None)],
+                history.timeline("alice", Some("DESIGN"))
+            );
+            assert_eq!(
+                vec![&Membership::new("Alice", "Sales", date(1), None)],
+                history.timeline("alice", Some("sales"))
+            );
+            assert_eq!(2, history.timeline("alice", None).len());
+        }
+
+        #[test]
+        fn rejects_closing_before_opening() {
+            let history = transferred();
+
+            assert_eq!(
+                Err(QueryError::Conflict(
+                    "Employee \"Bob\" cannot leave department \"Design\" on 2026-03-04, before joining it on 2026-03-05".to_string()
+                )),
+                history.check_close(&EmployeeRef::new(Names::default(), "design", "bob"), date(4))
+            );
+            assert_eq!(
+                Ok(()),
+                history.check_close(
+                    &EmployeeRef::new(Names::default(), "design", "bob"),
+                    date(5)
+                )
+            );
+        }
+
+        #[test]
+        fn renames_follow_the_membership() {
+            let mut history = transferred();
+            history.rename_department("SALES", "Revenue");
+            history.rename_employee(&EmployeeRef::new(Names::default(), "design", "bob"), "Rob");
+
+            assert_eq!(
+                vec![&Membership::new("Bob", "Revenue", date(1), Some(date(5)))],
+                history.timeline("Bob", None)
+            );
+            assert_eq!(
+                vec![&Membership::new("Rob", "Design", date(5), None)],
+                history.timeline("Rob", None)
+            );
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use super::date::Date;
+use std::fmt;
+
+/// The type of every cell in a column of tabular data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ColumnType {
+    /// Free text, such as names
+    Text,
+    /// Whole numbers, such as headcounts
+    Integer,
+    /// Fractional numbers, such as salaries
+    Decimal,
+    /// Calendar dates
+    Date,
+    /// Yes / no flags
+    Boolean,
+    /// Columns that never hold a value
+    Null,
+}
+
+impl ColumnType {
+    /// Whether values of this type are numbers, which renderers usually right-align.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use employees::database::ColumnType;
+    ///
+    /// assert!(ColumnType::Decimal.is_numeric());
+    /// assert!(!ColumnType::Date.is_numeric());
+    /// ```
+    pub fn is_numeric(self) -> bool {
+        matches!(self, ColumnType::Integer | ColumnType::Decimal)
+    }
+}
+
+/// A single typed cell of tabular data.
+///
+/// Text is held as `S`, which is an owned `String` in a `Table`, and a `&str` borrowed from the
+/// rows when reading a `Row`. Text cells compare equal to string slices, so
+/// `assert_eq!("Bob", table.data[0]["Employee"])` reads naturally.
+///
+/// # Examples
+///
+/// ```rust
+/// use employees::database::{ColumnType, Value};
+///
+/// let headcount: Value = Value::Integer(12);
+///
+/// assert_eq!(ColumnType::Integer, headcount.kind());
+/// assert_eq!(Some(12), headcount.as_i64());
+/// assert_eq!("12", headcount.to_string());
+/// assert_eq!("Sales", Value::Text("Sales".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "snake_case")
+)]
+pub enum Value<S = String> {
+    /// No value, such as a department without a head
+    Null,
+    /// Free text
+    Text(S),
+    /// A whole number
+    Integer(i64),
+    /// A fractional number
+    Decimal(f64),
+    /// A calendar date
+    Date(Date),
+    /// A yes / no flag
+    Boolean(bool),
+}
+
+impl<S: AsRef<str>> Value<S> {
+    /// The column type this value belongs in.
+    pub fn kind(&self) -> ColumnType {
+        match self {
+            Value::Null => ColumnType::Null,
+            Value::Text(_) => ColumnType::Text,
+            Value::Integer(_) => ColumnType::Integer,
+            Value::Decimal(_) => ColumnType::Decimal,
+            Value::Date(_) => ColumnType::Date,
+            Value::Boolean(_) => ColumnType::Boolean,
+        }
+    }
+
+    /// Whether this is `Value::Null`.
+    pub fn is_null(&self) -> bool {
+        matches!(self, Value::Null)
+    }
+
+    /// The text of a text value, or `None` for any other value.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Text(text) => Some(text.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// The number of an integer value, or `None` for any other value.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Integer(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// The number of an integer or decimal value, or `None` for any other value.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Integer(number) => Some(*number as f64),
+            Value::Decimal(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// The date of a date value, or `None` for any other value.
+    pub fn as_date(&self) -> Option<Date> {
+        match self {
+            Value::Date(date) => Some(*date),
+            _ => None,
+        }
+    }
+
+    /// The flag of a boolean value, or `None` for any other value.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(flag) => Some(*flag),
+            _ => None,
+        }
+    }
+
+    /// Borrows the text of this value, for values that are stored elsewhere.
+    pub fn as_borrowed(&self) -> Value<&str> {
+        match self {
+            Value::Null => Value::Null,
+            Value::Text(text) => Value::Text(text.as_ref()),
+            Value::Integer(number) => Value::Integer(*number),
+            Value::Decimal(number) => Value::Decimal(*number),
+            Value::Date(date) => Value::Date(*date),
+            Value::Boolean(flag) => Value::Boolean(*flag),
+        }
+    }
+
+    /// Copies the text of this value, so that it no longer borrows from anything.
+    pub fn into_owned(self) -> Value {
+        match self.as_borrowed() {
+            Value::Null => Value::Null,
+            Value::Text(text) => Value::Text(text.to_owned()),
+            Value::Integer(number) => Value::Integer(number),
+            Value::Decimal(number) => Value::Decimal(number),
+            Value::Date(date) => Value::Date(date),
+            Value::Boolean(flag) => Value::Boolean(flag),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Value<&'a str> {
+    fn from(text: &'a str) -> Self {
+        Value::Text(text)
+    }
+}
+
+impl<S> From<i64> for Value<S> {
+    fn from(number: i64) -> Self {
+        Value::Integer(number)
+    }
+}
+
+impl<S> From<f64> for Value<S> {
+    fn from(number: f64) -> Self {
+        Value::Decimal(number)
+    }
+}
+
+impl<S> From<Date> for Value<S> {
+    fn from(date: Date) -> Self {
+        Value::Date(date)
+    }
+}
+
+impl<S> From<bool> for Value<S> {
+    fn from(flag: bool) -> Self {
+        Value::Boolean(flag)
+    }
+}
+
+// Nulls display as nothing, so that renderers can choose how to mark them
+impl<S: AsRef<str>> fmt::Display for Value<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => Ok(()),
+            Value::Text(text) => formatter.pad(text.as_ref()),
+            Value::Integer(number) => fmt::Display::fmt(number, formatter),
+            Value::Decimal(number) => fmt::Display::fmt(number, formatter),
+            Value::Date(date) => formatter.pad(&date.to_string()),
+            Value::Boolean(flag) => fmt::Display::fmt(flag, formatter),
+        }
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<str> for Value<S> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<&str> for Value<S> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<Value<S>> for str {
+    fn eq(&self, other: &Value<S>) -> bool {
+        other == self
+    }
+}
+
+impl<S: AsRef<str>> PartialEq<Value<S>> for &str {
+    fn eq(&self, other: &Value<S>) -> bool {
+        other == self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod value {
+        use super::*;
+
+        #[test]
+        fn compares_text_with_strings() {
+            let name: Value = Value::Text(String::from("Bob"));
+
+            assert_eq!("Bob", name);
+            assert_eq!(name, "Bob");
+            assert_eq!("Bob", &name);
+            assert_ne!("12", Value::<String>::Integer(12));
+            assert_ne!("", Value::<String>::Null);
+        }
+
+        #[test]
+        fn displays_values() {
+            assert_eq!("", Value::<&str>::Null.to_string());
+            assert_eq!("1.5", Value::<&str>::Decimal(1.5).to_string());
+            assert_eq!(
+                "1947-12-01",
+                Value::<&str>::Date(Date::new(1947, 12, 1).unwrap()).to_string()
+            );
+            assert_eq!("  42", format!("{:>4}", Value::<&str>::Integer(42)));
+            assert_eq!("ab  ", format!("{:<4}", Value::Text("ab")));
+        }
+
+        #[test]
+        fn converts_between_borrowed_and_owned() {
+            let owned: Value = Value::Text(String::from("Sales"));
+
+            assert_eq!(Value::Text("Sales"), owned.as_borrowed());
+            assert_eq!(owned, Value::Text("Sales").into_owned());
+            assert_eq!(ColumnType::Text, owned.kind());
+        }
+    }
+}
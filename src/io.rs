@@ -1,6 +1,6 @@
 //! Utilities for formatting and piping queries through stdin/stdout
-use crate::database::Table;
-use std::io::{stdin, stdout, Write};
+use crate::database::{Report, ResponsePart, Rows, Table, Value};
+use std::io::{stdin, stdout, BufWriter, Result, Write};
 
 /// Retrieves a query from stdin
 pub fn get_query() -> String {
@@ -22,61 +22,183 @@ pub fn print_message(message: String) {
 
 /// Formats query output data to stdout
 pub fn print_table(table: Table) {
-    println!("\n{}\n", table.title);
+    print_rows(Rows::from(table));
+}
+
+/// Formats query output rows to stdout, writing each row as soon as it is formatted
+pub fn print_rows(rows: Rows) {
+    let stdout = stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    write_rows(&mut out, &rows)
+        .and_then(|_| out.flush())
+        .expect("Error writing to stdout");
+}
+
+/// Formats the sections of a report to stdout
+pub fn print_report(report: Report) {
+    let stdout = stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    write_report(&mut out, report)
+        .and_then(|_| out.flush())
+        .expect("Error writing to stdout");
+}
+
+/// Formats the sections of a report to any writer, one table after another under the report title
+///
+/// # Examples
+///
+/// ```rust
+/// use employees::database::Database;
+/// use employees::io::write_report;
+///
+/// let report = Database::new().headcount_report(None, None);
+/// let mut out = Vec::new();
+/// write_report(&mut out, report).unwrap();
+/// let out = String::from_utf8(out).unwrap();
+///
+/// assert!(out.starts_with("\n=== Headcount Report ===\n"));
+/// assert!(out.contains("\nEmpty Departments\n"));
+/// ```
+pub fn write_report<W: Write>(out: &mut W, report: Report) -> Result<()> {
+    writeln!(out, "\n=== {} ===", report.title)?;
+    report
+        .sections
+        .into_iter()
+        .try_for_each(|section| write_rows(out, &Rows::from(section)))
+}
+
+/// Formats several query results to stdout, in order
+pub fn print_composite(parts: Vec<ResponsePart>) {
+    let stdout = stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    write_composite(&mut out, parts)
+        .and_then(|_| out.flush())
+        .expect("Error writing to stdout");
+}
+
+/// Formats several query results to any writer, in order
+///
+/// Messages are written as they are, warnings are marked with `WARNING:`, and tables are
+/// written like `write_rows` writes them.
+///
+/// # Examples
+///
+/// ```rust
+/// use employees::database::{ResponsePart, Rows};
+/// use employees::io::write_composite;
+///
+/// let mut rows = Rows::new("Showing Users".to_string(), vec!["User".to_string()]);
+/// rows.push(&["Root"]);
+/// let mut out = Vec::new();
+/// write_composite(
+///     &mut out,
+///     vec![
+///         ResponsePart::Rows(rows),
+///         ResponsePart::Warning("Ellen has no roles".to_string()),
+///     ],
+/// )
+/// .unwrap();
+///
+/// assert!(String::from_utf8(out)
+///     .unwrap()
+///     .ends_with(" Root \n\nWARNING: Ellen has no roles\n"));
+/// ```
+pub fn write_composite<W: Write>(out: &mut W, parts: Vec<ResponsePart>) -> Result<()> {
+    parts.into_iter().try_for_each(|part| match part {
+        ResponsePart::Message(message) => writeln!(out, "{}", message),
+        ResponsePart::Warning(warning) => writeln!(out, "WARNING: {}", warning),
+        ResponsePart::Table(table) => write_rows(out, &Rows::from(table)),
+        ResponsePart::Rows(rows) => write_rows(out, &rows),
+    })
+}
+
+/// Formats query output rows to any writer, one row at a time
+///
+/// Numeric columns are right-aligned, and null cells are shown as `NULL`.
+///
+/// # Examples
+///
+/// ```rust
+/// use employees::database::{ColumnType, Rows, Value};
+/// use employees::io::write_rows;
+///
+/// let mut rows = Rows::with_types(
+///     "Showing Departments".to_string(),
+///     vec!["Department".to_string(), "Head".to_string(), "Headcount".to_string()],
+///     vec![ColumnType::Text, ColumnType::Text, ColumnType::Integer],
+/// );
+/// rows.push_values(&[Value::Text("Sales"), Value::Null, Value::Integer(12)]);
+/// let mut out = Vec::new();
+/// write_rows(&mut out, &rows).unwrap();
+///
+/// assert_eq!(
+///     "\nShowing Departments\n\n \
+///      Department | Head | Headcount \n\
+///      ------------|------|-----------\n \
+///      Sales      | NULL |        12 \n\n",
+///     String::from_utf8(out).unwrap()
+/// );
+/// ```
+pub fn write_rows<W: Write>(out: &mut W, rows: &Rows) -> Result<()> {
+    writeln!(out, "\n{}\n", rows.title())?;
 
     // Calculate width of columns based on contents
-    let mut column_widths = table
-        .headers
+    let mut column_widths = rows
+        .headers()
         .iter()
         .map(|header| header.chars().count())
         .collect::<Vec<usize>>();
-    table.data.iter().for_each(|data_map| {
-        table
-            .headers
-            .iter()
-            .enumerate()
-            .for_each(|(header_index, header_name)| {
-                if let Some(data_name) = data_map.get(header_name) {
-                    let data_width = data_name.len();
-                    if data_width > column_widths[header_index] {
-                        column_widths[header_index] = data_width;
-                    }
-                }
-            });
+    rows.iter().for_each(|row| {
+        row.cells().enumerate().for_each(|(index, cell)| {
+            column_widths[index] = column_widths[index].max(cell_width(&cell));
+        });
     });
     let column_widths = column_widths;
 
-    table.headers.iter().enumerate().for_each(|(index, name)| {
+    for (index, name) in rows.headers().iter().enumerate() {
         if index > 0 {
-            print!("|");
+            write!(out, "|")?;
         }
-        print!(" {name:width$} ", name = name, width = column_widths[index]);
-    });
-    println!();
-    column_widths.iter().enumerate().for_each(|(index, width)| {
+        if rows.types()[index].is_numeric() {
+            write!(out, " {:>width$} ", name, width = column_widths[index])?;
+        } else {
+            write!(out, " {:<width$} ", name, width = column_widths[index])?;
+        }
+    }
+    writeln!(out)?;
+    for (index, width) in column_widths.iter().enumerate() {
         if index > 0 {
-            print!("|");
+            write!(out, "|")?;
         }
-        print!("-{:-<width$}-", "-", width = width);
-    });
-    println!();
+        write!(out, "-{:-<width$}-", "-", width = width)?;
+    }
+    writeln!(out)?;
 
-    table.data.iter().for_each(|row| {
-        table
-            .headers
-            .iter()
-            .enumerate()
-            .for_each(|(index, column_name)| {
-                if index > 0 {
-                    print!("|");
+    for row in rows.iter() {
+        for (index, cell) in row.cells().enumerate() {
+            if index > 0 {
+                write!(out, "|")?;
+            }
+            let width = column_widths[index];
+            match cell {
+                Value::Null => write!(out, " {:<width$} ", NULL, width = width)?,
+                cell if rows.types()[index].is_numeric() => {
+                    write!(out, " {:>width$} ", cell, width = width)?
                 }
-                let data = match row.get(column_name) {
-                    None => String::from(" "),
-                    Some(value) => String::from(value),
-                };
-                print!(" {:<width$} ", data, width = column_widths[index]);
-            });
-        println!();
-    });
-    println!();
+                cell => write!(out, " {:<width$} ", cell, width = width)?,
+            }
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)
+}
+
+const NULL: &str = "NULL";
+
+fn cell_width(cell: &Value<&str>) -> usize {
+    match cell {
+        Value::Null => NULL.len(),
+        Value::Text(text) => text.chars().count(),
+        cell => cell.to_string().len(),
+    }
 }
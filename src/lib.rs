@@ -1,6 +1,10 @@
 //! # employees
 //!
 //! `employees` is an in-memory data store for departmental employee data with a SQL-like query language.
+//!
+//! With the optional `serde` feature, `Database` serializes to a snapshot of its departments,
+//! employees and users, and query results serialize with stable, snake_case field names.
+//! The `export` feature, on by default, builds on it to export databases as JSON, CSV or YAML.
 
 #![deny(missing_docs)]
 #![warn(missing_doc_code_examples)]
@@ -1,4 +1,4 @@
-use database::QueryResponse::{Exit, Message, NoOp, Table};
+use database::QueryResponse::{Composite, Exit, Message, NoOp, Report, Rows, Table};
 use employees::database;
 use employees::io;
 
@@ -12,11 +12,14 @@ fn main() {
         io::print_message(String::from(
             "Enter query (Type \"Help\" for list of commands):",
         ));
-        match db.query(io::get_query()) {
+        match db.query_rows(io::get_query()) {
             NoOp => continue,
             Exit => break,
             Message(message) => io::print_message(message),
             Table(table) => io::print_table(table),
+            Rows(rows) => io::print_rows(rows),
+            Report(report) => io::print_report(report),
+            Composite(parts) => io::print_composite(parts),
         }
     }
     io::print_message(String::from("\nThank you for using Departmental Employee Tracking System (TM) for you labor tracking needs!\n"));
@@ -1,10 +1,21 @@
-use employees::database::{Database, QueryResponse};
+use employees::database::{
+    Chart, ChartFormat, Collation, ColumnType, Database, Date, Generator, NameCase, QueryResponse,
+    ResponsePart, Rows, SizeDistribution, Table, Value,
+};
 
 #[test]
 fn user_can_exit() {
     let mut db = Database::new();
 
     assert_eq!(QueryResponse::Exit, db.query("exit".to_string()));
+    assert_eq!(
+        QueryResponse::Exit,
+        db.query("form sales; exit; form marketing".to_string())
+    );
+    match db.query("show departments".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(1, table.data.len()),
+        _ => panic!(),
+    }
 }
 
 #[test]
@@ -21,13 +32,49 @@ fn user_can_see_help() {
                 "DISSOLVE {DEPARTMENT}",
                 "EXIT",
                 "FORM {DEPARTMENT}",
+                "FORM {DEPARTMENT} UNDER {DEPARTMENT}",
+                "GRANT {ROLE} TO {USER}",
+                "GRANT {ROLE} TO {USER} ON {DEPARTMENT}",
                 "HELP",
                 "LIST EMPLOYEES",
                 "LIST EMPLOYEES BY DEPARTMENT",
                 "LIST EMPLOYEES IN {DEPARTMENT}",
+                "LIST EMPLOYEES UNDER {DEPARTMENT}",
+                "SHOW TIMELINE OF {EMPLOYEE}",
+                "MOVE {DEPARTMENT} UNDER {DEPARTMENT}",
+                "MOVE {DEPARTMENT} TO TOP",
                 "PULL {EMPLOYEE} FROM {DEPARTMENT}",
+                "SET MANAGER OF {EMPLOYEE} TO {EMPLOYEE}",
+                "CLEAR MANAGER OF {EMPLOYEE}",
+                "LIST REPORTS OF {EMPLOYEE}",
+                "LIST ALL REPORTS OF {EMPLOYEE}",
+                "SHOW CHAIN OF COMMAND FOR {EMPLOYEE}",
+                "REVOKE {ROLE} FROM {USER}",
+                "REVOKE {ROLE} FROM {USER} ON {DEPARTMENT}",
+                "RENAME DEPARTMENT {DEPARTMENT} TO {DEPARTMENT}",
+                "RENAME EMPLOYEE {EMPLOYEE} IN {DEPARTMENT} TO {EMPLOYEE}",
                 "SHOW DEPARTMENTS",
+                "SHOW DEPARTMENT TREE",
+                "SHOW DEPARTMENTS DETAILED",
+                "SET {FIELD} OF {DEPARTMENT} TO {VALUE}",
+                "CLEAR {FIELD} OF {DEPARTMENT}",
+                "SHOW USERS",
                 "TRANSFER {EMPLOYEE} FROM {DEPARTMENT} TO {DEPARTMENT}",
+                "TRANSFER ALL FROM {DEPARTMENT} TO {DEPARTMENT}",
+                "TRANSFER EMPLOYEES WHERE {CONDITION} TO {DEPARTMENT}",
+                "PULL ALL FROM {DEPARTMENT}",
+                "FIND {EMPLOYEE}",
+                "WHERE IS {EMPLOYEE}",
+                "SEARCH {TEXT}",
+                "MERGE DEPARTMENT {DEPARTMENT} INTO {DEPARTMENT}",
+                "EXPORT {FORMAT} TO {FILE}",
+                "EXPORT {FORMAT} OF {DEPARTMENT} TO {FILE}",
+                "DIFF {FILE}",
+                "CHART {FORMAT}",
+                "REPORT HEADCOUNT",
+                "RUN {FILE}",
+                "DEFINE ALIAS {NAME} AS {QUERY}",
+                "UNDEFINE {NAME}",
             ]
             .iter()
             .map(|command| {
@@ -63,6 +110,61 @@ fn user_can_see_help() {
     };
 }
 
+#[test]
+fn user_can_see_the_same_usages_in_help_for_each_command() {
+    let mut db = Database::new();
+
+    let overview = match db.query("help".to_string()) {
+        QueryResponse::Message(message) => message,
+        _ => panic!(),
+    };
+    let usages = overview
+        .lines()
+        .filter(|line| line.starts_with("- \""))
+        .collect::<Vec<&str>>();
+    assert!(usages.len() > 50);
+    // Each command is described again, with the same syntax, by "Help {command}"
+    usages.iter().for_each(|usage| {
+        let keyword = usage[3..]
+            .split(|c: char| !c.is_alphabetic())
+            .next()
+            .unwrap();
+        match db.query(format!("help {}", keyword)) {
+            QueryResponse::Message(help) => {
+                assert!(help.lines().any(|line| line == *usage), "{}", usage);
+                assert!(help.contains("\nExamples:\n"), "{}", keyword);
+            }
+            _ => panic!(),
+        }
+    });
+}
+
+#[test]
+fn user_can_see_help_for_a_command() {
+    let mut db = Database::new();
+
+    match db.query("help transfer".to_string()) {
+        QueryResponse::Message(help) => {
+            assert!(help.starts_with(
+                "\n\"Transfer\" Operations:\
+                \n- \"Transfer {employee} from {department} to {department}\" - move employee from first department to second\n"
+            ));
+            assert!(help.contains("\nClauses:\n"));
+            assert!(help.contains("\n- \"on conflict {policy}\" - "));
+            assert!(help.contains("\n- \"Transfer all from Sales to Design on conflict rename\"\n"));
+            assert!(help.ends_with("\nSee Also: \"Help Assign\", \"Help Pull\", \"Help Merge\"\n"));
+        }
+        _ => panic!(),
+    }
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Invalid command syntax: \"frobnicate\" is not a command or alias: type \"Help\" for the list of available commands"
+                .to_string()
+        ),
+        db.query("help frobnicate".to_string())
+    );
+}
+
 #[test]
 fn user_can_form_departments() {
     let mut db = Database::new();
@@ -108,7 +210,7 @@ fn user_can_show_departments_alphabetically() {
             let departments = table
                 .data
                 .iter()
-                .map(|row| row.get(&header_name).unwrap().to_owned())
+                .map(|row| row.get(&header_name).unwrap().as_str().unwrap().to_owned())
                 .collect::<Vec<String>>();
             assert_eq!(
                 vec![
@@ -208,8 +310,8 @@ fn user_can_list_all_employees_alphabetically() {
                 table
                     .data
                     .iter()
-                    .map(|row| row.get("Employee").unwrap())
-                    .collect::<Vec<&String>>()
+                    .map(|row| row.get("Employee").unwrap().as_str().unwrap())
+                    .collect::<Vec<&str>>()
             );
         }
         _ => panic!(),
@@ -243,8 +345,8 @@ fn user_can_list_all_employees_alphabetically_grouped_by_department_alphabetical
                     .data
                     .iter()
                     .map(|row| (
-                        row.get("Department").unwrap().to_owned(),
-                        row.get("Employee").unwrap().to_owned()
+                        row.get("Department").unwrap().as_str().unwrap().to_owned(),
+                        row.get("Employee").unwrap().as_str().unwrap().to_owned()
                     ))
                     .collect::<Vec<(String, String)>>()
             );
@@ -274,8 +376,8 @@ fn user_can_list_all_employees_alphabetically_in_a_department() {
                 table
                     .data
                     .iter()
-                    .map(|row| row.get("Employee").unwrap())
-                    .collect::<Vec<&String>>()
+                    .map(|row| row.get("Employee").unwrap().as_str().unwrap())
+                    .collect::<Vec<&str>>()
             );
         }
         _ => panic!(),
@@ -365,3 +467,1422 @@ fn user_can_pull_employees_from_departments() {
         _ => panic!(),
     }
 }
+
+#[test]
+fn user_can_restrict_queries_by_role() {
+    let mut db = Database::new();
+
+    db.query("form engineering".to_string());
+    db.query("form sales".to_string());
+
+    match db.query("grant admin to root".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!("Granted role \"Admin\" to user \"root\"", message);
+        }
+        _ => panic!(),
+    }
+
+    match db.query("show departments".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!("ERROR: Permission denied: No user is logged in", message);
+        }
+        _ => panic!(),
+    }
+
+    db.login("root");
+    db.query("grant viewer to intern".to_string());
+    db.query("grant editor to lead on sales".to_string());
+
+    match db.query("show users".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![
+                    ("intern", "Viewer", "All"),
+                    ("lead", "Editor", "Sales"),
+                    ("root", "Admin", "All"),
+                ],
+                table
+                    .data
+                    .iter()
+                    .map(|row| (
+                        row.get("User").unwrap().as_str().unwrap(),
+                        row.get("Role").unwrap().as_str().unwrap(),
+                        row.get("Department").unwrap().as_str().unwrap()
+                    ))
+                    .collect::<Vec<(&str, &str, &str)>>()
+            );
+        }
+        _ => panic!(),
+    }
+
+    db.login("intern");
+    match db.query("show departments".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(2, table.data.len()),
+        _ => panic!(),
+    }
+    match db.query("dissolve engineering".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Permission denied: User \"intern\" requires the Admin role on department(s) \"engineering\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+
+    db.login("lead");
+    match db.query("assign margaret to sales".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Assigned employee \"Margaret\" to Sales department",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("transfer margaret from sales to engineering".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Permission denied: User \"lead\" requires the Editor role on department(s) \"sales\", \"engineering\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_nest_departments() {
+    let mut db = Database::new();
+
+    db.query("form operations".to_string());
+    match db.query("form logistics under operations".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Formed \"Logistics\" department under \"Operations\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    db.query("form shipping".to_string());
+    db.query("move shipping under logistics".to_string());
+    db.query("assign olga to operations".to_string());
+    db.query("assign lars to logistics".to_string());
+    db.query("assign sven to shipping".to_string());
+
+    match db.query("show department tree".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![
+                    ("Operations", " "),
+                    ("  Logistics", "Operations"),
+                    ("    Shipping", "Logistics"),
+                ],
+                table
+                    .data
+                    .iter()
+                    .map(|row| (
+                        row.get("Department").unwrap().as_str().unwrap(),
+                        row.get("Parent").unwrap().as_str().unwrap_or(" ")
+                    ))
+                    .collect::<Vec<(&str, &str)>>()
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("list employees under logistics".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![("Logistics", "Lars"), ("Shipping", "Sven")],
+                table
+                    .data
+                    .iter()
+                    .map(|row| (
+                        row.get("Department").unwrap().as_str().unwrap(),
+                        row.get("Employee").unwrap().as_str().unwrap()
+                    ))
+                    .collect::<Vec<(&str, &str)>>()
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("move operations under shipping".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Query conflict: Department \"operations\" cannot be placed under itself or its own sub-department \"shipping\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("dissolve logistics".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Dissolved \"Logistics\" department; moved its sub-departments \"Shipping\" under \"Operations\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("move shipping to top".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!("Moved \"Shipping\" department to the top level", message);
+        }
+        _ => panic!(),
+    }
+
+    db.query("form customs under shipping".to_string());
+    db.query("form receiving".to_string());
+    match db.query("merge shipping into receiving".to_string()) {
+        QueryResponse::Composite(parts) => assert_eq!(
+            vec![
+                ResponsePart::Message(
+                    "Moved sub-departments \"Customs\" under \"Receiving\"".to_string()
+                ),
+                ResponsePart::Message("Dissolved \"Shipping\" department".to_string()),
+            ],
+            parts[1..]
+        ),
+        _ => panic!(),
+    }
+    match db.query("merge receiving into receiving".to_string()) {
+        QueryResponse::Message(message) => assert_eq!(
+            "ERROR: Query conflict: Cannot merge department into itself",
+            message
+        ),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_track_reporting_lines() {
+    let mut db = Database::new();
+
+    db.query("form office".to_string());
+    db.query("form annex".to_string());
+    db.query("assign michael scott to office".to_string());
+    db.query("assign dwight schrute to office".to_string());
+    db.query("assign jim halpert to office".to_string());
+    db.query("assign andy bernard to annex".to_string());
+
+    match db.query("set manager of dwight schrute to michael scott".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Set manager of \"Dwight Schrute\" in Office to \"Michael Scott\" in Office",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    db.query("set manager of jim halpert to dwight schrute".to_string());
+    db.query("set manager of andy bernard in annex to dwight schrute in office".to_string());
+
+    match db.query("set manager of michael scott to jim halpert".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Query conflict: Employee \"Michael Scott\" cannot report to \"Jim Halpert\": reporting lines cannot form a cycle",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("list all reports of michael scott".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![
+                    ("Dwight Schrute", "Michael Scott"),
+                    ("Andy Bernard", "Dwight Schrute"),
+                    ("Jim Halpert", "Dwight Schrute"),
+                ],
+                table
+                    .data
+                    .iter()
+                    .map(|row| (
+                        row.get("Employee").unwrap().as_str().unwrap(),
+                        row.get("Manager").unwrap().as_str().unwrap()
+                    ))
+                    .collect::<Vec<(&str, &str)>>()
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("show chain of command for jim halpert".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec!["Jim Halpert", "Dwight Schrute", "Michael Scott"],
+                table
+                    .data
+                    .iter()
+                    .map(|row| row.get("Employee").unwrap().as_str().unwrap())
+                    .collect::<Vec<&str>>()
+            );
+        }
+        _ => panic!(),
+    }
+
+    db.query("transfer dwight schrute from office to annex".to_string());
+    match db.query("list reports of dwight schrute".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(2, table.data.len()),
+        _ => panic!(),
+    }
+
+    match db.query("pull dwight schrute from annex".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Pulled employee \"dwight schrute\" from department \"annex\"; reassigned reports \"Andy Bernard\", \"Jim Halpert\" to \"Michael Scott\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("pull michael scott from office".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Pulled employee \"michael scott\" from department \"office\"; WARNING: \"Andy Bernard\", \"Jim Halpert\" no longer have a manager",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_describe_departments() {
+    let mut db = Database::new();
+
+    db.query("form vault".to_string());
+    db.query("assign scrooge to vault".to_string());
+    db.query("assign launchpad to vault".to_string());
+
+    match db.query("set head of vault to magica".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Query target not found: Employee \"magica\" does not exist",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("set head of vault to scrooge".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!("Set head of \"Vault\" department to \"Scrooge\"", message);
+        }
+        _ => panic!(),
+    }
+    db.query("set cost center of vault to CC-0001".to_string());
+    db.query("set location of vault to Killmotor Hill".to_string());
+    db.query("set created of vault to 1947-12-01".to_string());
+
+    match db.query("show departments detailed".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![
+                    "Department",
+                    "Head",
+                    "Cost Center",
+                    "Location",
+                    "Description",
+                    "Created",
+                    "Headcount"
+                ],
+                table.headers
+            );
+            let row = &table.data[0];
+            assert_eq!("Scrooge", row.get("Head").unwrap());
+            assert_eq!("CC-0001", row.get("Cost Center").unwrap());
+            assert_eq!("Killmotor Hill", row.get("Location").unwrap());
+            assert_eq!(Some(&Value::Null), row.get("Description"));
+            assert_eq!(
+                Some(&Value::Date(Date::new(1947, 12, 1).unwrap())),
+                row.get("Created")
+            );
+            assert_eq!(Some(&Value::Integer(2)), row.get("Headcount"));
+            assert_eq!(ColumnType::Integer, table.types[6]);
+        }
+        _ => panic!(),
+    }
+
+    match db.query("clear location of vault".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!("Cleared location of \"Vault\" department", message);
+        }
+        _ => panic!(),
+    }
+
+    match db.query("pull scrooge from vault".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Pulled employee \"scrooge\" from department \"vault\"; \"Scrooge\" is no longer head of the Vault department",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_rename_departments_and_employees() {
+    let mut db = Database::new();
+
+    db.query("form acounting".to_string());
+    db.query("form payroll under acounting".to_string());
+    db.query("assign jon to acounting".to_string());
+    db.query("assign jane to payroll".to_string());
+    db.query("set head of acounting to jon".to_string());
+    db.query("set manager of jane to jon".to_string());
+
+    match db.query("rename department acounting to payroll".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Query conflict: Department \"payroll\" already exists",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("rename department acounting to accounting".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Renamed \"Acounting\" department to \"Accounting\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("rename employee jon in accounting to john".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Renamed employee \"Jon\" in Accounting to \"John\"",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+
+    match db.query("show departments detailed".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!("Accounting", table.data[0].get("Department").unwrap());
+            assert_eq!("John", table.data[0].get("Head").unwrap());
+        }
+        _ => panic!(),
+    }
+    match db.query("show chain of command for jane".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![("Jane", "Payroll"), ("John", "Accounting")],
+                table
+                    .data
+                    .iter()
+                    .map(|row| (
+                        row.get("Employee").unwrap().as_str().unwrap(),
+                        row.get("Department").unwrap().as_str().unwrap()
+                    ))
+                    .collect::<Vec<(&str, &str)>>()
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("show department tree".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!("Accounting", table.data[1].get("Parent").unwrap());
+        }
+        _ => panic!(),
+    }
+
+    // Only the department that exists now is renamed in the history, not an earlier one of
+    // the same name
+    db.set_date(Date::new(2026, 1, 5));
+    db.query("form legal".to_string());
+    db.query("assign lee to legal".to_string());
+    db.set_date(Date::new(2026, 2, 2));
+    db.query("dissolve legal".to_string());
+    db.query("form legal".to_string());
+    db.query("assign lou to legal".to_string());
+    db.set_date(None);
+    db.query("rename department legal to counsel".to_string());
+    let employees = |db: &mut Database, query: &str| match db.query(query.to_string()) {
+        QueryResponse::Table(table) => table
+            .data
+            .iter()
+            .map(|row| row["Employee"].as_str().unwrap().to_string())
+            .collect::<Vec<String>>(),
+        response => panic!("{:?}", response),
+    };
+    assert_eq!(
+        vec!["Lee"],
+        employees(&mut db, "list employees in legal as of 2026-01-10")
+    );
+    assert_eq!(
+        vec!["Lou"],
+        employees(&mut db, "list employees in counsel as of 2026-02-02")
+    );
+    assert!(employees(&mut db, "list employees in counsel as of 2026-01-10").is_empty());
+}
+
+#[test]
+fn user_can_transfer_and_merge_in_bulk() {
+    let mut db = Database::new();
+
+    db.query("form sales".to_string());
+    db.query("form marketing".to_string());
+    db.query("form support".to_string());
+    db.query("assign ann to sales".to_string());
+    db.query("assign bob to sales".to_string());
+    db.query("assign bob to marketing".to_string());
+    db.query("assign cid to support".to_string());
+
+    match db.query("transfer all from sales to marketing".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Query conflict: Employees \"Bob\" already exist in department \"Marketing\"; no employees were transferred",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("transfer all from sales to marketing on conflict rename".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec!["Transferred", "Transferred as \"Bob 2\""],
+                table
+                    .data
+                    .iter()
+                    .map(|row| row.get("Result").unwrap().as_str().unwrap())
+                    .collect::<Vec<&str>>()
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("transfer employees where name starts with bob to support".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(2, table.data.len());
+            assert_eq!("Bob 2", table.data[1].get("Employee").unwrap());
+        }
+        _ => panic!(),
+    }
+    match db.query("merge department support into marketing on conflict skip".to_string()) {
+        QueryResponse::Composite(parts) => {
+            match &parts[0] {
+                ResponsePart::Table(table) => {
+                    assert_eq!(
+                        "Merging the Support Department into the Marketing Department",
+                        table.title
+                    );
+                    assert_eq!(3, table.data.len());
+                    assert!(table
+                        .data
+                        .iter()
+                        .all(|row| row.get("Result").unwrap() == "Transferred"));
+                }
+                _ => panic!(),
+            }
+            assert_eq!(
+                vec![ResponsePart::Message(
+                    "Dissolved \"Support\" department".to_string()
+                )],
+                parts[1..]
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("show departments".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(2, table.data.len());
+        }
+        _ => panic!(),
+    }
+    match db.query("pull all from marketing".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(4, table.data.len());
+        }
+        _ => panic!(),
+    }
+    match db.query("list employees in marketing".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(0, table.data.len());
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_see_warnings_alongside_bulk_results() {
+    let mut db = Database::new();
+    db.query("form sales".to_string());
+    db.query("form marketing".to_string());
+    db.query("assign ann to sales".to_string());
+    db.query("assign bob to sales".to_string());
+    db.query("assign bob to marketing".to_string());
+
+    match db.query("transfer all from sales to marketing on conflict skip".to_string()) {
+        QueryResponse::Composite(parts) => {
+            assert_eq!(2, parts.len());
+            match &parts[0] {
+                ResponsePart::Table(table) => assert_eq!(2, table.data.len()),
+                _ => panic!(),
+            }
+            assert_eq!(
+                ResponsePart::Warning(
+                    "1 of 2 employees were not transferred, as their names are already taken in department \"Marketing\"".to_string()
+                ),
+                parts[1]
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query_rows("merge department sales into marketing on conflict skip".to_string()) {
+        QueryResponse::Composite(parts) => {
+            match &parts[0] {
+                ResponsePart::Rows(rows) => assert_eq!(1, rows.len()),
+                _ => panic!(),
+            }
+            assert_eq!(
+                vec![
+                    ResponsePart::Message("Dissolved \"Sales\" department".to_string()),
+                    ResponsePart::Warning(
+                        "1 of 1 employees were pulled, as their names are already taken in department \"Marketing\"".to_string()
+                    ),
+                ],
+                parts[1..]
+            );
+        }
+        _ => panic!(),
+    }
+
+    db.query("form board".to_string());
+    db.query("assign carl to board".to_string());
+    db.query("assign dee to marketing".to_string());
+    db.query("set manager of dee to carl".to_string());
+    match db.query("pull all from board".to_string()) {
+        QueryResponse::Composite(parts) => {
+            match &parts[0] {
+                ResponsePart::Table(table) => assert_eq!(
+                    "Pulling all Employees from the Board Department",
+                    table.title
+                ),
+                _ => panic!(),
+            }
+            assert_eq!(
+                vec![ResponsePart::Warning(
+                    "\"Dee\" no longer have a manager".to_string()
+                )],
+                parts[1..]
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_run_scripts() {
+    use std::fs;
+
+    let mut db = Database::new();
+    let file = std::env::temp_dir().join(format!("employees-script-{}.sql", std::process::id()));
+    let file_name = file.to_str().unwrap();
+    fs::write(
+        &file,
+        "# Set up sales\nform sales;\nassign bob to sales; -- first hire\n\nassign ann to support;\nlist employees in sales\n",
+    )
+    .unwrap();
+
+    let response = db.query(format!("run {}", file_name));
+    match response {
+        QueryResponse::Composite(parts) => {
+            assert_eq!(5, parts.len());
+            assert_eq!(
+                ResponsePart::Message(
+                    "ERROR: Line 5: Query target not found: Department \"support\" not found"
+                        .to_string()
+                ),
+                parts[2]
+            );
+            match &parts[3] {
+                ResponsePart::Table(table) => assert_eq!(1, table.data.len()),
+                _ => panic!(),
+            }
+            assert_eq!(
+                ResponsePart::Message(format!(
+                    "Ran 4 of 4 statements from \"{}\" with 1 errors",
+                    file_name
+                )),
+                parts[4]
+            );
+        }
+        _ => panic!(),
+    }
+
+    let response = db.query(format!("run {} on error stop", file_name));
+    match response {
+        QueryResponse::Composite(parts) => {
+            assert_eq!(
+                vec![
+                    ResponsePart::Message(
+                        "ERROR: Line 2: Query conflict: Department \"sales\" already exists"
+                            .to_string()
+                    ),
+                    ResponsePart::Warning(
+                        "Stopped at the error on line 2; 3 statements were not run".to_string()
+                    ),
+                    ResponsePart::Message(format!(
+                        "Ran 1 of 4 statements from \"{}\" with 1 errors",
+                        file_name
+                    )),
+                ],
+                parts
+            );
+        }
+        _ => panic!(),
+    }
+
+    db.query("grant admin to root".to_string());
+    db.login("root");
+    db.query("grant editor to lead".to_string());
+    db.login("lead");
+    let response = db.query(format!("run {}", file_name));
+    fs::remove_file(&file).unwrap();
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Permission denied: User \"lead\" requires the Admin role".to_string()
+        ),
+        response
+    );
+}
+
+#[test]
+fn user_can_find_employees() {
+    let mut db = Database::new();
+
+    db.query("form sales".to_string());
+    db.query("form support".to_string());
+    db.query("assign ann lee to sales".to_string());
+    db.query("assign ann lee to support".to_string());
+
+    let departments_of = |response: QueryResponse| match response {
+        QueryResponse::Table(table) => table
+            .data
+            .iter()
+            .map(|row| row.get("Department").unwrap().as_str().unwrap().to_owned())
+            .collect::<Vec<String>>(),
+        _ => panic!(),
+    };
+
+    assert_eq!(
+        vec!["Sales", "Support"],
+        departments_of(db.query("find ann lee".to_string()))
+    );
+
+    db.query("rename employee ann lee in sales to ann li".to_string());
+    db.query("transfer all from support to sales".to_string());
+    db.query("dissolve support".to_string());
+
+    assert_eq!(
+        vec!["Sales"],
+        departments_of(db.query("where is ANN LEE".to_string()))
+    );
+    assert_eq!(
+        vec!["Sales"],
+        departments_of(db.query("find ann li".to_string()))
+    );
+    assert_eq!(
+        Vec::<String>::new(),
+        departments_of(db.query("where is bob".to_string()))
+    );
+}
+
+#[test]
+fn user_can_search_employees() {
+    let mut db = Database::new();
+
+    db.query("form accounting".to_string());
+    db.query("form logistics".to_string());
+    db.query("assign shyam calderon to accounting".to_string());
+    db.query("assign kingsley calderon to logistics".to_string());
+    db.query("assign zara dupont to logistics".to_string());
+
+    match db.query("search the caldreon in logistics".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![
+                    ("Kingsley Calderon", "Logistics"),
+                    ("Zara Dupont", "Logistics"),
+                    ("Shyam Calderon", "Accounting")
+                ],
+                table
+                    .data
+                    .iter()
+                    .map(|row| (
+                        row.get("Employee").unwrap().as_str().unwrap(),
+                        row.get("Department").unwrap().as_str().unwrap()
+                    ))
+                    .collect::<Vec<(&str, &str)>>()
+            );
+            assert_eq!(Some(&Value::Integer(140)), table.data[0].get("Score"));
+        }
+        _ => panic!(),
+    }
+    match db.query("search nobody".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(0, table.data.len());
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_configure_name_handling() {
+    let mut db = Database::with_names(NameCase::Preserve, Collation::Unicode);
+
+    db.query("form IT".to_string());
+    db.query("assign Pieter van der Berg to it".to_string());
+    db.query("assign Jos\u{e9} O'Neil to IT".to_string());
+
+    match db.query("assign jose\u{301} o'neil to it".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "ERROR: Query conflict: Employee \"jose\u{301} o'neil\" already exists",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+    match db.query("list employees in it".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                "Showing Employees assigned to the IT Department",
+                table.title
+            );
+            assert_eq!(
+                vec!["Jos\u{e9} O'Neil", "Pieter van der Berg"],
+                table
+                    .data
+                    .iter()
+                    .map(|row| row.get("Employee").unwrap().as_str().unwrap())
+                    .collect::<Vec<&str>>()
+            );
+        }
+        _ => panic!(),
+    }
+
+    let mut db = Database::with_names(NameCase::Title, Collation::Uppercase);
+
+    db.query("form it".to_string());
+    match db.query("assign jos\u{e9} to it".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!("Assigned employee \"Jos\u{e9}\" to It department", message);
+        }
+        _ => panic!(),
+    }
+    match db.query("assign jose\u{301} to it".to_string()) {
+        QueryResponse::Message(message) => {
+            assert_eq!(
+                "Assigned employee \"Jose\u{301}\" to It department",
+                message
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_generate_synthetic_organizations() {
+    let generator = Generator {
+        distribution: SizeDistribution::Uniform,
+        attributes: true,
+        ..Generator::new(1, 3, 300)
+    };
+    let mut db = Database::new();
+    db.seed();
+
+    let generated = db.generate(&generator);
+
+    assert_eq!(0, generated.departments);
+    assert_eq!(300, generated.employees + generated.conflicts.len() - 3);
+    assert_eq!(
+        "Department \"Accounting\" already exists; merged generated employees into it",
+        generated.conflicts[0]
+    );
+    let second = db.generate(&generator);
+    assert_eq!(0, second.employees);
+    assert_eq!(303, second.conflicts.len());
+    match db.query("list employees".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(70 + generated.employees, table.data.len()),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_stream_rows() {
+    let mut db = Database::new();
+    db.seed();
+
+    let rows = match db.query_rows("list employees by department".to_string()) {
+        QueryResponse::Rows(rows) => rows,
+        _ => panic!(),
+    };
+    assert_eq!(70, rows.len());
+    let first = rows.iter().next().unwrap();
+    assert_eq!(Some(Value::Text("Accounting")), first.get("Department"));
+    assert_eq!(Some(Value::Text("Anil Mcgregor")), first.get("Employee"));
+    match db.query("list employees by department".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(table, Table::from(rows)),
+        _ => panic!(),
+    }
+
+    match db.query_rows("show departments".to_string()) {
+        QueryResponse::Rows(rows) => {
+            assert_eq!(vec!["Department"], rows.headers());
+            assert_eq!(7, rows.len());
+        }
+        _ => panic!(),
+    }
+    assert_eq!(
+        QueryResponse::Message("Formed \"Legal\" department".to_string()),
+        db.query_rows("form legal".to_string())
+    );
+
+    let mut out = Vec::new();
+    let mut rows = Rows::new(
+        "Showing Employees".to_string(),
+        vec!["Employee".to_string()],
+    );
+    rows.push(&["Jos\u{e9}"]);
+    employees::io::write_rows(&mut out, &rows).unwrap();
+    assert_eq!(
+        "\nShowing Employees\n\n Employee \n----------\n Jos\u{e9}     \n\n",
+        String::from_utf8(out).unwrap()
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn user_can_serialize_databases_and_responses() {
+    let mut db = Database::new();
+    db.seed();
+    db.query("set manager of kirk short to kaci costa".to_string());
+    db.query("set created of design to 2001-02-03".to_string());
+
+    let json = serde_json::to_string(&db).unwrap();
+    let mut restored: Database = serde_json::from_str(&json).unwrap();
+
+    [
+        "list employees by department",
+        "show departments detailed",
+        "show chain of command for kirk short",
+    ]
+    .iter()
+    .for_each(|query| {
+        assert_eq!(
+            db.query(query.to_string()),
+            restored.query(query.to_string())
+        )
+    });
+
+    let response = db.query("show departments detailed".to_string());
+    let value = serde_json::to_value(&response).unwrap();
+    assert_eq!("Showing all Departments in detail", value["table"]["title"]);
+    assert_eq!("integer", value["table"]["types"][6]);
+    assert_eq!(
+        serde_json::json!({ "type": "date", "value": "2001-02-03" }),
+        value["table"]["data"][1]["Created"]
+    );
+    assert_eq!(
+        response,
+        serde_json::from_value::<QueryResponse>(value).unwrap()
+    );
+
+    let rows = db.query_rows("list employees in design".to_string());
+    assert_eq!(
+        serde_json::to_value(db.query("list employees in design".to_string())).unwrap()["table"],
+        serde_json::to_value(&rows).unwrap()["rows"]
+    );
+
+    assert!(serde_json::from_str::<Database>(
+        &json.replace("\"employee\":\"Kaci Costa\"", "\"employee\":\"Nobody\"")
+    )
+    .is_err());
+}
+
+#[cfg(feature = "export")]
+#[test]
+fn user_can_export_the_organization() {
+    use employees::database::ExportFormat;
+    use std::fs::{self, File};
+
+    let mut db = Database::new();
+    db.seed();
+    db.query("set manager of kirk short to kaci costa".to_string());
+    db.query("set manager of atif wells to kaci costa".to_string());
+    let directory = std::env::temp_dir();
+
+    ["json", "csv", "yaml"].iter().for_each(|format| {
+        let file = directory.join(format!("employees-{}.{}", std::process::id(), format));
+        let file_name = file.to_str().unwrap();
+
+        assert_eq!(
+            QueryResponse::Message(format!(
+                "Exported 7 departments to \"{}\" as {}",
+                file_name,
+                format.to_uppercase()
+            )),
+            db.query(format!("export {} to {}", format, file_name))
+        );
+        let mut imported = Database::import(
+            ExportFormat::parse(format).unwrap(),
+            File::open(&file).unwrap(),
+        )
+        .unwrap();
+        fs::remove_file(&file).unwrap();
+        [
+            "list employees by department",
+            "show departments detailed",
+            "list reports of kaci costa",
+        ]
+        .iter()
+        .for_each(|query| {
+            assert_eq!(
+                db.query(query.to_string()),
+                imported.query(query.to_string())
+            )
+        });
+    });
+
+    let mut document = Vec::new();
+    db.export(ExportFormat::Json, Some("accounting"), &mut document)
+        .unwrap();
+    let mut accounting = Database::import(ExportFormat::Json, &document[..]).unwrap();
+    match accounting.query("list employees by department".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(10, table.data.len()),
+        _ => panic!(),
+    }
+    match accounting.query("list reports of kaci costa".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(
+            vec!["Kirk Short"],
+            table
+                .data
+                .iter()
+                .map(|row| row["Employee"].as_str().unwrap())
+                .collect::<Vec<&str>>()
+        ),
+        _ => panic!(),
+    }
+
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Query target not found: Department \"Nowhere\" not found".to_string()
+        ),
+        db.query("export csv of Nowhere to nowhere.csv".to_string())
+    );
+    assert!(db
+        .export(ExportFormat::Csv, Some("Nowhere"), Vec::new())
+        .is_err());
+}
+
+#[cfg(feature = "export")]
+#[test]
+fn user_can_diff_the_organization_against_an_export() {
+    use std::fs;
+
+    let mut db = Database::new();
+    db.seed();
+    let file = std::env::temp_dir().join(format!("employees-diff-{}.yaml", std::process::id()));
+    let file_name = file.to_str().unwrap();
+    db.query(format!("export yaml to {}", file_name));
+    [
+        "form legal",
+        "dissolve logistics",
+        "transfer kirk short from accounting to design",
+        "assign bob to legal",
+    ]
+    .iter()
+    .for_each(|query| {
+        db.query(query.to_string());
+    });
+
+    let response = db.query(format!("diff {}", file_name));
+    fs::remove_file(&file).unwrap();
+    match response {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                format!("Showing Changes since \"{}\"", file_name),
+                table.title
+            );
+            let changes = table
+                .data
+                .iter()
+                .map(|row| {
+                    format!(
+                        "{} {} {} {}",
+                        row["Change"], row["Name"], row["From"], row["To"]
+                    )
+                })
+                .collect::<Vec<String>>();
+            assert_eq!("Department removed Logistics  ", changes[0]);
+            assert_eq!("Department added Legal  ", changes[1]);
+            assert!(changes.contains(&String::from("Employee added Bob  Legal")));
+            assert!(changes.contains(&String::from(
+                "Employee transferred Kirk Short Accounting Design"
+            )));
+            assert_eq!(
+                2 + 2 + 10,
+                changes.len(),
+                "Every Logistics employee is removed"
+            );
+        }
+        _ => panic!(),
+    }
+
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Could not tell the format of \"org.txt\" from its extension".to_string()
+        ),
+        db.query("diff org.txt".to_string())
+    );
+
+    db.query("grant admin to root".to_string());
+    db.login("root");
+    db.query("grant viewer to intern".to_string());
+    db.login("intern");
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Permission denied: User \"intern\" requires the Admin role".to_string()
+        ),
+        db.query("diff /etc/passwd.json".to_string())
+    );
+}
+
+#[test]
+fn user_can_chart_the_organization() {
+    let mut db = Database::new();
+    db.seed();
+    db.query("form payroll under accounting".to_string());
+    db.query("assign bob to payroll".to_string());
+    db.query("set manager of bob in payroll to kirk short".to_string());
+
+    match db.query("chart dot".to_string()) {
+        QueryResponse::Message(chart) => {
+            assert!(chart.starts_with("digraph org {"));
+            assert!(chart.contains("[label=\"Payroll\", shape=folder];"));
+            assert_eq!(db.chart(&Chart::new(ChartFormat::Dot)), chart);
+        }
+        _ => panic!(),
+    }
+    match db.query("chart mermaid depth 0 collapse above 5".to_string()) {
+        QueryResponse::Message(chart) => {
+            assert!(chart.starts_with("flowchart TD"));
+            assert!(!chart.contains("Payroll"));
+            assert!(!chart.contains("-.->"));
+            assert!(chart.contains("(\"10 employees\")"));
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_report_headcount() {
+    let mut db = Database::new();
+    db.set_date(Date::new(2026, 1, 1));
+    db.seed();
+    db.set_date(Date::new(2026, 4, 1));
+    db.query("form legal".to_string());
+    db.query("transfer kirk short from accounting to design".to_string());
+    db.set_date(None);
+
+    match db.query("report headcount from 2026-04-01".to_string()) {
+        QueryResponse::Report(report) => {
+            assert_eq!("Headcount Report", report.title);
+            let titles = report
+                .sections
+                .iter()
+                .map(|section| section.title.as_str())
+                .collect::<Vec<&str>>();
+            assert_eq!(
+                vec![
+                    "Headcount by Department",
+                    "Largest and Smallest Departments",
+                    "Empty Departments",
+                    "Movement since 2026-04-01",
+                ],
+                titles
+            );
+            assert_eq!("Legal", report.sections[2].data[0]["Department"]);
+            let movement = &report.sections[3].data;
+            assert_eq!(2, movement.len());
+            assert_eq!("Accounting", movement[0]["Department"]);
+            assert_eq!(Value::Integer(-1), movement[0]["Net Change"]);
+            assert_eq!(Value::Integer(1), movement[1]["Transferred In"]);
+            #[cfg(feature = "serde")]
+            assert_eq!(
+                "Headcount by Department",
+                serde_json::to_value(&report).unwrap()["sections"][0]["title"]
+            );
+        }
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_query_the_organization_as_of_a_past_date() {
+    let mut db = Database::new();
+    db.query("form sales".to_string());
+    db.query("form design".to_string());
+    db.set_date(Date::new(2026, 1, 5));
+    db.query("assign bob to sales".to_string());
+    db.query("assign alice to sales".to_string());
+    db.set_date(Date::new(2026, 2, 10));
+    db.query("transfer bob from sales to design".to_string());
+    db.set_date(Date::new(2026, 3, 2));
+    db.query("pull alice from sales".to_string());
+    db.set_date(None);
+
+    let employees = |db: &mut Database, query: &str| match db.query(query.to_string()) {
+        QueryResponse::Table(table) => table
+            .data
+            .iter()
+            .map(|row| row["Employee"].as_str().unwrap().to_string())
+            .collect::<Vec<String>>(),
+        response => panic!("{:?}", response),
+    };
+    assert_eq!(
+        vec!["Alice", "Bob"],
+        employees(&mut db, "list employees in sales as of 2026-02-09")
+    );
+    assert_eq!(
+        vec!["Alice"],
+        employees(&mut db, "list employees in Sales as of 2026-02-10")
+    );
+    assert!(employees(&mut db, "list employees in sales as of 2026-03-02").is_empty());
+    assert!(employees(&mut db, "list employees in sales as of 2026-01-04").is_empty());
+    assert!(employees(&mut db, "list employees as of 2026-03-02") == vec!["Bob"]);
+    match db.query("list employees by department as of 2026-02-10".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                "Showing Employees grouped by Department as of 2026-02-10",
+                table.title
+            );
+            assert_eq!("Design", table.data[0]["Department"]);
+            assert_eq!("Sales", table.data[1]["Department"]);
+        }
+        _ => panic!(),
+    }
+
+    match db.query("show timeline of BOB".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(
+                vec![
+                    ColumnType::Text,
+                    ColumnType::Text,
+                    ColumnType::Date,
+                    ColumnType::Date
+                ],
+                table.types
+            );
+            assert_eq!(2, table.data.len());
+            assert_eq!("Sales", table.data[0]["Department"]);
+            assert_eq!(
+                Some(&Value::Date(Date::new(2026, 2, 10).unwrap())),
+                table.data[0].get("To")
+            );
+            assert_eq!("Design", table.data[1]["Department"]);
+            assert_eq!(Some(&Value::Null), table.data[1].get("To"));
+        }
+        _ => panic!(),
+    }
+
+    db.query("dissolve sales".to_string());
+    assert_eq!(
+        vec!["Alice", "Bob"],
+        employees(&mut db, "list employees in sales as of 2026-02-01")
+    );
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Query target not found: Department \"Legal\" not found".to_string()
+        ),
+        db.query("list employees in Legal as of 2026-02-01".to_string())
+    );
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Invalid command syntax: \"2026-02-30\" is not a YYYY-MM-DD date".to_string()
+        ),
+        db.query("list employees as of 2026-02-30".to_string())
+    );
+
+    db.query("form legal".to_string());
+    db.set_date(Date::new(2026, 4, 1));
+    db.query("assign bob to legal".to_string());
+    db.set_date(Date::new(2026, 3, 20));
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Query conflict: Employee \"Bob\" cannot leave department \"Legal\" on 2026-03-20, before joining it on 2026-04-01".to_string()
+        ),
+        db.query("pull bob from legal".to_string())
+    );
+    db.set_date(None);
+    match db.query("show timeline of bob in legal".to_string()) {
+        QueryResponse::Table(table) => {
+            assert_eq!(1, table.data.len());
+            assert_eq!("Legal", table.data[0]["Department"]);
+        }
+        _ => panic!(),
+    }
+    match db.query("show timeline of bob".to_string()) {
+        QueryResponse::Table(table) => assert_eq!(3, table.data.len()),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn user_can_prepare_queries() {
+    let mut db = Database::new();
+    db.query("form sales".to_string());
+    db.query("form design".to_string());
+
+    let mut assign = db.prepare("assign ? to ?").unwrap();
+    assert_eq!(2, assign.parameters());
+    ["Bob", "Alice to Design", "Carol"].iter().for_each(|name| {
+        assign.execute(&[*name, "sales"]);
+    });
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Invalid command syntax: Query expects 2 parameters but 3 were given"
+                .to_string()
+        ),
+        assign.execute(&["Dan", "sales", "design"])
+    );
+
+    match db
+        .prepare("list employees in $1")
+        .unwrap()
+        .execute(&["sales"])
+    {
+        QueryResponse::Table(table) => assert_eq!(
+            vec!["Alice To Design", "Bob", "Carol"],
+            table
+                .data
+                .iter()
+                .map(|row| row["Employee"].to_string())
+                .collect::<Vec<String>>()
+        ),
+        _ => panic!(),
+    }
+    assert_eq!(
+        Some(
+            "Invalid command \"frobnicate\". Please check your spelling, or type \"Help\" for the list of available commands"
+                .to_string()
+        ),
+        db.prepare("frobnicate ?").err()
+    );
+}
+
+#[test]
+fn user_can_define_aliases_and_macros() {
+    let mut db = Database::new();
+    db.query("form sales".to_string());
+
+    assert_eq!(
+        QueryResponse::Message("Defined alias \"hire\" taking 2 arguments".to_string()),
+        db.query("define alias hire as assign $1 to $2".to_string())
+    );
+    assert_eq!(
+        QueryResponse::Message(
+            "Assigned employee \"Ann To Sales\" to Sales department".to_string()
+        ),
+        db.query("hire Ann to Sales, sales".to_string())
+    );
+
+    db.query(
+        "define macro onboard as\n  form $2;\n  hire $1, $2;\n  set head of $2 to $1;\nend"
+            .to_string(),
+    );
+    match db.query("onboard Bob, Legal".to_string()) {
+        QueryResponse::Composite(parts) => assert_eq!(
+            vec![
+                ResponsePart::Message("Formed \"Legal\" department".to_string()),
+                ResponsePart::Message("Assigned employee \"Bob\" to Legal department".to_string()),
+                ResponsePart::Message("Set head of \"Legal\" department to \"Bob\"".to_string()),
+            ],
+            parts
+        ),
+        _ => panic!(),
+    }
+    match db.query("onboard Carol, Legal".to_string()) {
+        QueryResponse::Composite(parts) => {
+            assert_eq!(2, parts.len());
+            assert_eq!(
+                ResponsePart::Warning(
+                    "Stopped at the error on line 1; 2 statements were not run".to_string()
+                ),
+                parts[1]
+            );
+        }
+        _ => panic!(),
+    }
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Invalid command syntax: Macro \"onboard\" expects 2 arguments separated by commas, but 1 were given"
+                .to_string()
+        ),
+        db.query("onboard Dan".to_string())
+    );
+
+    let file = std::env::temp_dir().join(format!("employees-macro-{}.sql", std::process::id()));
+    let file_name = file.to_str().unwrap();
+    std::fs::write(&file, "onboard Carol, Legal;\nform Support;\n").unwrap();
+    let response = db.query(format!("run {} on error stop", file_name));
+    std::fs::remove_file(&file).unwrap();
+    match response {
+        QueryResponse::Composite(parts) => assert_eq!(
+            vec![
+                ResponsePart::Warning(
+                    "Stopped at the error on line 1; 1 statements were not run".to_string()
+                ),
+                ResponsePart::Message(format!(
+                    "Ran 1 of 2 statements from \"{}\" with 1 errors",
+                    file_name
+                )),
+            ],
+            parts[parts.len() - 2..]
+        ),
+        _ => panic!(),
+    }
+
+    db.query("define macro again as form Design; again".to_string());
+    assert_eq!(
+        QueryResponse::Composite(vec![
+            ResponsePart::Message("Formed \"Design\" department".to_string()),
+            ResponsePart::Message(
+                "ERROR: Line 2: Invalid command syntax: Macro \"again\" cannot run itself"
+                    .to_string()
+            ),
+        ]),
+        db.query("again".to_string())
+    );
+    db.query("undefine again".to_string());
+
+    match db.query("help".to_string()) {
+        QueryResponse::Message(message) => assert!(message.ends_with(
+            "User-defined Operations:\
+            \n- \"hire $1, $2\" - alias for \"assign $1 to $2\"\
+            \n- \"onboard $1, $2\" - macro for \"form $2; hire $1, $2; set head of $2 to $1\"\n"
+        )),
+        _ => panic!(),
+    }
+    assert_eq!(
+        QueryResponse::Message("Removed alias \"hire\"".to_string()),
+        db.query("undefine HIRE".to_string())
+    );
+    assert_eq!(
+        QueryResponse::Message(
+            "ERROR: Invalid command \"hire\". Please check your spelling, or type \"Help\" for the list of available commands".to_string()
+        ),
+        db.query("hire Eve, sales".to_string())
+    );
+}